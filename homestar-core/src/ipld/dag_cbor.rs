@@ -25,6 +25,25 @@ where
         let hash = Code::Sha3_256.digest(&bytes);
         Ok(Cid::new_v1(DAG_CBOR, hash))
     }
+
+    /// Serialize an owned `Self` type to [DagCbor] bytes.
+    ///
+    /// [DagCbor]: DagCborCodec
+    fn to_cbor(self) -> Result<Vec<u8>, Error<Unit>> {
+        let ipld: Ipld = self.into();
+        Ok(DagCborCodec.encode(&ipld)?)
+    }
+
+    /// Deserialize `Self` type from [DagCbor] bytes.
+    ///
+    /// [DagCbor]: DagCborCodec
+    fn from_cbor(data: &[u8]) -> Result<Self, Error<Unit>>
+    where
+        Self: TryFrom<Ipld>,
+    {
+        let ipld: Ipld = DagCborCodec.decode(data)?;
+        Self::try_from(ipld.clone()).map_err(|_err| Error::<Unit>::UnexpectedIpldType(ipld))
+    }
 }
 
 /// Trait for [DagCbor]-related encode/decode for references.
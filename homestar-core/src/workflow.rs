@@ -157,6 +157,7 @@ mod test {
         workflow::{config::Resources, instruction::RunInstruction, prf::UcanPrf},
         Unit,
     };
+    use libipld::{cbor::DagCborCodec, prelude::Codec};
 
     #[test]
     fn workflow_to_json_roundtrip() {
@@ -235,4 +236,29 @@ mod test {
 
         assert_eq!(workflow, de);
     }
+
+    #[test]
+    fn cbor_roundtrip() {
+        let config = Resources::default();
+        let (instruction1, instruction2, _) =
+            test_utils::workflow::related_wasm_instructions::<Unit>();
+
+        let task1 = Task::new(
+            RunInstruction::Expanded(instruction1),
+            config.clone().into(),
+            UcanPrf::default(),
+        );
+        let task2 = Task::new(
+            RunInstruction::Expanded(instruction2),
+            config.into(),
+            UcanPrf::default(),
+        );
+
+        let workflow = Workflow::new(vec![task1, task2]);
+        let bytes = workflow.clone().to_cbor().unwrap();
+        let decoded = Workflow::from_cbor(&bytes).unwrap();
+
+        assert_eq!(workflow, decoded);
+        assert!(Workflow::<Unit>::from_cbor(&DagCborCodec.encode(&Ipld::Null).unwrap()).is_err());
+    }
 }
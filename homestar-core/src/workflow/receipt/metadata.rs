@@ -19,3 +19,21 @@ pub const WORKFLOW_KEY: &str = "workflow";
 ///
 /// [Cid]: libipld::Cid
 pub const WORKFLOW_NAME_KEY: &str = "name";
+
+/// Metadata key for the time a task's execution started, in milliseconds
+/// since the Unix epoch.
+pub const STARTED_AT_KEY: &str = "started_at";
+
+/// Metadata key for the time a task's execution finished, in milliseconds
+/// since the Unix epoch.
+pub const FINISHED_AT_KEY: &str = "finished_at";
+
+/// Metadata key for the wall-clock duration of a task's execution, in
+/// milliseconds.
+pub const DURATION_KEY: &str = "duration";
+
+/// Metadata key for the fuel consumed by a task's execution.
+pub const FUEL_KEY: &str = "fuel";
+
+/// Metadata key for the peak memory, in bytes, used by a task's execution.
+pub const MEMORY_KEY: &str = "memory";
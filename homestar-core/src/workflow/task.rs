@@ -70,6 +70,11 @@ where
         &self.meta
     }
 
+    /// Return a reference to the [UcanPrf] proofs authorizing the [Task].
+    pub fn prf(&self) -> &UcanPrf {
+        &self.prf
+    }
+
     /// Turn [Task] into owned [RunInstruction].
     pub fn into_instruction(self) -> RunInstruction<'a, T> {
         self.run
@@ -0,0 +1,4 @@
+CREATE TABLE instructions (
+  cid          TEXT NOT NULL PRIMARY KEY,
+  instruction  BLOB NOT NULL
+);
@@ -0,0 +1,4 @@
+CREATE TABLE proofs (
+  cid    TEXT NOT NULL PRIMARY KEY,
+  token  TEXT NOT NULL
+);
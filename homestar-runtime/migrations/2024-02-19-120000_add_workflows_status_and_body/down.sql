@@ -0,0 +1,6 @@
+DROP INDEX status_index;
+
+ALTER TABLE workflows DROP COLUMN body;
+ALTER TABLE workflows DROP COLUMN finished_at;
+ALTER TABLE workflows DROP COLUMN last_error;
+ALTER TABLE workflows DROP COLUMN status;
@@ -0,0 +1,9 @@
+ALTER TABLE workflows ADD COLUMN status TEXT NOT NULL DEFAULT 'queued';
+ALTER TABLE workflows ADD COLUMN last_error TEXT;
+ALTER TABLE workflows ADD COLUMN finished_at TIMESTAMP;
+ALTER TABLE workflows ADD COLUMN body BLOB;
+
+UPDATE workflows SET status = 'completed', finished_at = completed_at
+  WHERE completed_at IS NOT NULL;
+
+CREATE INDEX status_index ON workflows (status);
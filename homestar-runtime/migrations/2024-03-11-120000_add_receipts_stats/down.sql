@@ -0,0 +1,8 @@
+DROP INDEX duration_index;
+DROP INDEX started_at_index;
+
+ALTER TABLE receipts DROP COLUMN memory;
+ALTER TABLE receipts DROP COLUMN fuel;
+ALTER TABLE receipts DROP COLUMN duration;
+ALTER TABLE receipts DROP COLUMN finished_at;
+ALTER TABLE receipts DROP COLUMN started_at;
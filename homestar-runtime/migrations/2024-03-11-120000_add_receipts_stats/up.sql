@@ -0,0 +1,8 @@
+ALTER TABLE receipts ADD COLUMN started_at TIMESTAMP;
+ALTER TABLE receipts ADD COLUMN finished_at TIMESTAMP;
+ALTER TABLE receipts ADD COLUMN duration BIGINT;
+ALTER TABLE receipts ADD COLUMN fuel BIGINT;
+ALTER TABLE receipts ADD COLUMN memory BIGINT;
+
+CREATE INDEX started_at_index ON receipts (started_at);
+CREATE INDEX duration_index ON receipts (duration);
@@ -0,0 +1,110 @@
+//! Embedded, filesystem-backed blockstore, keyed by [Cid].
+//!
+//! Blocks are laid out like a [flatfs] datastore, sharded into directories
+//! by the next-to-last two characters of their (v1) [Cid], and are verified
+//! against their [Cid] before being written.
+//!
+//! [flatfs]: https://github.com/ipfs/go-ds-flatfs
+
+use crate::{settings, tasks::unixfs};
+use anyhow::Result;
+use libipld::Cid;
+use std::{io, path::PathBuf, sync::Arc};
+use tokio::fs;
+
+/// Filesystem-backed store of content-addressed blocks.
+#[derive(Clone, Debug)]
+pub(crate) struct Blockstore {
+    path: Arc<PathBuf>,
+}
+
+impl Blockstore {
+    /// Create a [Blockstore] rooted at the configured path.
+    ///
+    /// The directory is only created once a block is written.
+    pub(crate) fn new(settings: &settings::Blockstore) -> Self {
+        Self {
+            path: Arc::new(settings.path.clone()),
+        }
+    }
+
+    /// Get a block by [Cid], if it's stored.
+    pub(crate) async fn get(&self, cid: Cid) -> Result<Option<Vec<u8>>> {
+        match fs::read(self.block_path(cid)).await {
+            Ok(block) => Ok(Some(block)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Check whether a block is stored for a [Cid].
+    #[allow(dead_code)]
+    pub(crate) async fn has(&self, cid: Cid) -> bool {
+        fs::try_exists(self.block_path(cid)).await.unwrap_or(false)
+    }
+
+    /// Verify and store a block under its [Cid].
+    ///
+    /// Writes go to a temporary file first, so readers never see a partial
+    /// block.
+    pub(crate) async fn put(&self, cid: Cid, block: &[u8]) -> Result<()> {
+        unixfs::verify(cid, block)?;
+
+        let path = self.block_path(cid);
+        if fs::try_exists(&path).await.unwrap_or(false) {
+            return Ok(());
+        }
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).await?;
+        }
+
+        let tmp = path.with_extension(format!("tmp{}", rand::random::<u32>()));
+        fs::write(&tmp, block).await?;
+        if let Err(err) = fs::rename(&tmp, &path).await {
+            let _ = fs::remove_file(&tmp).await;
+            return Err(err.into());
+        }
+
+        Ok(())
+    }
+
+    fn block_path(&self, cid: Cid) -> PathBuf {
+        // Key by v1 [Cid]s, so that v0 and v1 [Cid]s of the same block
+        // share an entry, and keys are case-insensitive.
+        let key = Cid::new_v1(cid.codec(), *cid.hash()).to_string();
+        let shard = &key[key.len() - 3..key.len() - 1];
+        self.path.join(shard).join(format!("{key}.data"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use libipld::multihash::{Code, MultihashDigest};
+
+    const RAW: u64 = 0x55;
+
+    #[tokio::test]
+    async fn put_and_get_blocks() {
+        let path = std::env::temp_dir().join(format!("homestar_blockstore_{}", std::process::id()));
+        let blockstore = Blockstore::new(&settings::Blockstore { path: path.clone() });
+
+        let block = b"beep boop".to_vec();
+        let cid = Cid::new_v1(RAW, Code::Sha2_256.digest(&block));
+        assert!(!blockstore.has(cid).await);
+        assert_eq!(blockstore.get(cid).await.unwrap(), None);
+
+        blockstore.put(cid, &block).await.unwrap();
+        assert!(blockstore.has(cid).await);
+        assert_eq!(blockstore.get(cid).await.unwrap(), Some(block.clone()));
+        // Re-putting an existing block is a no-op.
+        blockstore.put(cid, &block).await.unwrap();
+
+        let other = Cid::new_v1(RAW, Code::Sha2_256.digest(b"bop"));
+        assert!(blockstore.put(other, &block).await.is_err());
+        assert!(!blockstore.has(other).await);
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}
@@ -4,7 +4,7 @@ use crate::{
     network::rpc::Client,
     runner::{file, response},
 };
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use clap::{Args, Parser, Subcommand};
 use serde::{Deserialize, Serialize};
 use std::{
@@ -133,6 +133,8 @@ pub enum Command {
         /// IPVM-configured workflow file to run.
         /// Supported:
         ///   - JSON (.json).
+        ///   - DAG-CBOR (.cbor).
+        ///   - CARv1 archive (.car).
         #[arg(
             short='w',
             long = "workflow",
@@ -141,10 +143,89 @@ pub enum Command {
             value_parser = clap::value_parser!(file::ReadWorkflow),
             help = r#"IPVM-configured workflow file to run.
 Supported:
-  - JSON (.json)"#
+  - JSON (.json)
+  - DAG-CBOR (.cbor)
+  - CARv1 archive (.car)"#
         )]
         workflow: file::ReadWorkflow,
     },
+    /// Query workflows known to the Homestar runtime.
+    #[clap(subcommand)]
+    Workflow(WorkflowCommand),
+    /// Cancel a running or queued workflow on the Homestar runtime.
+    Cancel {
+        /// RPC host / port arguments.
+        #[clap(flatten)]
+        args: RpcArgs,
+        /// Workflow [Cid] or local name.
+        ///
+        /// [Cid]: libipld::Cid
+        #[arg(
+            value_name = "WORKFLOW",
+            help = "Workflow cid or local name, matching its latest run"
+        )]
+        workflow: String,
+    },
+    /// Pause a running or queued workflow on the Homestar runtime, letting
+    /// its in-flight tasks complete.
+    Pause {
+        /// RPC host / port arguments.
+        #[clap(flatten)]
+        args: RpcArgs,
+        /// Workflow [Cid] or local name.
+        ///
+        /// [Cid]: libipld::Cid
+        #[arg(
+            value_name = "WORKFLOW",
+            help = "Workflow cid or local name, matching its latest run"
+        )]
+        workflow: String,
+    },
+    /// Resume a paused workflow on the Homestar runtime.
+    Resume {
+        /// RPC host / port arguments.
+        #[clap(flatten)]
+        args: RpcArgs,
+        /// Workflow [Cid] or local name.
+        ///
+        /// [Cid]: libipld::Cid
+        #[arg(
+            value_name = "WORKFLOW",
+            help = "Workflow cid or local name, matching its latest run"
+        )]
+        workflow: String,
+    },
+}
+
+/// Workflow query subcommands.
+#[derive(Debug, Subcommand)]
+pub enum WorkflowCommand {
+    /// List workflows known to the Homestar runtime.
+    List {
+        /// RPC host / port arguments.
+        #[clap(flatten)]
+        args: RpcArgs,
+        /// Output JSON rather than a table.
+        #[arg(long = "json", default_value = "false", help = "Output as JSON")]
+        json: bool,
+    },
+    /// Show the status and progress of a workflow.
+    Status {
+        /// RPC host / port arguments.
+        #[clap(flatten)]
+        args: RpcArgs,
+        /// Workflow [Cid] or local name.
+        ///
+        /// [Cid]: libipld::Cid
+        #[arg(
+            value_name = "WORKFLOW",
+            help = "Workflow cid or local name, matching its latest run"
+        )]
+        workflow: String,
+        /// Output JSON rather than a table.
+        #[arg(long = "json", default_value = "false", help = "Output as JSON")]
+        json: bool,
+    },
 }
 
 impl Command {
@@ -154,6 +235,11 @@ impl Command {
             Command::Stop { .. } => "stop",
             Command::Ping { .. } => "ping",
             Command::Run { .. } => "run",
+            Command::Workflow(WorkflowCommand::List { .. }) => "workflow list",
+            Command::Workflow(WorkflowCommand::Status { .. }) => "workflow status",
+            Command::Cancel { .. } => "cancel",
+            Command::Pause { .. } => "pause",
+            Command::Resume { .. } => "resume",
         }
     }
 
@@ -187,19 +273,88 @@ impl Command {
                 workflow: workflow_file,
             } => {
                 let response = rt.block_on(async {
+                    // Read the workflow locally, as the runner may not share
+                    // this filesystem.
+                    let workflow = workflow_file.read().await.with_context(|| {
+                        format!("failed to validate/parse workflow @ path: {workflow_file}")
+                    })?;
                     let client = args.client().await?;
-                    let response = client.run(name.map(|n| n.into()), workflow_file).await??;
+                    let response = client.run(name.map(|n| n.into()), workflow).await??;
                     Ok::<Box<response::AckWorkflow>, Error>(response)
                 })?;
 
                 response.echo_table()?;
                 Ok(())
             }
+            Command::Workflow(WorkflowCommand::List { args, json }) => {
+                let response = rt.block_on(async {
+                    let client = args.client().await?;
+                    let response = client.list_workflows().await??;
+                    Ok::<response::WorkflowList, Error>(response)
+                })?;
+
+                echo(&response, json)
+            }
+            Command::Workflow(WorkflowCommand::Status {
+                args,
+                workflow,
+                json,
+            }) => {
+                let response = rt.block_on(async {
+                    let client = args.client().await?;
+                    let response = client.workflow_status(workflow.into()).await??;
+                    Ok::<Box<response::WorkflowStatus>, Error>(response)
+                })?;
+
+                echo(response.as_ref(), json)
+            }
+            Command::Cancel { args, workflow } => {
+                let response = rt.block_on(async {
+                    let client = args.client().await?;
+                    let response = client.cancel_workflow(workflow.into()).await??;
+                    Ok::<Box<response::WorkflowStatus>, Error>(response)
+                })?;
+
+                response.echo_table()?;
+                Ok(())
+            }
+            Command::Pause { args, workflow } => {
+                let response = rt.block_on(async {
+                    let client = args.client().await?;
+                    let response = client.pause_workflow(workflow.into()).await??;
+                    Ok::<Box<response::WorkflowStatus>, Error>(response)
+                })?;
+
+                response.echo_table()?;
+                Ok(())
+            }
+            Command::Resume { args, workflow } => {
+                let response = rt.block_on(async {
+                    let client = args.client().await?;
+                    let response = client.resume_workflow(workflow.into()).await??;
+                    Ok::<Box<response::WorkflowStatus>, Error>(response)
+                })?;
+
+                response.echo_table()?;
+                Ok(())
+            }
             _ => Err(anyhow!("Invalid command {}", self.name()).into()),
         }
     }
 }
 
+/// Print a response to the console, as a table or as (pretty) JSON.
+fn echo<T: ConsoleTable + Serialize>(response: &T, json: bool) -> Result<(), Error> {
+    if json {
+        let json = serde_json::to_string_pretty(response).map_err(anyhow::Error::from)?;
+        show::Output::new(json).echo()?;
+    } else {
+        response.echo_table()?;
+    }
+
+    Ok(())
+}
+
 impl RpcArgs {
     async fn client(&self) -> Result<Client, Error> {
         let addr = SocketAddr::new(self.host, self.port);
@@ -2,13 +2,13 @@
 
 use crate::{
     settings,
-    workflow::{self, StoredReceipt},
+    workflow::{self, StoredInstruction, StoredProof, StoredReceipt},
     Receipt,
 };
 use anyhow::Result;
 use byte_unit::{AdjustedByte, Byte, ByteUnit};
+use chrono::{NaiveDateTime, Utc};
 use diesel::{
-    dsl::now,
     prelude::*,
     r2d2::{self, CustomizeConnection, ManageConnection},
     BelongingToDsl, Connection as SingleConnection, RunQueryDsl, SqliteConnection,
@@ -226,6 +226,82 @@ pub trait Database: Send + Sync + Clone {
             .load(conn)
     }
 
+    /// Store an [Instruction], encoded as [StoredInstruction], given a
+    /// connection to the database pool.
+    ///
+    /// On conflicts, do nothing.
+    ///
+    /// [Instruction]: homestar_core::workflow::Instruction
+    fn store_instruction(
+        instruction: StoredInstruction,
+        conn: &mut Connection,
+    ) -> Result<usize, diesel::result::Error> {
+        diesel::insert_into(schema::instructions::table)
+            .values(&instruction)
+            .on_conflict(schema::instructions::cid)
+            .do_nothing()
+            .execute(conn)
+    }
+
+    /// Find a [StoredInstruction] for a given [Instruction] [Cid].
+    ///
+    /// [Instruction]: homestar_core::workflow::Instruction
+    fn find_stored_instruction(
+        cid: Cid,
+        conn: &mut Connection,
+    ) -> Result<StoredInstruction, diesel::result::Error> {
+        schema::instructions::dsl::instructions
+            .filter(schema::instructions::cid.eq(Pointer::new(cid)))
+            .select(StoredInstruction::as_select())
+            .get_result(conn)
+    }
+
+    /// Find [StoredInstruction]s given a set of [Instruction] [Pointer]s.
+    ///
+    /// [Instruction]: homestar_core::workflow::Instruction
+    fn find_stored_instructions(
+        pointers: &Vec<Pointer>,
+        conn: &mut Connection,
+    ) -> Result<Vec<StoredInstruction>, diesel::result::Error> {
+        schema::instructions::dsl::instructions
+            .filter(schema::instructions::cid.eq_any(pointers))
+            .load(conn)
+    }
+
+    /// Store a UCAN proof, encoded as [StoredProof], given a connection to
+    /// the database pool.
+    ///
+    /// On conflicts, do nothing.
+    fn store_proof(
+        proof: StoredProof,
+        conn: &mut Connection,
+    ) -> Result<usize, diesel::result::Error> {
+        diesel::insert_into(schema::proofs::table)
+            .values(&proof)
+            .on_conflict(schema::proofs::cid)
+            .do_nothing()
+            .execute(conn)
+    }
+
+    /// Store UCAN proofs, encoded as [StoredProof]s, given a connection to
+    /// the database pool.
+    fn store_proofs(
+        proofs: Vec<StoredProof>,
+        conn: &mut Connection,
+    ) -> Result<usize, diesel::result::Error> {
+        proofs
+            .into_iter()
+            .try_fold(0, |acc, proof| Ok(acc + Self::store_proof(proof, conn)?))
+    }
+
+    /// Find a [StoredProof] for a given UCAN [Cid].
+    fn find_proof(cid: Cid, conn: &mut Connection) -> Result<StoredProof, diesel::result::Error> {
+        schema::proofs::dsl::proofs
+            .filter(schema::proofs::cid.eq(Pointer::new(cid)))
+            .select(StoredProof::as_select())
+            .get_result(conn)
+    }
+
     /// Store localized workflow cid and information, e.g. number of tasks.
     ///
     /// On conflicts, do nothing.
@@ -296,6 +372,58 @@ pub trait Database: Send + Sync + Clone {
             .get_result(conn)
     }
 
+    /// Select the most recently created workflow given its local name.
+    fn select_workflow_by_name(
+        name: &str,
+        conn: &mut Connection,
+    ) -> Result<workflow::Stored, diesel::result::Error> {
+        schema::workflows::dsl::workflows
+            .filter(schema::workflows::name.eq(name))
+            .order(schema::workflows::created_at.desc())
+            .select(workflow::Stored::as_select())
+            .first(conn)
+    }
+
+    /// Select all stored workflows, most recently created first.
+    fn select_workflows(
+        conn: &mut Connection,
+    ) -> Result<Vec<workflow::Stored>, diesel::result::Error> {
+        schema::workflows::dsl::workflows
+            .order(schema::workflows::created_at.desc())
+            .select(workflow::Stored::as_select())
+            .load(conn)
+    }
+
+    /// Select workflows left queued or running, whose body was stored for
+    /// resuming them, oldest first.
+    ///
+    /// Paused workflows are left to be resumed by the user.
+    fn select_unfinished_workflows(
+        conn: &mut Connection,
+    ) -> Result<Vec<workflow::Stored>, diesel::result::Error> {
+        schema::workflows::dsl::workflows
+            .filter(schema::workflows::status.eq_any(workflow::Status::UNFINISHED))
+            .filter(schema::workflows::status.ne(workflow::Status::Paused))
+            .filter(schema::workflows::body.is_not_null())
+            .order(schema::workflows::created_at.asc())
+            .select(workflow::Stored::as_select())
+            .load(conn)
+    }
+
+    /// Store the encoded body of a workflow, for resuming it later.
+    fn store_workflow_body(
+        cid: Cid,
+        body: Vec<u8>,
+        conn: &mut Connection,
+    ) -> Result<(), diesel::result::Error> {
+        diesel::update(schema::workflows::dsl::workflows)
+            .filter(schema::workflows::cid.eq(Pointer::new(cid)))
+            .set(schema::workflows::body.eq(body))
+            .execute(conn)?;
+
+        Ok(())
+    }
+
     /// Return workflow information with number of receipts emitted.
     fn get_workflow_info(
         workflow_cid: Cid,
@@ -317,10 +445,66 @@ pub trait Database: Send + Sync + Clone {
         Ok((name, info))
     }
 
+    /// Mark a workflow as queued to (re-)run, clearing the outcome of any
+    /// previous run.
+    fn queue_workflow(cid: Cid, conn: &mut Connection) -> Result<(), diesel::result::Error> {
+        diesel::update(schema::workflows::dsl::workflows)
+            .filter(schema::workflows::cid.eq(Pointer::new(cid)))
+            .set((
+                schema::workflows::status.eq(workflow::Status::Queued),
+                schema::workflows::last_error.eq(None::<String>),
+                schema::workflows::finished_at.eq(None::<NaiveDateTime>),
+            ))
+            .execute(conn)?;
+
+        Ok(())
+    }
+
+    /// Move a queued or running workflow on to the given status, along with
+    /// the error it failed with, if any.
+    ///
+    /// Final statuses are stamped with a finished timestamp (and completion
+    /// timestamp, if completed), and are never moved on from, so a worker
+    /// finishing up can't overwrite a cancellation or timeout.
+    ///
+    /// Return whether the workflow's status was updated.
+    fn update_workflow_status(
+        cid: Cid,
+        status: workflow::Status,
+        last_error: Option<String>,
+        conn: &mut Connection,
+    ) -> Result<bool, diesel::result::Error> {
+        let finished_at = status.is_finished().then(|| Utc::now().naive_utc());
+        let completed_at = finished_at.filter(|_| status == workflow::Status::Completed);
+
+        let updated = diesel::update(schema::workflows::dsl::workflows)
+            .filter(schema::workflows::cid.eq(Pointer::new(cid)))
+            .filter(schema::workflows::status.eq_any(workflow::Status::UNFINISHED))
+            .set((
+                schema::workflows::status.eq(status),
+                schema::workflows::last_error.eq(last_error),
+                schema::workflows::finished_at.eq(finished_at),
+            ))
+            .execute(conn)?;
+
+        if updated > 0 && completed_at.is_some() {
+            diesel::update(schema::workflows::dsl::workflows)
+                .filter(schema::workflows::cid.eq(Pointer::new(cid)))
+                .set(schema::workflows::completed_at.eq(completed_at))
+                .execute(conn)?;
+        }
+
+        Ok(updated > 0)
+    }
+
     /// Update the local (view) name of a workflow.
-    fn update_local_name(name: &str, conn: &mut Connection) -> Result<(), diesel::result::Error> {
+    fn update_local_name(
+        workflow_cid: Cid,
+        name: &str,
+        conn: &mut Connection,
+    ) -> Result<(), diesel::result::Error> {
         diesel::update(schema::workflows::dsl::workflows)
-            .filter(schema::workflows::created_at.lt(now))
+            .filter(schema::workflows::cid.eq(Pointer::new(workflow_cid)))
             .set(schema::workflows::name.eq(name))
             .execute(conn)?;
 
@@ -389,6 +573,7 @@ where
 mod test {
     use super::*;
     use crate::test_utils::db::MemoryDb;
+    use libipld::multihash::{Code, MultihashDigest};
 
     #[homestar_runtime_proc_macro::db_async_test]
     fn check_pragmas_memory_db() {
@@ -415,4 +600,163 @@ mod test {
 
         assert_eq!(busy_timeout, vec!["1000".to_string()]);
     }
+
+    #[homestar_runtime_proc_macro::db_async_test]
+    fn select_workflows_by_name() {
+        let settings = TestSettings::load();
+        let db = MemoryDb::setup_connection_pool(settings.node(), None).unwrap();
+        let mut conn = db.conn().unwrap();
+
+        let created_at = chrono::Utc::now().naive_utc();
+        let (older, newer) = (
+            Cid::new_v1(0x71, Code::Sha2_256.digest(b"older")),
+            Cid::new_v1(0x71, Code::Sha2_256.digest(b"newer")),
+        );
+        for (cid, created_at) in [
+            (older, created_at - chrono::Duration::seconds(10)),
+            (newer, created_at),
+        ] {
+            MemoryDb::store_workflow(
+                workflow::Stored::new(
+                    Pointer::new(cid),
+                    Some("add-one".to_string()),
+                    1,
+                    workflow::IndexedResources::default(),
+                    created_at,
+                ),
+                &mut conn,
+            )
+            .unwrap();
+        }
+
+        let stored = MemoryDb::select_workflows(&mut conn).unwrap();
+        assert_eq!(
+            stored.iter().map(|w| w.cid.cid()).collect::<Vec<_>>(),
+            vec![newer, older]
+        );
+        assert_eq!(
+            MemoryDb::select_workflow_by_name("add-one", &mut conn)
+                .unwrap()
+                .cid
+                .cid(),
+            newer
+        );
+        assert!(MemoryDb::select_workflow_by_name("add-two", &mut conn).is_err());
+    }
+
+    #[homestar_runtime_proc_macro::db_async_test]
+    fn update_workflow_status() {
+        let settings = TestSettings::load();
+        let db = MemoryDb::setup_connection_pool(settings.node(), None).unwrap();
+        let mut conn = db.conn().unwrap();
+
+        let cid = Cid::new_v1(0x71, Code::Sha2_256.digest(b"workflow"));
+        let stored =
+            MemoryDb::store_workflow(workflow::Stored::default(Pointer::new(cid), 1), &mut conn)
+                .unwrap();
+        assert_eq!(stored.status, workflow::Status::Queued);
+
+        assert!(
+            MemoryDb::update_workflow_status(cid, workflow::Status::Running, None, &mut conn)
+                .unwrap()
+        );
+        let running = MemoryDb::select_workflow(cid, &mut conn).unwrap();
+        assert_eq!(running.status, workflow::Status::Running);
+        assert!(running.finished_at.is_none());
+
+        assert!(MemoryDb::update_workflow_status(
+            cid,
+            workflow::Status::Failed,
+            Some("out of fuel".to_string()),
+            &mut conn
+        )
+        .unwrap());
+        let failed = MemoryDb::select_workflow(cid, &mut conn).unwrap();
+        assert_eq!(failed.status, workflow::Status::Failed);
+        assert_eq!(failed.last_error, Some("out of fuel".to_string()));
+        assert!(failed.finished_at.is_some());
+        assert!(failed.completed_at.is_none());
+
+        // Final statuses are never moved on from.
+        assert!(!MemoryDb::update_workflow_status(
+            cid,
+            workflow::Status::Completed,
+            None,
+            &mut conn
+        )
+        .unwrap());
+
+        MemoryDb::queue_workflow(cid, &mut conn).unwrap();
+        assert!(MemoryDb::update_workflow_status(
+            cid,
+            workflow::Status::Completed,
+            None,
+            &mut conn
+        )
+        .unwrap());
+        let completed = MemoryDb::select_workflow(cid, &mut conn).unwrap();
+        assert_eq!(completed.status, workflow::Status::Completed);
+        assert!(completed.last_error.is_none());
+        assert!(completed.completed_at.is_some());
+        assert_eq!(completed.finished_at, completed.completed_at);
+    }
+
+    #[homestar_runtime_proc_macro::db_async_test]
+    fn update_local_name() {
+        let settings = TestSettings::load();
+        let db = MemoryDb::setup_connection_pool(settings.node(), None).unwrap();
+        let mut conn = db.conn().unwrap();
+
+        let (one, two) = (
+            Cid::new_v1(0x71, Code::Sha2_256.digest(b"one")),
+            Cid::new_v1(0x71, Code::Sha2_256.digest(b"two")),
+        );
+        for cid in [one, two] {
+            MemoryDb::store_workflow(workflow::Stored::default(Pointer::new(cid), 1), &mut conn)
+                .unwrap();
+        }
+
+        MemoryDb::update_local_name(one, "add-one", &mut conn).unwrap();
+        MemoryDb::update_local_name(two, "add-two", &mut conn).unwrap();
+
+        for (name, cid) in [("add-one", one), ("add-two", two)] {
+            let stored = MemoryDb::select_workflow_by_name(name, &mut conn).unwrap();
+            assert_eq!(stored.cid.cid(), cid);
+            assert_eq!(stored.name, Some(name.to_string()));
+        }
+    }
+
+    #[homestar_runtime_proc_macro::db_async_test]
+    fn select_unfinished_workflows() {
+        let settings = TestSettings::load();
+        let db = MemoryDb::setup_connection_pool(settings.node(), None).unwrap();
+        let mut conn = db.conn().unwrap();
+
+        let (unstored, running, paused, cancelled) = (
+            Cid::new_v1(0x71, Code::Sha2_256.digest(b"unstored")),
+            Cid::new_v1(0x71, Code::Sha2_256.digest(b"running")),
+            Cid::new_v1(0x71, Code::Sha2_256.digest(b"paused")),
+            Cid::new_v1(0x71, Code::Sha2_256.digest(b"cancelled")),
+        );
+        for cid in [unstored, running, paused, cancelled] {
+            MemoryDb::store_workflow(workflow::Stored::default(Pointer::new(cid), 1), &mut conn)
+                .unwrap();
+        }
+        for cid in [running, paused, cancelled] {
+            MemoryDb::store_workflow_body(cid, b"body".to_vec(), &mut conn).unwrap();
+        }
+        for (cid, status) in [
+            (paused, workflow::Status::Paused),
+            (cancelled, workflow::Status::Cancelled),
+        ] {
+            assert!(MemoryDb::update_workflow_status(cid, status, None, &mut conn).unwrap());
+        }
+        let stored = MemoryDb::select_workflow(paused, &mut conn).unwrap();
+        assert!(stored.finished_at.is_none());
+
+        let unfinished = MemoryDb::select_unfinished_workflows(&mut conn).unwrap();
+        assert_eq!(unfinished.len(), 1);
+        assert_eq!(unfinished[0].cid.cid(), running);
+        assert_eq!(unfinished[0].body, Some(b"body".to_vec()));
+    }
 }
@@ -1,5 +1,19 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    instructions (cid) {
+        cid -> Text,
+        instruction -> Binary,
+    }
+}
+
+diesel::table! {
+    proofs (cid) {
+        cid -> Text,
+        token -> Text,
+    }
+}
+
 diesel::table! {
     receipts (cid) {
         cid -> Text,
@@ -10,6 +24,12 @@ diesel::table! {
         issuer -> Nullable<Text>,
         prf -> Binary,
         version -> Text,
+        sig -> Nullable<Binary>,
+        started_at -> Nullable<Timestamp>,
+        finished_at -> Nullable<Timestamp>,
+        duration -> Nullable<BigInt>,
+        fuel -> Nullable<BigInt>,
+        memory -> Nullable<BigInt>,
     }
 }
 
@@ -21,6 +41,10 @@ diesel::table! {
         resources -> Binary,
         created_at -> Timestamp,
         completed_at -> Nullable<Timestamp>,
+        status -> Text,
+        last_error -> Nullable<Text>,
+        finished_at -> Nullable<Timestamp>,
+        body -> Nullable<Binary>,
     }
 }
 
@@ -34,4 +58,10 @@ diesel::table! {
 diesel::joinable!(workflows_receipts -> receipts (receipt_cid));
 diesel::joinable!(workflows_receipts -> workflows (workflow_cid));
 
-diesel::allow_tables_to_appear_in_same_query!(receipts, workflows, workflows_receipts,);
+diesel::allow_tables_to_appear_in_same_query!(
+    instructions,
+    proofs,
+    receipts,
+    workflows,
+    workflows_receipts,
+);
@@ -5,14 +5,17 @@ use crate::network::webserver::{self, notifier};
 #[cfg(feature = "ipfs")]
 use crate::network::IpfsCli;
 use crate::{
+    blockstore::Blockstore,
     channel,
     db::Database,
     network::swarm::{ComposedBehaviour, PeerDiscoveryInfo, RequestResponseKey},
-    settings,
+    receipt::Verifier,
+    settings, Receipt,
 };
 use anyhow::Result;
 use async_trait::async_trait;
 use fnv::FnvHashMap;
+use libipld::Cid;
 use libp2p::{
     core::ConnectedPoint, futures::StreamExt, kad::QueryId, rendezvous::Cookie,
     request_response::RequestId, swarm::Swarm, PeerId,
@@ -61,6 +64,8 @@ pub(crate) struct EventHandler<DB: Database> {
     p2p_provider_timeout: Duration,
     /// Accessible database instance.
     db: DB,
+    /// Embedded [Blockstore] instance.
+    blockstore: Blockstore,
     /// [libp2p::swarm::Swarm] swarm instance.
     swarm: Swarm<ComposedBehaviour>,
     /// [moka::future::Cache] instance, used for retry logic.
@@ -75,6 +80,13 @@ pub(crate) struct EventHandler<DB: Database> {
     connections: Connections,
     /// [RequestId] to [RequestResponseKey] and [P2PSender] mapping.
     request_response_senders: FnvHashMap<RequestId, (RequestResponseKey, P2PSender)>,
+    /// [Instruction] [Cid] to [P2PSender]s awaiting on its [Receipt].
+    ///
+    /// [Instruction]: homestar_core::workflow::Instruction
+    /// [Receipt]: crate::Receipt
+    receipt_subscribers: ReceiptSubscribers,
+    /// [Verifier] for [Receipt]s received over gossip or the DHT.
+    receipt_verifier: Verifier,
     /// Rendezvous protocol configurations and state (cookies).
     rendezvous: Rendezvous,
     /// Whether or not to enable pubsub.
@@ -91,7 +103,7 @@ pub(crate) struct EventHandler<DB: Database> {
     announce_addresses: Vec<libp2p::Multiaddr>,
     /// Maximum number of externally reachable addresses to announce to the network.
     external_address_limit: u32,
-    /// Interval for polling the cache for expired entries.
+    /// Interval for polling the cache and receipt subscribers for expired entries.
     poll_cache_interval: Duration,
 }
 
@@ -109,6 +121,8 @@ pub(crate) struct EventHandler<DB: Database> {
     p2p_provider_timeout: Duration,
     /// Accesible database instance.
     db: DB,
+    /// Embedded [Blockstore] instance.
+    blockstore: Blockstore,
     /// [libp2p::swarm::Swarm] swarm instance.
     swarm: Swarm<ComposedBehaviour>,
     /// [moka::future::Cache] instance, centered around retry logic.
@@ -123,6 +137,13 @@ pub(crate) struct EventHandler<DB: Database> {
     connections: Connections,
     /// [RequestId] to [RequestResponseKey] and [P2PSender] mapping.
     request_response_senders: FnvHashMap<RequestId, (RequestResponseKey, P2PSender)>,
+    /// [Instruction] [Cid] to [P2PSender]s awaiting on its [Receipt].
+    ///
+    /// [Instruction]: homestar_core::workflow::Instruction
+    /// [Receipt]: crate::Receipt
+    receipt_subscribers: ReceiptSubscribers,
+    /// [Verifier] for [Receipt]s received over gossip or the DHT.
+    receipt_verifier: Verifier,
     /// Rendezvous protocol configurations and state (cookies).
     rendezvous: Rendezvous,
     /// Whether or not to enable pubsub.
@@ -133,7 +154,7 @@ pub(crate) struct EventHandler<DB: Database> {
     announce_addresses: Vec<libp2p::Multiaddr>,
     /// Maximum number of externally reachable addresses to announce to the network.
     external_address_limit: u32,
-    /// Interval for polling the cache for expired entries.
+    /// Interval for polling the cache and receipt subscribers for expired entries.
     poll_cache_interval: Duration,
 }
 
@@ -151,6 +172,33 @@ struct Connections {
     max_peers: u32,
 }
 
+// Subscribers awaiting on receipts, keyed by instruction [Cid].
+#[derive(Default)]
+struct ReceiptSubscribers(FnvHashMap<Cid, Vec<P2PSender>>);
+
+impl ReceiptSubscribers {
+    fn subscribe(&mut self, instruction_cid: Cid, sender: P2PSender) {
+        self.0.entry(instruction_cid).or_default().push(sender);
+    }
+
+    fn notify(&mut self, receipt: &Receipt) {
+        if let Some(subscribers) = self.0.remove(&receipt.instruction().cid()) {
+            for subscriber in subscribers {
+                let _ = subscriber.try_send(ResponseEvent::Receipt(receipt.clone()));
+            }
+        }
+    }
+
+    // Drop subscribers that have stopped waiting, e.g. past their deadline,
+    // along with instructions no one is waiting on anymore.
+    fn prune(&mut self) {
+        self.0.retain(|_, subscribers| {
+            subscribers.retain(|subscriber| !subscriber.is_disconnected());
+            !subscribers.is_empty()
+        });
+    }
+}
+
 impl<DB> EventHandler<DB>
 where
     DB: Database,
@@ -169,6 +217,7 @@ where
     pub(crate) fn new(
         swarm: Swarm<ComposedBehaviour>,
         db: DB,
+        blockstore: Blockstore,
         settings: &settings::Network,
         ws_evt_sender: webserver::Notifier<notifier::Message>,
         ws_workflow_sender: webserver::Notifier<notifier::Message>,
@@ -181,12 +230,15 @@ where
             p2p_workflow_info_timeout: settings.libp2p.dht.p2p_workflow_info_timeout,
             p2p_provider_timeout: settings.libp2p.dht.p2p_provider_timeout,
             db,
+            blockstore,
             swarm,
             cache: Arc::new(setup_cache(sender.clone())),
             sender,
             receiver,
             query_senders: FnvHashMap::default(),
             request_response_senders: FnvHashMap::default(),
+            receipt_subscribers: ReceiptSubscribers::default(),
+            receipt_verifier: Verifier::new(&settings.receipt_verification),
             connections: Connections {
                 peers: FnvHashMap::default(),
                 max_peers: settings.libp2p.max_connected_peers,
@@ -212,6 +264,7 @@ where
     pub(crate) fn new(
         swarm: Swarm<ComposedBehaviour>,
         db: DB,
+        blockstore: Blockstore,
         settings: &settings::Network,
     ) -> Self {
         let (sender, receiver) = Self::setup_channel(settings);
@@ -222,12 +275,15 @@ where
             p2p_workflow_info_timeout: settings.libp2p.dht.p2p_workflow_info_timeout,
             p2p_provider_timeout: settings.libp2p.dht.p2p_provider_timeout,
             db,
+            blockstore,
             swarm,
             cache: Arc::new(setup_cache(sender.clone())),
             sender,
             receiver,
             query_senders: FnvHashMap::default(),
             request_response_senders: FnvHashMap::default(),
+            receipt_subscribers: ReceiptSubscribers::default(),
+            receipt_verifier: Verifier::new(&settings.receipt_verification),
             connections: Connections {
                 peers: FnvHashMap::default(),
                 max_peers: settings.libp2p.max_connected_peers,
@@ -254,6 +310,22 @@ where
         self.sender.clone()
     }
 
+    /// Register a [P2PSender] awaiting on the [Receipt] of an [Instruction].
+    ///
+    /// Subscribers that stop waiting are pruned on the cache polling interval.
+    ///
+    /// [Instruction]: homestar_core::workflow::Instruction
+    pub(crate) fn subscribe_receipt(&mut self, instruction_cid: Cid, sender: P2PSender) {
+        self.receipt_subscribers.subscribe(instruction_cid, sender);
+    }
+
+    /// Hand a [Receipt] over to subscribers awaiting on its [Instruction].
+    ///
+    /// [Instruction]: homestar_core::workflow::Instruction
+    pub(crate) fn notify_receipt_subscribers(&mut self, receipt: &Receipt) {
+        self.receipt_subscribers.notify(receipt);
+    }
+
     /// [tokio::sync::broadcast::Sender] for sending workflow-related messages
     /// through the WebSocket server to subscribers.
     #[cfg(feature = "websocket-notify")]
@@ -279,6 +351,7 @@ where
     pub(crate) async fn start(mut self) -> Result<()> {
         let handle = Handle::current();
         handle.spawn(poll_cache(self.cache.clone(), self.poll_cache_interval));
+        let mut prune_interval = tokio::time::interval(self.poll_cache_interval);
 
         loop {
             select! {
@@ -291,6 +364,7 @@ where
                      swarm_event.handle_event(&mut self).await;
 
                 }
+                _ = prune_interval.tick() => self.receipt_subscribers.prune(),
             }
         }
     }
@@ -301,6 +375,7 @@ where
     pub(crate) async fn start(mut self, ipfs: IpfsCli) -> Result<()> {
         let handle = Handle::current();
         handle.spawn(poll_cache(self.cache.clone(), self.poll_cache_interval));
+        let mut prune_interval = tokio::time::interval(self.poll_cache_interval);
 
         loop {
             select! {
@@ -313,6 +388,7 @@ where
                     let ipfs_clone = ipfs.clone();
                         swarm_event.handle_event(&mut self, ipfs_clone).await;
                 }
+                _ = prune_interval.tick() => self.receipt_subscribers.prune(),
             }
         }
     }
@@ -327,3 +403,30 @@ async fn poll_cache(cache: Arc<Cache<String, CacheValue>>, poll_interval: Durati
         cache.run_pending_tasks().await;
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use libipld::multihash::{Code, MultihashDigest};
+
+    #[test]
+    fn prune_expired_receipt_subscribers() {
+        let expired = Cid::new_v1(0x55, Code::Sha3_256.digest(b"expired"));
+        let waiting = Cid::new_v1(0x55, Code::Sha3_256.digest(b"waiting"));
+
+        let mut subscribers = ReceiptSubscribers::default();
+        let (tx, rx) = channel::AsyncChannel::oneshot();
+        subscribers.subscribe(expired, tx);
+        let (tx, _rx) = channel::AsyncChannel::oneshot();
+        subscribers.subscribe(waiting, tx);
+
+        subscribers.prune();
+        assert_eq!(subscribers.0.len(), 2);
+
+        // Subscriber gives up, e.g. on reaching its deadline.
+        drop(rx);
+        subscribers.prune();
+        assert!(!subscribers.0.contains_key(&expired));
+        assert_eq!(subscribers.0[&waiting].len(), 1);
+    }
+}
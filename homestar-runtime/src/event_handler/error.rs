@@ -18,6 +18,11 @@ pub(crate) enum RequestResponseError {
     /// [Ipld]: libipld::Ipld
     #[error("failed to wrap {} into a Ipld capsule, tagged with {:?}", .0.cid, .0.capsule_tag.tag())]
     InvalidCapsule(RequestResponseKey),
+    /// Error when data keyed by [Cid] is not available to be sent.
+    ///
+    /// [Cid]: libipld::Cid
+    #[error("failed to find data keyed by cid {}, tagged with {:?}", .0.cid, .0.capsule_tag.tag())]
+    NotFound(RequestResponseKey),
     /// Unsupported message request based on the capsule tag.
     #[error("unsupported message request for tag {:?}, with cid {}", .0.capsule_tag.tag(), .0.cid)]
     Unsupported(RequestResponseKey),
@@ -120,6 +120,11 @@ pub(crate) enum Event {
     OutboundRequest(PeerRequest),
     /// Get providers for a record in the DHT, e.g. workflow information.
     GetProviders(QueryRecord),
+    /// Subscribe to the [Receipt] of an [Instruction], given its [Cid],
+    /// as it's captured locally or received over gossip.
+    ///
+    /// [Instruction]: homestar_core::workflow::Instruction
+    SubscribeReceipt(Cid, P2PSender),
     /// Provide a record in the DHT, e.g. workflow information.
     ProvideRecord(Cid, Option<P2PSender>, CapsuleTag),
     /// Found Providers/[PeerId]s on the DHT.
@@ -202,6 +207,9 @@ impl Event {
                         )
                     }
                 }
+                // Resolved instructions and blocks are not surfaced as
+                // notifications.
+                FoundEvent::Instruction(_) | FoundEvent::Block(_) => {}
             },
             Event::OutboundRequest(PeerRequest {
                 peer,
@@ -219,6 +227,7 @@ impl Event {
                     .insert(request_id, (request, sender));
             }
             Event::GetProviders(record) => record.get_providers(event_handler).await,
+            Event::SubscribeReceipt(cid, sender) => event_handler.subscribe_receipt(cid, sender),
             Event::ProvideRecord(cid, sender, capsule_tag) => {
                 let query_id = event_handler
                     .swarm
@@ -319,10 +328,27 @@ impl Captured {
         DB: Database,
     {
         let receipt = Db::find_receipt_by_cid(self.receipt, &mut event_handler.db.conn()?)?;
+        event_handler.notify_receipt_subscribers(&receipt);
+
         let invocation_receipt = InvocationReceipt::from(&receipt);
         let instruction_bytes = receipt.instruction_cid_as_bytes();
         let receipt_cid = receipt.cid();
 
+        // Write the receipt into the blockstore in the background, so it can
+        // be served without IPFS.
+        if let Ok(bytes) = Vec::<u8>::try_from(invocation_receipt.clone()) {
+            let blockstore = event_handler.blockstore.clone();
+            tokio::spawn(async move {
+                if let Err(err) = blockstore.put(receipt_cid, &bytes).await {
+                    warn!(subject = "blockstore.put.receipt.err",
+                          category = "publish_event",
+                          cid = receipt_cid.to_string(),
+                          err=?err,
+                          "failed to store receipt in blockstore");
+                }
+            });
+        }
+
         #[cfg(feature = "websocket-notify")]
         {
             emit_receipt(
@@ -346,6 +372,7 @@ impl Captured {
                 TopicMessage::CapturedReceipt(pubsub::Message::new(receipt.clone())),
             ) {
                 Ok(msg_id) => {
+                    crate::metrics::record_gossip_receipt_published();
                     info!(
                         subject = "libp2p.gossip.publish",
                         category = "publish_event",
@@ -392,7 +419,7 @@ impl Captured {
             Quorum::One
         };
 
-        if let Ok(receipt_bytes) = Receipt::invocation_capsule(&invocation_receipt) {
+        if let Ok(receipt_bytes) = receipt.capsule() {
             event_handler
                 .swarm
                 .behaviour_mut()
@@ -554,6 +581,7 @@ impl Replay {
                         TopicMessage::CapturedReceipt(pubsub::Message::new(receipt.clone())),
                     )
                     .map(|msg_id| {
+                        crate::metrics::record_gossip_receipt_published();
                         info!(
                             subject = "libp2p.gossip.publish.replay",
                             category = "publish_event",
@@ -16,15 +16,17 @@ use homestar_core::{
         Receipt as InvocationReceipt,
     },
 };
-use libipld::{serde::from_ipld, Ipld};
+use libipld::{serde::from_ipld, Cid, Ipld};
 use serde::{Deserialize, Serialize};
 use std::{collections::BTreeMap, fmt, str::FromStr};
 use tracing::{debug, warn};
 
 pub(crate) mod receipt;
 pub(crate) mod swarm;
+pub(crate) mod workflow;
 pub(crate) use receipt::ReceiptNotification;
 pub(crate) use swarm::SwarmNotification;
+pub(crate) use workflow::{WorkflowNotification, WorkflowNotificationTyp};
 
 const TYPE_KEY: &str = "type";
 const DATA_KEY: &str = "data";
@@ -71,6 +73,35 @@ pub(crate) fn emit_receipt(
     }
 }
 
+/// Send workflow lifecycle notification as bytes, to subscribers of the
+/// workflow.
+pub(crate) fn emit_workflow(
+    notifier: Notifier<notifier::Message>,
+    workflow_cid: Cid,
+    ty: WorkflowNotificationTyp,
+) {
+    let notification = WorkflowNotification::with(workflow_cid, ty.clone());
+
+    if let Ok(json) = notification.to_json() {
+        debug!(
+            subject = "notification.workflow",
+            category = "notification",
+            cid = workflow_cid.to_string(),
+            "emitting workflow {ty} to WebSocket"
+        );
+        let header = Header::new(SubscriptionTyp::Cid(workflow_cid), None);
+        let _ = notifier.notify(Message::new(header, json));
+    } else {
+        warn!(
+            subject = "notification.err",
+            category = "notification",
+            cid = workflow_cid.to_string(),
+            "unable to serialize workflow notification as bytes: {}",
+            ty
+        );
+    }
+}
+
 /// Send event notification as bytes.
 pub(crate) fn emit_event(
     notifier: Notifier<notifier::Message>,
@@ -225,6 +256,7 @@ impl TryFrom<Ipld> for EventNotificationTyp {
 #[cfg(test)]
 mod test {
     use super::*;
+    use libipld::multihash::{Code, MultihashDigest};
     use libp2p::PeerId;
     use maplit::btreemap;
 
@@ -253,6 +285,21 @@ mod test {
         assert_eq!(data.get("address").unwrap(), &address);
     }
 
+    #[test]
+    fn workflow_notification_json() {
+        let cid = Cid::new_v1(0x71, Code::Sha2_256.digest(b"workflow"));
+        let notification = WorkflowNotification::with(cid, WorkflowNotificationTyp::Cancelled);
+        let parsed = WorkflowNotification::from_json(&notification.to_json().unwrap()).unwrap();
+        let data: BTreeMap<String, Ipld> = from_ipld(parsed.into_inner()).unwrap();
+
+        assert_eq!(
+            data.get(TYPE_KEY).unwrap(),
+            &Ipld::String("workflow:cancelled".into())
+        );
+        assert_eq!(data.get("workflow_cid").unwrap(), &Ipld::Link(cid));
+        assert!(data.contains_key(TIMESTAMP_KEY));
+    }
+
     #[test]
     fn notification_json_string_rountrip() {
         let peer_id = PeerId::random().to_string();
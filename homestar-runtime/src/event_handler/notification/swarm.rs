@@ -16,6 +16,7 @@ pub(crate) enum SwarmNotification {
     IncomingConnectionError,
     PublishedReceiptPubsub,
     ReceivedReceiptPubsub,
+    RejectedReceipt,
     GotReceiptDht,
     PutReceiptDht,
     GotWorkflowInfoDht,
@@ -46,6 +47,9 @@ impl fmt::Display for SwarmNotification {
             SwarmNotification::PublishedReceiptPubsub => {
                 write!(f, "publishedReceiptPubsub")
             }
+            SwarmNotification::RejectedReceipt => {
+                write!(f, "rejectedReceipt")
+            }
             SwarmNotification::PutReceiptDht => {
                 write!(f, "putReceiptDht")
             }
@@ -92,6 +96,7 @@ impl FromStr for SwarmNotification {
             "incomingConnectionError" => Ok(Self::IncomingConnectionError),
             "receivedReceiptPubsub" => Ok(Self::ReceivedReceiptPubsub),
             "publishedReceiptPubsub" => Ok(Self::PublishedReceiptPubsub),
+            "rejectedReceipt" => Ok(Self::RejectedReceipt),
             "putReciptDht" => Ok(Self::PutReceiptDht),
             "gotReceiptDht" => Ok(Self::GotReceiptDht),
             "putWorkflowInfoDht" => Ok(Self::PutWorkflowInfoDht),
@@ -0,0 +1,66 @@
+//! Notification types for [Workflow] lifecycle changes.
+//!
+//! [Workflow]: homestar_core::Workflow
+
+use chrono::prelude::Utc;
+use homestar_core::ipld::DagJson;
+use libipld::{ipld, Cid, Ipld};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Workflow notification types sent to clients.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) enum WorkflowNotificationTyp {
+    Cancelled,
+    Paused,
+    Resumed,
+}
+
+impl fmt::Display for WorkflowNotificationTyp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            WorkflowNotificationTyp::Cancelled => write!(f, "cancelled"),
+            WorkflowNotificationTyp::Paused => write!(f, "paused"),
+            WorkflowNotificationTyp::Resumed => write!(f, "resumed"),
+        }
+    }
+}
+
+/// A [Workflow] lifecycle change sent out for websocket notifications.
+///
+/// [Workflow]: homestar_core::Workflow
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct WorkflowNotification(Ipld);
+
+impl WorkflowNotification {
+    /// Create a new [WorkflowNotification].
+    pub(crate) fn with(cid: Cid, typ: WorkflowNotificationTyp) -> Self {
+        let typ = format!("workflow:{typ}");
+        let data = ipld!({
+            "type": typ,
+            "workflow_cid": cid,
+            "timestamp": Utc::now().timestamp_millis(),
+        });
+        WorkflowNotification(data)
+    }
+
+    /// Obtain ownership of the inner [Ipld] value.
+    #[allow(dead_code)]
+    pub(crate) fn into_inner(self) -> Ipld {
+        self.0
+    }
+}
+
+impl DagJson for WorkflowNotification where Ipld: From<WorkflowNotification> {}
+
+impl From<WorkflowNotification> for Ipld {
+    fn from(notification: WorkflowNotification) -> Self {
+        notification.0
+    }
+}
+
+impl From<Ipld> for WorkflowNotification {
+    fn from(ipld: Ipld) -> Self {
+        WorkflowNotification(ipld)
+    }
+}
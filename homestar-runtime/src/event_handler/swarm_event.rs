@@ -13,13 +13,14 @@ use crate::{
     },
     libp2p::multiaddr::MultiaddrExt,
     network::{
+        exchange::{self, BLOCK_TAG},
         pubsub,
         swarm::{
             CapsuleTag, ComposedEvent, PeerDiscoveryInfo, RequestResponseKey, HOMESTAR_PROTOCOL_VER,
         },
     },
-    workflow,
-    workflow::WORKFLOW_TAG,
+    receipt::VerifyError,
+    workflow::{self, StoredInstruction, INSTRUCTION_TAG, WORKFLOW_TAG},
     Db, Receipt,
 };
 use anyhow::{anyhow, Result};
@@ -60,6 +61,9 @@ pub(crate) enum ResponseEvent {
     /// Found providers/[PeerId]s on the DHT.
     #[allow(dead_code)]
     Providers(Result<HashSet<PeerId>>),
+    /// [Receipt] captured locally or received over gossip, handed to
+    /// subscribers awaiting on its instruction.
+    Receipt(Receipt),
 }
 
 /// Internal events within the [SwarmEvent] context related to finding specific
@@ -70,6 +74,12 @@ pub(crate) enum FoundEvent {
     Receipt(ReceiptEvent),
     /// Found [workflow::Info] on the DHT.
     Workflow(WorkflowInfoEvent),
+    /// Found [Instruction] on the DHT or from a provider.
+    ///
+    /// [Instruction]: homestar_core::workflow::Instruction
+    Instruction(InstructionEvent),
+    /// Found block from a provider.
+    Block(BlockEvent),
 }
 
 /// [FoundEvent] variant for receipts found on the DHT.
@@ -90,6 +100,20 @@ pub(crate) struct WorkflowInfoEvent {
     pub(crate) notification_type: EventNotificationTyp,
 }
 
+/// [FoundEvent] variant for instructions found on the DHT or from a provider.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct InstructionEvent {
+    pub(crate) peer_id: Option<PeerId>,
+    pub(crate) instruction: Box<StoredInstruction>,
+}
+
+/// [FoundEvent] variant for blocks received from a provider.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BlockEvent {
+    pub(crate) peer_id: Option<PeerId>,
+    pub(crate) block: Vec<u8>,
+}
+
 #[async_trait]
 impl<THandlerErr, DB> Handler<THandlerErr, DB> for SwarmEvent<ComposedEvent, THandlerErr>
 where
@@ -471,6 +495,18 @@ async fn handle_swarm_event<THandlerErr: fmt::Debug + Send, DB: Database>(
                 match pubsub::Message::<Receipt>::try_from(bytes) {
                     Ok(msg) => {
                         let receipt = msg.payload;
+                        if verify_receipt(
+                            event_handler,
+                            &receipt,
+                            Some(propagation_source),
+                            "pubsub",
+                        )
+                        .is_err()
+                        {
+                            return;
+                        }
+
+                        crate::metrics::record_gossip_receipt_received();
                         info!(
                             subject = "libp2p.gossipsub.recv",
                             category = "handle_swarm_event",
@@ -487,6 +523,8 @@ async fn handle_swarm_event<THandlerErr: fmt::Debug + Send, DB: Database>(
                             .as_mut()
                             .map(|conn| Db::store_receipt(receipt.clone(), conn));
 
+                        event_handler.notify_receipt_subscribers(&receipt);
+
                         #[cfg(feature = "websocket-notify")]
                         notification::emit_event(
                             event_handler.ws_evt_sender(),
@@ -582,11 +620,28 @@ async fn handle_swarm_event<THandlerErr: fmt::Debug + Send, DB: Database>(
                     }
                 }
                 QueryResult::GetRecord(Ok(GetRecordOk::FoundRecord(peer_record))) => {
-                    match peer_record.found_record() {
+                    // Reject receipts that don't verify as if the record
+                    // couldn't be decoded.
+                    let decoded = peer_record
+                        .found_record()
+                        .and_then(|decoded| match decoded {
+                            DecodedRecord::Receipt(ReceiptRecord {
+                                peer_id,
+                                ref receipt,
+                            }) => {
+                                verify_receipt(event_handler, receipt, peer_id, "dht")?;
+                                Ok(decoded)
+                            }
+                            decoded => Ok(decoded),
+                        });
+
+                    match decoded {
                         Ok(decoded_record) => {
-                            let Some((_, sender)) = event_handler.query_senders.remove(&id) else {
+                            let Some((key, sender)) = event_handler.query_senders.remove(&id)
+                            else {
                                 return;
                             };
+                            crate::metrics::record_dht_quorum("get", key.capsule_tag.tag(), true);
 
                             match decoded_record {
                                 DecodedRecord::Receipt(ReceiptRecord { peer_id, receipt }) => {
@@ -648,6 +703,44 @@ async fn handle_swarm_event<THandlerErr: fmt::Debug + Send, DB: Database>(
                                         }
                                     );
                                 }
+                                DecodedRecord::Instruction(InstructionRecord {
+                                    peer_id,
+                                    instruction,
+                                }) => {
+                                    let cid = instruction.cid();
+                                    let response_event = ResponseEvent::Found(Ok(
+                                        FoundEvent::Instruction(InstructionEvent {
+                                            peer_id,
+                                            instruction,
+                                        }),
+                                    ));
+
+                                    if let Some(sender) = sender {
+                                        let _ = sender.send_async(response_event).await;
+                                    }
+
+                                    debug!(
+                                        subject = "libp2p.kad.get_record",
+                                        category = "handle_swarm_event",
+                                        cid = cid.to_string(),
+                                        "found instruction record published by {}",
+                                        match peer_id {
+                                            Some(peer) => peer.to_string(),
+                                            None => "unknown peer".to_string(),
+                                        }
+                                    );
+                                }
+                                // Blocks are only exchanged with providers, never
+                                // put as records.
+                                DecodedRecord::Block(_) => {
+                                    if let Some(sender) = sender {
+                                        let _ = sender
+                                            .send_async(ResponseEvent::Found(Err(anyhow!(
+                                                "found block record, blocks are only exchanged with providers"
+                                            ))))
+                                            .await;
+                                    }
+                                }
                             }
                         }
                         Err(err) => {
@@ -672,12 +765,16 @@ async fn handle_swarm_event<THandlerErr: fmt::Debug + Send, DB: Database>(
                           err=?err,
                           "error retrieving record");
 
+                    if let Some((key, _)) = event_handler.query_senders.get(&id) {
+                        crate::metrics::record_dht_quorum("get", key.capsule_tag.tag(), false);
+                    }
+
                     // Upon an error, attempt to find the record on the DHT via
-                    // a provider if it's a Workflow/Info one.
+                    // a provider if it's a Workflow/Info or Instruction one.
                     match event_handler.query_senders.remove(&id) {
                         Some((
                             RequestResponseKey {
-                                capsule_tag: CapsuleTag::Workflow,
+                                capsule_tag: CapsuleTag::Workflow | CapsuleTag::Instruction,
                                 ..
                             },
                             sender,
@@ -714,6 +811,7 @@ async fn handle_swarm_event<THandlerErr: fmt::Debug + Send, DB: Database>(
                     let Some((key, _)) = event_handler.query_senders.remove(&id) else {
                         return;
                     };
+                    crate::metrics::record_dht_quorum("put", key.capsule_tag.tag(), true);
 
                     debug!(
                         subject = "libp2p.kad.put_record",
@@ -723,6 +821,8 @@ async fn handle_swarm_event<THandlerErr: fmt::Debug + Send, DB: Database>(
                         match key.capsule_tag {
                             CapsuleTag::Receipt => "receipt",
                             CapsuleTag::Workflow => "workflow info",
+                            CapsuleTag::Instruction => "instruction",
+                            CapsuleTag::Block => "block",
                         }
                     );
 
@@ -748,12 +848,16 @@ async fn handle_swarm_event<THandlerErr: fmt::Debug + Send, DB: Database>(
                                 "quorum" => Ipld::Integer(event_handler.workflow_quorum as i128),
                             },
                         ),
+                        // Instructions and blocks are only provided, never put
+                        // as records.
+                        CapsuleTag::Instruction | CapsuleTag::Block => {}
                     }
                 }
                 QueryResult::PutRecord(Err(err)) => {
                     let Some((key, _)) = event_handler.query_senders.remove(&id) else {
                         return;
                     };
+                    crate::metrics::record_dht_quorum("put", key.capsule_tag.tag(), false);
 
                     warn!(
                       subject = "libp2p.kad.put_record.err",
@@ -764,6 +868,8 @@ async fn handle_swarm_event<THandlerErr: fmt::Debug + Send, DB: Database>(
                       match key.capsule_tag {
                           CapsuleTag::Receipt => "receipt",
                           CapsuleTag::Workflow => "workflow info",
+                          CapsuleTag::Instruction => "instruction",
+                          CapsuleTag::Block => "block",
                       }
                     );
 
@@ -794,6 +900,9 @@ async fn handle_swarm_event<THandlerErr: fmt::Debug + Send, DB: Database>(
                                     "storedToPeers" => Ipld::List(success.iter().map(|cid| Ipld::String(cid.to_string())).collect())
                                 },
                             ),
+                            // Instructions and blocks are only provided, never
+                            // put as records.
+                            CapsuleTag::Instruction | CapsuleTag::Block => {}
                         }
                     }
                 }
@@ -803,7 +912,8 @@ async fn handle_swarm_event<THandlerErr: fmt::Debug + Send, DB: Database>(
                     if let Some((
                         RequestResponseKey {
                             cid: ref cid_str,
-                            capsule_tag: CapsuleTag::Workflow,
+                            capsule_tag:
+                                CapsuleTag::Workflow | CapsuleTag::Instruction | CapsuleTag::Block,
                         },
                         _,
                     )) = event_handler.query_senders.remove(&id)
@@ -822,7 +932,8 @@ async fn handle_swarm_event<THandlerErr: fmt::Debug + Send, DB: Database>(
                     if let Some((
                         RequestResponseKey {
                             cid: ref cid_str,
-                            capsule_tag: CapsuleTag::Workflow,
+                            capsule_tag:
+                                CapsuleTag::Workflow | CapsuleTag::Instruction | CapsuleTag::Block,
                         },
                         _,
                     )) = event_handler.query_senders.remove(&id)
@@ -938,6 +1049,105 @@ async fn handle_swarm_event<THandlerErr: fmt::Debug + Send, DB: Database>(
                         }
                     }
                 }
+                (Ok(cid), INSTRUCTION_TAG) => {
+                    match event_handler
+                        .db
+                        .conn()
+                        .and_then(|mut conn| Ok(Db::find_stored_instruction(cid, &mut conn)?))
+                    {
+                        Ok(instruction) => {
+                            if let Ok(bytes) = instruction.capsule() {
+                                let _ = event_handler
+                                    .swarm
+                                    .behaviour_mut()
+                                    .request_response
+                                    .send_response(channel, bytes);
+
+                                debug!(subject = "libp2p.req_resp",
+                                      category = "handle_swarm_event",
+                                      cid=?cid,
+                                      peer_id = peer.to_string(),
+                                      "sent instruction to peer"
+                                );
+                            } else {
+                                let _ = event_handler
+                                    .swarm
+                                    .behaviour_mut()
+                                    .request_response
+                                    .send_response(
+                                        channel,
+                                        RequestResponseError::InvalidCapsule(request)
+                                            .encode()
+                                            .unwrap_or_default(),
+                                    );
+                            }
+                        }
+                        Err(err) => {
+                            warn!(subject = "libp2p.req_resp.err",
+                                  category = "handle_swarm_event",
+                                  err=?err,
+                                  cid=?cid,
+                                  "error retrieving instruction");
+
+                            let _ = event_handler
+                                .swarm
+                                .behaviour_mut()
+                                .request_response
+                                .send_response(
+                                    channel,
+                                    RequestResponseError::NotFound(request)
+                                        .encode()
+                                        .unwrap_or_default(),
+                                );
+                        }
+                    }
+                }
+                (Ok(cid), BLOCK_TAG) => match event_handler.blockstore.get(cid).await {
+                    Ok(Some(block)) => {
+                        if let Ok(bytes) = exchange::capsule(block) {
+                            let _ = event_handler
+                                .swarm
+                                .behaviour_mut()
+                                .request_response
+                                .send_response(channel, bytes);
+
+                            debug!(subject = "libp2p.req_resp",
+                                  category = "handle_swarm_event",
+                                  cid=?cid,
+                                  peer_id = peer.to_string(),
+                                  "sent block to peer"
+                            );
+                        } else {
+                            let _ = event_handler
+                                .swarm
+                                .behaviour_mut()
+                                .request_response
+                                .send_response(
+                                    channel,
+                                    RequestResponseError::InvalidCapsule(request)
+                                        .encode()
+                                        .unwrap_or_default(),
+                                );
+                        }
+                    }
+                    Ok(None) | Err(_) => {
+                        warn!(subject = "libp2p.req_resp.err",
+                                  category = "handle_swarm_event",
+                                  cid=?cid,
+                                  "block not available in blockstore");
+
+                        let _ = event_handler
+                            .swarm
+                            .behaviour_mut()
+                            .request_response
+                            .send_response(
+                                channel,
+                                RequestResponseError::NotFound(request)
+                                    .encode()
+                                    .unwrap_or_default(),
+                            );
+                    }
+                },
                 _ => {
                     let _ = event_handler
                         .swarm
@@ -984,6 +1194,42 @@ async fn handle_swarm_event<THandlerErr: fmt::Debug + Send, DB: Database>(
                                       "received workflow info from peer"
                                 );
                             }
+                            Ok(DecodedRecord::Instruction(InstructionRecord {
+                                peer_id,
+                                instruction,
+                            })) => {
+                                let response_event = ResponseEvent::Found(Ok(
+                                    FoundEvent::Instruction(InstructionEvent {
+                                        peer_id,
+                                        instruction,
+                                    }),
+                                ));
+
+                                let _ = sender.send_async(response_event).await;
+
+                                debug!(subject = "libp2p.req_resp",
+                                      category = "handle_swarm_event",
+                                      cid=?cid,
+                                      peer_id = peer.to_string(),
+                                      "received instruction from peer"
+                                );
+                            }
+                            Ok(DecodedRecord::Block(BlockRecord { peer_id, block })) => {
+                                let response_event =
+                                    ResponseEvent::Found(Ok(FoundEvent::Block(BlockEvent {
+                                        peer_id,
+                                        block,
+                                    })));
+
+                                let _ = sender.send_async(response_event).await;
+
+                                debug!(subject = "libp2p.req_resp",
+                                      category = "handle_swarm_event",
+                                      cid=?cid,
+                                      peer_id = peer.to_string(),
+                                      "received block from peer"
+                                );
+                            }
                             Ok(DecodedRecord::Receipt(record)) => {
                                 debug!(subject = "libp2p.req_resp.resp.err",
                                       category = "handle_swarm_event",
@@ -1245,3 +1491,39 @@ async fn handle_swarm_event<THandlerErr: fmt::Debug + Send, DB: Database>(
                     "uncaught event"),
     }
 }
+
+/// Verify a [Receipt] received over gossip or the DHT, logging and notifying
+/// on its rejection.
+fn verify_receipt<DB: Database>(
+    event_handler: &EventHandler<DB>,
+    receipt: &Receipt,
+    peer_id: Option<PeerId>,
+    source: &str,
+) -> Result<(), VerifyError> {
+    event_handler
+        .receipt_verifier
+        .verify(receipt)
+        .map_err(|err| {
+            warn!(subject = "libp2p.receipt.rejected",
+                  category = "handle_swarm_event",
+                  cid = receipt.cid().to_string(),
+                  peer_id=?peer_id,
+                  err=?err,
+                  "rejected receipt received over {source}");
+
+            #[cfg(feature = "websocket-notify")]
+            notification::emit_event(
+                event_handler.ws_evt_sender(),
+                EventNotificationTyp::SwarmNotification(SwarmNotification::RejectedReceipt),
+                btreemap! {
+                    "publisher" => peer_id.map_or(Ipld::Null, |peer| Ipld::String(peer.to_string())),
+                    "cid" => Ipld::String(receipt.cid().to_string()),
+                    "ran" => Ipld::String(receipt.ran()),
+                    "source" => Ipld::String(source.to_string()),
+                    "reason" => Ipld::String(err.to_string())
+                },
+            );
+
+            err
+        })
+}
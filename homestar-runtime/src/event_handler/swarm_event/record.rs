@@ -2,9 +2,10 @@
 
 use crate::{
     event_handler::RequestResponseError,
-    receipt::{RECEIPT_TAG, VERSION_KEY},
-    workflow,
-    workflow::WORKFLOW_TAG,
+    network::exchange::BLOCK_TAG,
+    receipt::{RECEIPT_TAG, SIGNATURE_KEY, VERSION_KEY},
+    tasks::unixfs,
+    workflow::{self, StoredInstruction, INSTRUCTION_TAG, WORKFLOW_TAG},
     Receipt,
 };
 use anyhow::{anyhow, Result};
@@ -12,7 +13,7 @@ use homestar_core::{
     consts,
     workflow::{Pointer, Receipt as InvocationReceipt},
 };
-use libipld::{Cid, Ipld};
+use libipld::{cbor::DagCborCodec, prelude::Codec, Cid, Ipld};
 use libp2p::{kad::PeerRecord, PeerId};
 
 /// Trait for handling [PeerRecord]s found on the DHT.
@@ -33,6 +34,8 @@ impl FoundRecord for PeerRecord {
 pub(crate) enum DecodedRecord {
     Receipt(ReceiptRecord),
     Workflow(WorkflowInfoRecord),
+    Instruction(InstructionRecord),
+    Block(BlockRecord),
 }
 
 /// [DecodedRecord] variant for receipts found on DHT.
@@ -55,6 +58,26 @@ pub(crate) struct WorkflowInfoRecord {
     pub(crate) workflow_info: workflow::Info,
 }
 
+/// [DecodedRecord] variant for instructions found on DHT or from a provider.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct InstructionRecord {
+    /// The [PeerId] of the peer from whom the record was retrieved. `None` if the record
+    /// was retrieved from local storage.
+    pub(crate) peer_id: Option<PeerId>,
+    /// The retrieved [StoredInstruction], verified against its [Cid].
+    pub(crate) instruction: Box<StoredInstruction>,
+}
+
+/// [DecodedRecord] variant for blocks received from a provider.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BlockRecord {
+    /// The [PeerId] of the peer from whom the block was retrieved. `None` if the block
+    /// was retrieved from local storage.
+    pub(crate) peer_id: Option<PeerId>,
+    /// The retrieved block, verified against its [Cid].
+    pub(crate) block: Vec<u8>,
+}
+
 pub(crate) fn decode_capsule(
     key_cid: Cid,
     peer_id: Option<PeerId>,
@@ -71,8 +94,14 @@ pub(crate) fn decode_capsule(
                 if rest.remove(VERSION_KEY)
                     == Some(Ipld::String(consts::INVOCATION_VERSION.to_string()))
                 {
+                    let sig = match rest.remove(SIGNATURE_KEY) {
+                        Some(Ipld::Bytes(sig)) => Some(sig),
+                        _ => None,
+                    };
                     let invocation_receipt = InvocationReceipt::try_from(Ipld::Map(rest))?;
-                    let receipt = Receipt::try_with(Pointer::new(key_cid), &invocation_receipt)?;
+                    let mut receipt =
+                        Receipt::try_with(Pointer::new(key_cid), &invocation_receipt)?;
+                    receipt.set_signature(sig);
 
                     Ok(DecodedRecord::Receipt(ReceiptRecord { peer_id, receipt }))
                 } else {
@@ -90,6 +119,20 @@ pub(crate) fn decode_capsule(
                     workflow_info,
                 }))
             }
+            Some((code, ipld @ Ipld::Map(_))) if code == INSTRUCTION_TAG => {
+                let instruction =
+                    StoredInstruction::try_with(key_cid, DagCborCodec.encode(&ipld)?)?.into();
+
+                Ok(DecodedRecord::Instruction(InstructionRecord {
+                    peer_id,
+                    instruction,
+                }))
+            }
+            Some((code, Ipld::Bytes(block))) if code == BLOCK_TAG => {
+                unixfs::verify(key_cid, &block)?;
+
+                Ok(DecodedRecord::Block(BlockRecord { peer_id, block }))
+            }
             Some((code, _)) if code == "Timeout" => Err(anyhow!("decode error: record timed out")),
             Some((code, _)) => Err(anyhow!("decode mismatch: {code} is not known")),
             None => Err(anyhow!("invalid record value")),
@@ -104,7 +147,7 @@ pub(crate) fn decode_capsule(
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{test_utils, workflow};
+    use crate::{network::exchange, test_utils, workflow};
     use homestar_core::{
         ipld::DagCbor,
         test_utils::workflow as workflow_test_utils,
@@ -112,6 +155,7 @@ mod test {
         Workflow,
     };
     use homestar_wasm::io::Arg;
+    use libipld::multihash::{Code, MultihashDigest};
     use libp2p::{kad::Record, PeerId};
 
     #[test]
@@ -131,6 +175,65 @@ mod test {
         }
     }
 
+    #[test]
+    fn found_signed_receipt_record() {
+        let signer =
+            crate::receipt::Signer::new(libp2p::identity::Keypair::generate_ed25519()).unwrap();
+        let (invocation_receipt, receipt) = test_utils::receipt::receipts();
+        let signed = Receipt::try_signed(
+            receipt.instruction().to_owned(),
+            invocation_receipt,
+            &signer,
+        )
+        .unwrap();
+        let record = Record::new(signed.instruction_cid_as_bytes(), signed.capsule().unwrap());
+        let peer_record = PeerRecord {
+            record,
+            peer: Some(PeerId::random()),
+        };
+        if let DecodedRecord::Receipt(decoded) = peer_record.found_record().unwrap() {
+            assert_eq!(decoded.receipt, signed);
+            assert!(decoded.receipt.signature().is_some());
+        } else {
+            panic!("Incorrect record type")
+        }
+    }
+
+    #[test]
+    fn found_instruction_record() {
+        let instruction = workflow_test_utils::wasm_instruction::<Arg>();
+        let stored = StoredInstruction::try_from(instruction).unwrap();
+        let bytes = stored.capsule().unwrap();
+        let record = Record::new(stored.cid().to_bytes(), bytes);
+        let peer_record = PeerRecord {
+            record,
+            peer: Some(PeerId::random()),
+        };
+        if let DecodedRecord::Instruction(decoded) = peer_record.found_record().unwrap() {
+            assert_eq!(*decoded.instruction, stored);
+        } else {
+            panic!("Incorrect record type")
+        }
+    }
+
+    #[test]
+    fn found_block_record() {
+        let block = b"beep boop".to_vec();
+        let cid = Cid::new_v1(0x55, Code::Sha2_256.digest(&block));
+        let bytes = exchange::capsule(block.clone()).unwrap();
+        let peer = PeerId::random();
+
+        if let DecodedRecord::Block(decoded) = decode_capsule(cid, Some(peer), &bytes).unwrap() {
+            assert_eq!(decoded.block, block);
+            assert_eq!(decoded.peer_id, Some(peer));
+        } else {
+            panic!("Incorrect record type")
+        }
+
+        let tampered = exchange::capsule(b"bop".to_vec()).unwrap();
+        assert!(decode_capsule(cid, Some(peer), &tampered).is_err());
+    }
+
     #[test]
     fn found_workflow_record() {
         let config = Resources::default();
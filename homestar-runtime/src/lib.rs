@@ -17,6 +17,7 @@
 //! [homestar-core]: homestar_core
 //! [homestar-wasm]: homestar_wasm
 
+mod blockstore;
 pub mod channel;
 pub mod cli;
 pub mod daemon;
@@ -48,4 +49,4 @@ pub use runner::Runner;
 pub(crate) use scheduler::TaskScheduler;
 pub use settings::Settings;
 pub(crate) use worker::Worker;
-pub use workflow::WORKFLOW_TAG;
+pub use workflow::{INSTRUCTION_TAG, WORKFLOW_TAG};
@@ -9,6 +9,15 @@ use tokio::runtime::Handle;
 mod exporter;
 #[cfg(feature = "monitoring")]
 mod node;
+mod runtime;
+
+#[cfg(feature = "websocket-notify")]
+pub(crate) use runtime::Subscriber;
+pub(crate) use runtime::{
+    record_dht_quorum, record_gossip_receipt_published, record_gossip_receipt_received,
+    record_resource_fetch, record_task_execution, record_workflow_completed,
+    record_workflow_failed, record_workflow_submitted,
+};
 
 /// Start metrics collection and setup scrape endpoint.
 /// Also, spawn a task to collect process metrics at a regular interval.
@@ -2,7 +2,7 @@
 
 #[cfg(feature = "monitoring")]
 use crate::metrics::node;
-use crate::settings;
+use crate::{metrics::runtime, settings};
 use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
 use metrics_util::layers::{PrefixLayer, Stack};
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
@@ -35,6 +35,7 @@ pub(crate) fn setup_metrics_recorder(
         .push(PrefixLayer::new("homestar"))
         .install()?;
 
+    runtime::describe();
     #[cfg(feature = "monitoring")]
     node::describe();
 
@@ -0,0 +1,201 @@
+//! Runtime metrics, covering workflows, task executions and network
+//! operations.
+
+use crate::{receipt::Stats, workflow::Resource};
+use dashmap::DashSet;
+use metrics::{
+    counter, decrement_gauge, describe_counter, describe_gauge, describe_histogram, histogram,
+    increment_counter, increment_gauge, Unit,
+};
+use once_cell::sync::Lazy;
+use std::time::Duration;
+
+const WORKFLOWS_SUBMITTED: &str = "workflows_submitted_total";
+const WORKFLOWS_COMPLETED: &str = "workflows_completed_total";
+const WORKFLOWS_FAILED: &str = "workflows_failed_total";
+const TASK_DURATION: &str = "task_duration_seconds";
+const TASK_FUEL: &str = "task_fuel_consumed_total";
+const RESOURCE_FETCH_DURATION: &str = "resource_fetch_duration_seconds";
+const RESOURCE_FETCH_FAILURES: &str = "resource_fetch_failures_total";
+const DHT_QUORUM_SUCCESSES: &str = "dht_quorum_successes_total";
+const DHT_QUORUM_FAILURES: &str = "dht_quorum_failures_total";
+const GOSSIP_RECEIPTS_PUBLISHED: &str = "gossip_receipts_published_total";
+const GOSSIP_RECEIPTS_RECEIVED: &str = "gossip_receipts_received_total";
+const WEBSOCKET_SUBSCRIBERS: &str = "websocket_subscribers";
+
+/// Maximum number of distinct `function` label values recorded for task
+/// executions. Function names come from submitted workflows, so they're
+/// capped to keep the label's cardinality bounded.
+const MAX_FUNCTION_LABELS: usize = 256;
+/// `function` label value for functions recorded past the cap.
+const OTHER_FUNCTION_LABEL: &str = "other";
+
+static FUNCTION_LABELS: Lazy<DashSet<String>> = Lazy::new(DashSet::new);
+
+/// Describe runtime metrics.
+pub(crate) fn describe() {
+    // Workflow metrics
+    describe_counter!(
+        WORKFLOWS_SUBMITTED,
+        Unit::Count,
+        "The number of workflows submitted to run."
+    );
+    describe_counter!(
+        WORKFLOWS_COMPLETED,
+        Unit::Count,
+        "The number of workflows that ran to completion."
+    );
+    describe_counter!(
+        WORKFLOWS_FAILED,
+        Unit::Count,
+        "The number of workflows that failed."
+    );
+
+    // Task metrics
+    describe_histogram!(
+        TASK_DURATION,
+        Unit::Seconds,
+        "The wall-clock duration of task executions, by op and function."
+    );
+    describe_counter!(
+        TASK_FUEL,
+        Unit::Count,
+        "The fuel consumed by task executions, by op and function."
+    );
+    describe_histogram!(
+        RESOURCE_FETCH_DURATION,
+        Unit::Seconds,
+        "The latency of fetching task resources, retries included."
+    );
+    describe_counter!(
+        RESOURCE_FETCH_FAILURES,
+        Unit::Count,
+        "The number of task resources that could not be fetched."
+    );
+
+    // Network metrics
+    describe_counter!(
+        DHT_QUORUM_SUCCESSES,
+        Unit::Count,
+        "The number of DHT record puts and gets that met quorum."
+    );
+    describe_counter!(
+        DHT_QUORUM_FAILURES,
+        Unit::Count,
+        "The number of DHT record puts and gets that failed."
+    );
+    describe_counter!(
+        GOSSIP_RECEIPTS_PUBLISHED,
+        Unit::Count,
+        "The number of receipts published over gossipsub."
+    );
+    describe_counter!(
+        GOSSIP_RECEIPTS_RECEIVED,
+        Unit::Count,
+        "The number of verified receipts received over gossipsub."
+    );
+    describe_gauge!(
+        WEBSOCKET_SUBSCRIBERS,
+        Unit::Count,
+        "The number of active websocket subscriptions, by subscription."
+    );
+}
+
+/// Record a workflow submitted to run.
+pub(crate) fn record_workflow_submitted() {
+    increment_counter!(WORKFLOWS_SUBMITTED);
+}
+
+/// Record a workflow that ran to completion.
+pub(crate) fn record_workflow_completed() {
+    increment_counter!(WORKFLOWS_COMPLETED);
+}
+
+/// Record a workflow that failed.
+pub(crate) fn record_workflow_failed() {
+    increment_counter!(WORKFLOWS_FAILED);
+}
+
+/// Record the duration and fuel of a task execution, given its [Stats].
+///
+/// Once [MAX_FUNCTION_LABELS] distinct functions have been recorded, any
+/// other function is recorded under the `other` label.
+pub(crate) fn record_task_execution(op: &str, function: &str, stats: &Stats) {
+    let labels = [
+        ("op", op.to_string()),
+        ("function", function_label(function)),
+    ];
+    histogram!(TASK_DURATION, stats.duration_ms() as f64 / 1000.0, &labels);
+    counter!(TASK_FUEL, stats.fuel, &labels);
+}
+
+fn function_label(function: &str) -> String {
+    // The length check and insert aren't atomic, so the cap may be exceeded
+    // by a few labels under concurrent executions, which is fine.
+    if FUNCTION_LABELS.contains(function) {
+        function.to_string()
+    } else if FUNCTION_LABELS.len() < MAX_FUNCTION_LABELS {
+        FUNCTION_LABELS.insert(function.to_string());
+        function.to_string()
+    } else {
+        OTHER_FUNCTION_LABEL.to_string()
+    }
+}
+
+/// Record a resource fetch, successful or not, which took `elapsed`.
+pub(crate) fn record_resource_fetch(rsc: &Resource, elapsed: Duration, fetched: bool) {
+    let scheme = match rsc {
+        Resource::Url(url) => url.scheme().to_string(),
+        Resource::Cid(_) => "cid".to_string(),
+    };
+
+    if fetched {
+        histogram!(RESOURCE_FETCH_DURATION, elapsed.as_secs_f64(), "scheme" => scheme);
+    } else {
+        increment_counter!(RESOURCE_FETCH_FAILURES, "scheme" => scheme);
+    }
+}
+
+/// Record the quorum outcome of a DHT `put` or `get` of a `record`, named
+/// by its capsule tag.
+pub(crate) fn record_dht_quorum(operation: &'static str, record: &'static str, success: bool) {
+    let name = if success {
+        DHT_QUORUM_SUCCESSES
+    } else {
+        DHT_QUORUM_FAILURES
+    };
+    increment_counter!(name, "operation" => operation, "record" => record);
+}
+
+/// Record a receipt published over gossipsub.
+pub(crate) fn record_gossip_receipt_published() {
+    increment_counter!(GOSSIP_RECEIPTS_PUBLISHED);
+}
+
+/// Record a verified receipt received over gossipsub.
+pub(crate) fn record_gossip_receipt_received() {
+    increment_counter!(GOSSIP_RECEIPTS_RECEIVED);
+}
+
+/// Guard counting an active websocket subscription for as long as it's
+/// held.
+#[cfg(feature = "websocket-notify")]
+#[derive(Debug)]
+pub(crate) struct Subscriber(String);
+
+#[cfg(feature = "websocket-notify")]
+impl Subscriber {
+    /// Count a new subscription to the given `subscription` endpoint.
+    pub(crate) fn new(subscription: impl Into<String>) -> Self {
+        let subscription = subscription.into();
+        increment_gauge!(WEBSOCKET_SUBSCRIBERS, 1.0, "subscription" => subscription.clone());
+        Self(subscription)
+    }
+}
+
+#[cfg(feature = "websocket-notify")]
+impl Drop for Subscriber {
+    fn drop(&mut self) {
+        decrement_gauge!(WEBSOCKET_SUBSCRIBERS, 1.0, "subscription" => self.0.clone());
+    }
+}
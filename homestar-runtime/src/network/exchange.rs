@@ -0,0 +1,202 @@
+//! Block exchange between Homestar nodes over [request_response], with
+//! blocks held in the [Blockstore] advertised through DHT provider records.
+//!
+//! [request_response]: libp2p::request_response
+
+#[cfg(feature = "ipfs")]
+use crate::network::IpfsCli;
+use crate::{
+    blockstore::Blockstore,
+    channel::{AsyncChannel, AsyncChannelSender},
+    event_handler::{
+        event::QueryRecord,
+        swarm_event::{FoundEvent, ResponseEvent},
+        Event,
+    },
+    network::swarm::CapsuleTag,
+    settings,
+};
+use anyhow::{anyhow, bail, Result};
+use libipld::{cbor::DagCborCodec, prelude::Codec, Cid, Ipld};
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+use tokio::time::{timeout_at, Instant};
+use tracing::debug;
+
+/// Block header tag, for sharing blocks over libp2p.
+pub(crate) const BLOCK_TAG: &str = "ipvm/block";
+
+/// Capsule-wrapper for a block to be shared over libp2p as [DagCbor]
+/// encoded bytes.
+///
+/// [DagCbor]: DagCborCodec
+pub(crate) fn capsule(block: Vec<u8>) -> Result<Vec<u8>> {
+    let capsule = Ipld::Map(BTreeMap::from([(BLOCK_TAG.into(), Ipld::Bytes(block))]));
+    DagCborCodec.encode(&capsule)
+}
+
+/// Retrieval of blocks, by [Cid], from the local [Blockstore], IPFS, or
+/// peers providing them.
+#[derive(Clone, Debug)]
+pub(crate) struct BlockExchange {
+    blockstore: Blockstore,
+    event_sender: Arc<AsyncChannelSender<Event>>,
+    p2p_provider_timeout: Duration,
+}
+
+impl BlockExchange {
+    /// Create a new [BlockExchange] over a [Blockstore].
+    pub(crate) fn new(
+        blockstore: Blockstore,
+        event_sender: Arc<AsyncChannelSender<Event>>,
+        settings: &settings::Dht,
+    ) -> Self {
+        Self {
+            blockstore,
+            event_sender,
+            p2p_provider_timeout: settings.p2p_provider_timeout,
+        }
+    }
+
+    /// Get a block via 3 lookups:
+    ///   * a check in the [Blockstore];
+    ///   * a lookup of the block on IPFS;
+    ///   * a request to peers providing the block on the DHT.
+    ///
+    /// Blocks retrieved from IPFS or peers are verified, written into the
+    /// [Blockstore], and provided to the network in turn.
+    pub(crate) async fn get_block(
+        &self,
+        cid: Cid,
+        #[cfg(feature = "ipfs")] ipfs: &IpfsCli,
+    ) -> Result<Vec<u8>> {
+        if let Some(block) = self.blockstore.get(cid).await? {
+            return Ok(block);
+        }
+
+        // IPFS lookups can hang on blocks the daemon can't find, so they're
+        // bounded to leave room for asking peers.
+        #[cfg(feature = "ipfs")]
+        let block = match tokio::time::timeout(self.p2p_provider_timeout, ipfs.get_block(cid)).await
+        {
+            Ok(Ok(block)) => Ok(block),
+            _ => self.retrieve_from_peers(cid).await,
+        };
+
+        #[cfg(not(feature = "ipfs"))]
+        let block = self.retrieve_from_peers(cid).await;
+
+        let block = block.map_err(|err| anyhow!("block {cid} could not be retrieved: {err}"))?;
+        self.blockstore.put(cid, &block).await?;
+        self.event_sender
+            .send_async(Event::ProvideRecord(cid, None, CapsuleTag::Block))
+            .await?;
+
+        Ok(block)
+    }
+
+    // Request a block from the peers providing it, taking the first block
+    // returned, as responses are verified against the [Cid].
+    async fn retrieve_from_peers(&self, cid: Cid) -> Result<Vec<u8>> {
+        let (tx, rx) = AsyncChannel::oneshot();
+        self.event_sender
+            .send_async(Event::GetProviders(QueryRecord::with(
+                cid,
+                CapsuleTag::Block,
+                Some(tx),
+            )))
+            .await?;
+
+        let deadline = Instant::now() + self.p2p_provider_timeout;
+        loop {
+            match timeout_at(deadline, rx.recv_async()).await {
+                Ok(Ok(ResponseEvent::Found(Ok(FoundEvent::Block(event))))) => {
+                    return Ok(event.block)
+                }
+                Ok(Ok(ResponseEvent::Providers(Err(err)))) => {
+                    bail!("failure in attempting to find providers: {err}")
+                }
+                // Other providers may still respond with the block.
+                Ok(Ok(event)) => {
+                    debug!(
+                        subject = "exchange.retrieve_from_peers",
+                        category = "exchange",
+                        cid = cid.to_string(),
+                        "unexpected response for block: {event:?}"
+                    )
+                }
+                Ok(Err(err)) => bail!("unexpected error while retrieving block: {err}"),
+                Err(_) => bail!("timeout deadline reached while retrieving block {cid}"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::event_handler::swarm_event::BlockEvent;
+    use libipld::multihash::{Code, MultihashDigest};
+
+    const RAW: u64 = 0x55;
+
+    #[tokio::test]
+    async fn get_block_from_peers() {
+        let path = std::env::temp_dir().join(format!("homestar_exchange_{}", std::process::id()));
+        let blockstore = Blockstore::new(&settings::Blockstore { path: path.clone() });
+        let (tx, rx) = AsyncChannel::with(4);
+        let exchange = BlockExchange::new(blockstore.clone(), tx.into(), &settings::Dht::default());
+        #[cfg(feature = "ipfs")]
+        let ipfs = IpfsCli::new(&settings::Ipfs {
+            host: "127.0.0.1".to_string(),
+            port: 1,
+        })
+        .unwrap();
+
+        let block = b"beep boop".to_vec();
+        let cid = Cid::new_v1(RAW, Code::Sha2_256.digest(&block));
+
+        let peer_block = block.clone();
+        let peer = tokio::spawn(async move {
+            let Ok(Event::GetProviders(QueryRecord {
+                cid: requested,
+                sender: Some(sender),
+                ..
+            })) = rx.recv_async().await
+            else {
+                panic!("expected a providers query");
+            };
+            assert_eq!(requested, cid);
+            sender
+                .send_async(ResponseEvent::Found(Ok(FoundEvent::Block(BlockEvent {
+                    peer_id: None,
+                    block: peer_block,
+                }))))
+                .await
+                .unwrap();
+
+            let Ok(Event::ProvideRecord(provided, None, CapsuleTag::Block)) = rx.recv_async().await
+            else {
+                panic!("expected the block to be provided");
+            };
+            assert_eq!(provided, cid);
+        });
+
+        #[cfg(feature = "ipfs")]
+        let fetched = exchange.get_block(cid, &ipfs).await.unwrap();
+        #[cfg(not(feature = "ipfs"))]
+        let fetched = exchange.get_block(cid).await.unwrap();
+        peer.await.unwrap();
+
+        assert_eq!(fetched, block);
+        assert_eq!(blockstore.get(cid).await.unwrap(), Some(block.clone()));
+
+        // Subsequent lookups are served by the blockstore.
+        #[cfg(feature = "ipfs")]
+        let fetched = exchange.get_block(cid, &ipfs).await.unwrap();
+        #[cfg(not(feature = "ipfs"))]
+        let fetched = exchange.get_block(cid).await.unwrap();
+        assert_eq!(fetched, block);
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}
@@ -60,6 +60,19 @@ impl IpfsCli {
             .map_err(Into::into)
     }
 
+    /// Retrieve a raw block, e.g. a [DagCbor]-encoded node, for a [Cid].
+    ///
+    /// [DagCbor]: libipld::cbor::DagCborCodec
+    #[allow(dead_code)]
+    pub(crate) async fn get_block(&self, cid: Cid) -> Result<Vec<u8>> {
+        self.0
+            .block_get(&cid.to_string())
+            .map_ok(|chunk| chunk.to_vec())
+            .try_concat()
+            .await
+            .map_err(Into::into)
+    }
+
     /// Put/Write [Receipt] into IPFS.
     #[allow(dead_code)]
     pub(crate) async fn put_receipt(&self, receipt: Receipt<Ipld>) -> Result<String> {
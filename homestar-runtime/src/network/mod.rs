@@ -6,6 +6,7 @@
 //! [ipfs]: ipfs_api
 
 pub(crate) mod error;
+pub(crate) mod exchange;
 #[cfg(feature = "ipfs")]
 pub(crate) mod ipfs;
 pub(crate) mod pubsub;
@@ -15,5 +16,6 @@ pub(crate) mod webserver;
 
 #[allow(unused_imports)]
 pub(crate) use error::Error;
+pub(crate) use exchange::BlockExchange;
 #[cfg(feature = "ipfs")]
 pub(crate) use ipfs::IpfsCli;
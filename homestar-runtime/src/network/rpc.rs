@@ -2,7 +2,7 @@
 
 use crate::{
     channel::{AsyncChannel, AsyncChannelReceiver, AsyncChannelSender},
-    runner::{self, file::ReadWorkflow, response, RpcSender},
+    runner::{self, file::EncodedWorkflow, response, RpcSender},
     settings,
 };
 use faststr::FastStr;
@@ -35,10 +35,11 @@ pub(crate) enum ServerMessage {
     ///
     /// [Runner]: crate::Runner
     GracefulShutdown(AsyncChannelSender<()>),
-    /// Message sent to start a [Workflow] run by reading a [Workflow] file.
+    /// Message sent to start a [Workflow] run, given the encoded contents of
+    /// a [Workflow] file read by the client.
     ///
     /// [Workflow]: homestar_core::Workflow
-    Run((Option<FastStr>, ReadWorkflow)),
+    Run((Option<FastStr>, EncodedWorkflow)),
     /// Acknowledgement of a [Workflow] run.
     ///
     /// [Workflow]: homestar_core::Workflow
@@ -47,6 +48,49 @@ pub(crate) enum ServerMessage {
     ///
     /// [Workflow]: homestar_core::Workflow
     RunErr(runner::Error),
+    /// Message sent to list [Workflow]s known to the [Runner].
+    ///
+    /// [Workflow]: homestar_core::Workflow
+    /// [Runner]: crate::Runner
+    ListWorkflows,
+    /// [Workflow]s known to the [Runner], in response to
+    /// [ServerMessage::ListWorkflows].
+    ///
+    /// [Workflow]: homestar_core::Workflow
+    /// [Runner]: crate::Runner
+    ListWorkflowsAck(response::WorkflowList),
+    /// Message sent to get the status of a [Workflow], given its [Cid] or
+    /// local name.
+    ///
+    /// [Workflow]: homestar_core::Workflow
+    /// [Cid]: libipld::Cid
+    WorkflowStatus(FastStr),
+    /// Status of a [Workflow], in response to [ServerMessage::WorkflowStatus].
+    ///
+    /// [Workflow]: homestar_core::Workflow
+    WorkflowStatusAck(Box<response::WorkflowStatus>),
+    /// Message sent to cancel a running or queued [Workflow], given its
+    /// [Cid] or local name.
+    ///
+    /// [Workflow]: homestar_core::Workflow
+    /// [Cid]: libipld::Cid
+    CancelWorkflow(FastStr),
+    /// Message sent to pause a running or queued [Workflow], given its
+    /// [Cid] or local name.
+    ///
+    /// [Workflow]: homestar_core::Workflow
+    /// [Cid]: libipld::Cid
+    PauseWorkflow(FastStr),
+    /// Message sent to resume a paused [Workflow], given its [Cid] or local
+    /// name.
+    ///
+    /// [Workflow]: homestar_core::Workflow
+    /// [Cid]: libipld::Cid
+    ResumeWorkflow(FastStr),
+    /// Error attempting to query or manage a [Workflow].
+    ///
+    /// [Workflow]: homestar_core::Workflow
+    WorkflowErr(runner::Error),
     /// For skipping server messages.
     Skip,
 }
@@ -57,8 +101,18 @@ pub(crate) trait Interface {
     /// Returns a greeting for name.
     async fn run(
         name: Option<FastStr>,
-        workflow_file: ReadWorkflow,
+        workflow: EncodedWorkflow,
     ) -> Result<Box<response::AckWorkflow>, Error>;
+    /// List workflows known to the server.
+    async fn list_workflows() -> Result<response::WorkflowList, Error>;
+    /// Get the status of a workflow, given its cid or local name.
+    async fn workflow_status(workflow: FastStr) -> Result<Box<response::WorkflowStatus>, Error>;
+    /// Cancel a running or queued workflow, given its cid or local name.
+    async fn cancel_workflow(workflow: FastStr) -> Result<Box<response::WorkflowStatus>, Error>;
+    /// Pause a running or queued workflow, given its cid or local name.
+    async fn pause_workflow(workflow: FastStr) -> Result<Box<response::WorkflowStatus>, Error>;
+    /// Resume a paused workflow, given its cid or local name.
+    async fn resume_workflow(workflow: FastStr) -> Result<Box<response::WorkflowStatus>, Error>;
     /// Ping the server.
     async fn ping() -> String;
     /// Stop the server.
@@ -109,6 +163,30 @@ impl ServerHandler {
             timeout,
         }
     }
+
+    /// Send a message to the [Runner] and await its reply, up to the server
+    /// timeout.
+    ///
+    /// [Runner]: crate::Runner
+    async fn query(&self, msg: ServerMessage) -> Result<ServerMessage, Error> {
+        let (tx, rx) = AsyncChannel::oneshot();
+        self.runner_sender
+            .send_async((msg, Some(tx)))
+            .await
+            .map_err(|e| Error::FailureToSendOnChannel(e.to_string()))?;
+
+        let now = time::Instant::now();
+        select! {
+            Ok(msg) = rx.recv_async() => Ok(msg),
+            _ = time::sleep_until(now + self.timeout) => {
+                let s = format!("server timeout of {} ms reached", self.timeout.as_millis());
+                info!(subject = "rpc.timeout",
+                      category = "rpc",
+                      "{s}");
+                Err(Error::FailureToReceiveOnChannel(s))
+            }
+        }
+    }
 }
 
 #[tarpc::server]
@@ -117,11 +195,11 @@ impl Interface for ServerHandler {
         self,
         _: context::Context,
         name: Option<FastStr>,
-        workflow_file: ReadWorkflow,
+        workflow: EncodedWorkflow,
     ) -> Result<Box<response::AckWorkflow>, Error> {
         let (tx, rx) = AsyncChannel::oneshot();
         self.runner_sender
-            .send_async((ServerMessage::Run((name, workflow_file)), Some(tx)))
+            .send_async((ServerMessage::Run((name, workflow)), Some(tx)))
             .await
             .map_err(|e| Error::FailureToSendOnChannel(e.to_string()))?;
 
@@ -146,6 +224,57 @@ impl Interface for ServerHandler {
 
         }
     }
+    async fn list_workflows(self, _: context::Context) -> Result<response::WorkflowList, Error> {
+        match self.query(ServerMessage::ListWorkflows).await? {
+            ServerMessage::ListWorkflowsAck(response) => Ok(response),
+            ServerMessage::WorkflowErr(err) => Err(Error::FromRunner(err.to_string())),
+            _ => Err(Error::FailureToSendOnChannel("unexpected message".into())),
+        }
+    }
+    async fn workflow_status(
+        self,
+        _: context::Context,
+        workflow: FastStr,
+    ) -> Result<Box<response::WorkflowStatus>, Error> {
+        match self.query(ServerMessage::WorkflowStatus(workflow)).await? {
+            ServerMessage::WorkflowStatusAck(response) => Ok(response),
+            ServerMessage::WorkflowErr(err) => Err(Error::FromRunner(err.to_string())),
+            _ => Err(Error::FailureToSendOnChannel("unexpected message".into())),
+        }
+    }
+    async fn cancel_workflow(
+        self,
+        _: context::Context,
+        workflow: FastStr,
+    ) -> Result<Box<response::WorkflowStatus>, Error> {
+        match self.query(ServerMessage::CancelWorkflow(workflow)).await? {
+            ServerMessage::WorkflowStatusAck(response) => Ok(response),
+            ServerMessage::WorkflowErr(err) => Err(Error::FromRunner(err.to_string())),
+            _ => Err(Error::FailureToSendOnChannel("unexpected message".into())),
+        }
+    }
+    async fn pause_workflow(
+        self,
+        _: context::Context,
+        workflow: FastStr,
+    ) -> Result<Box<response::WorkflowStatus>, Error> {
+        match self.query(ServerMessage::PauseWorkflow(workflow)).await? {
+            ServerMessage::WorkflowStatusAck(response) => Ok(response),
+            ServerMessage::WorkflowErr(err) => Err(Error::FromRunner(err.to_string())),
+            _ => Err(Error::FailureToSendOnChannel("unexpected message".into())),
+        }
+    }
+    async fn resume_workflow(
+        self,
+        _: context::Context,
+        workflow: FastStr,
+    ) -> Result<Box<response::WorkflowStatus>, Error> {
+        match self.query(ServerMessage::ResumeWorkflow(workflow)).await? {
+            ServerMessage::WorkflowStatusAck(response) => Ok(response),
+            ServerMessage::WorkflowErr(err) => Err(Error::FromRunner(err.to_string())),
+            _ => Err(Error::FailureToSendOnChannel("unexpected message".into())),
+        }
+    }
     async fn ping(self, _: context::Context) -> String {
         "pong".into()
     }
@@ -233,7 +362,10 @@ impl Client {
     ///
     /// [tcp]: tarpc::serde_transport::tcp
     pub async fn new(addr: SocketAddr, ctx: context::Context) -> Result<Self, io::Error> {
-        let transport = tarpc::serde_transport::tcp::connect(addr, MessagePack::default).await?;
+        let mut transport = tarpc::serde_transport::tcp::connect(addr, MessagePack::default);
+        // Workflows are sent whole, and may bundle their blocks.
+        transport.config_mut().max_frame_length(usize::MAX);
+        let transport = transport.await?;
         let client = InterfaceClient::new(client::Config::default(), transport).spawn();
         Ok(Client {
             cli: client,
@@ -257,14 +389,66 @@ impl Client {
         self.cli.stop(self.ctx).await
     }
 
-    /// Run a [Workflow].
+    /// List [Workflow]s known to the server.
+    ///
+    /// [Workflow]: homestar_core::Workflow
+    pub async fn list_workflows(&self) -> Result<Result<response::WorkflowList, Error>, RpcError> {
+        self.cli.list_workflows(self.ctx).await
+    }
+
+    /// Get the status of a [Workflow], given its [Cid] or local name.
+    ///
+    /// [Workflow]: homestar_core::Workflow
+    /// [Cid]: libipld::Cid
+    pub async fn workflow_status(
+        &self,
+        workflow: FastStr,
+    ) -> Result<Result<Box<response::WorkflowStatus>, Error>, RpcError> {
+        self.cli.workflow_status(self.ctx, workflow).await
+    }
+
+    /// Cancel a running or queued [Workflow], given its [Cid] or local name.
+    ///
+    /// [Workflow]: homestar_core::Workflow
+    /// [Cid]: libipld::Cid
+    pub async fn cancel_workflow(
+        &self,
+        workflow: FastStr,
+    ) -> Result<Result<Box<response::WorkflowStatus>, Error>, RpcError> {
+        self.cli.cancel_workflow(self.ctx, workflow).await
+    }
+
+    /// Pause a running or queued [Workflow], given its [Cid] or local name.
+    ///
+    /// [Workflow]: homestar_core::Workflow
+    /// [Cid]: libipld::Cid
+    pub async fn pause_workflow(
+        &self,
+        workflow: FastStr,
+    ) -> Result<Result<Box<response::WorkflowStatus>, Error>, RpcError> {
+        self.cli.pause_workflow(self.ctx, workflow).await
+    }
+
+    /// Resume a paused [Workflow], given its [Cid] or local name.
+    ///
+    /// [Workflow]: homestar_core::Workflow
+    /// [Cid]: libipld::Cid
+    pub async fn resume_workflow(
+        &self,
+        workflow: FastStr,
+    ) -> Result<Result<Box<response::WorkflowStatus>, Error>, RpcError> {
+        self.cli.resume_workflow(self.ctx, workflow).await
+    }
+
+    /// Run a [Workflow], given its encoded contents, which are validated
+    /// by the server.
     ///
     /// [Workflow]: homestar_core::Workflow
     pub async fn run(
         &self,
         name: Option<FastStr>,
-        workflow_file: ReadWorkflow,
+        workflow: EncodedWorkflow,
     ) -> Result<Result<Box<response::AckWorkflow>, Error>, RpcError> {
-        self.cli.run(self.ctx, name, workflow_file).await
+        self.cli.run(self.ctx, name, workflow).await
     }
 }
@@ -4,10 +4,10 @@
 //! [Swarm]: libp2p::Swarm
 
 use crate::{
-    network::{error::PubSubError, pubsub},
-    settings, Receipt, RECEIPT_TAG, WORKFLOW_TAG,
+    network::{error::PubSubError, exchange::BLOCK_TAG, pubsub},
+    settings, Receipt, INSTRUCTION_TAG, RECEIPT_TAG, WORKFLOW_TAG,
 };
-use anyhow::{Context, Result};
+use anyhow::Result;
 use const_format::formatcp;
 use enum_assoc::Assoc;
 use faststr::FastStr;
@@ -15,6 +15,7 @@ use libp2p::{
     core::upgrade,
     gossipsub::{self, MessageId, TopicHash},
     identify,
+    identity::Keypair,
     kad::{
         self,
         record::store::{MemoryStore, MemoryStoreConfig},
@@ -35,13 +36,12 @@ pub(crate) const HOMESTAR_PROTOCOL_VER: &str = formatcp!("homestar/{VERSION}");
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
-/// Build a new [Swarm] with a given transport and a tokio executor.
-pub(crate) async fn new(settings: &settings::Network) -> Result<Swarm<ComposedBehaviour>> {
-    let keypair = settings
-        .keypair_config
-        .keypair()
-        .with_context(|| "failed to generate/import keypair for libp2p".to_string())?;
-
+/// Build a new [Swarm] for the node's [Keypair], with a given transport and
+/// a tokio executor.
+pub(crate) async fn new(
+    keypair: Keypair,
+    settings: &settings::Network,
+) -> Result<Swarm<ComposedBehaviour>> {
     let peer_id = keypair.public().to_peer_id();
     info!(
         subject = "swarm.init",
@@ -264,6 +264,14 @@ pub(crate) enum CapsuleTag {
     #[assoc(tag = WORKFLOW_TAG)]
     #[assoc(capsule_type = WORKFLOW_TAG)]
     Workflow,
+    /// Instruction capsule-tag-wrapper: [INSTRUCTION_TAG].
+    #[assoc(tag = INSTRUCTION_TAG)]
+    #[assoc(capsule_type = INSTRUCTION_TAG)]
+    Instruction,
+    /// Block capsule-tag-wrapper: [BLOCK_TAG].
+    #[assoc(tag = BLOCK_TAG)]
+    #[assoc(capsule_type = BLOCK_TAG)]
+    Block,
 }
 
 impl fmt::Display for CapsuleTag {
@@ -3,13 +3,11 @@
 use crate::{
     db::Database,
     runner,
-    runner::{DynamicNodeInfo, StaticNodeInfo, WsSender},
+    runner::{file::ParsedWorkflow, response, DynamicNodeInfo, StaticNodeInfo, WsSender},
     settings,
 };
 use anyhow::{anyhow, Result};
 use faststr::FastStr;
-use homestar_core::Workflow;
-use homestar_wasm::io::Arg;
 use http::{
     header::{AUTHORIZATION, CONTENT_TYPE},
     method::Method,
@@ -51,13 +49,15 @@ use rpc::{Context, JsonRpc};
 /// WebSocket server to the [runner] for example.
 ///
 /// [runner]: crate::Runner
+/// [Workflow]: homestar_core::Workflow
 #[allow(dead_code)]
 #[derive(Debug)]
 pub(crate) enum Message {
     /// Error attempting to run a [Workflow].
     RunErr(runner::Error),
-    /// Run a workflow, given a tuple of name, and [Workflow].
-    RunWorkflow((FastStr, Workflow<'static, Arg>)),
+    /// Run a workflow, given a tuple of name, and [Workflow] parsed along
+    /// with its settings and proofs.
+    RunWorkflow((FastStr, ParsedWorkflow<'static>)),
     /// Acknowledgement of a [Workflow] run.
     AckWorkflow((Cid, FastStr)),
     /// Message sent to the [Runner] to gather node information from the [EventHandler].
@@ -68,6 +68,16 @@ pub(crate) enum Message {
     /// Acknowledgement of a [Message::GetNodeInfo] request, receiving static and dynamic
     /// node information.
     AckNodeInfo((StaticNodeInfo, DynamicNodeInfo)),
+    /// Cancel a running or queued [Workflow], given its [Cid] or local name.
+    CancelWorkflow(FastStr),
+    /// Pause a running or queued [Workflow], given its [Cid] or local name.
+    PauseWorkflow(FastStr),
+    /// Resume a paused [Workflow], given its [Cid] or local name.
+    ResumeWorkflow(FastStr),
+    /// Status of a [Workflow] after a change requested by a client.
+    AckWorkflowStatus(Box<response::WorkflowStatus>),
+    /// Error attempting to manage a [Workflow].
+    WorkflowErr(runner::Error),
 }
 
 /// Server fields.
@@ -287,8 +297,11 @@ mod test {
         ipld::DagJson,
         test_utils,
         workflow::{config::Resources, instruction::RunInstruction, prf::UcanPrf, Task},
+        Workflow,
     };
     #[cfg(feature = "websocket-notify")]
+    use homestar_wasm::io::Arg;
+    #[cfg(feature = "websocket-notify")]
     use jsonrpsee::core::client::{error::Error as ClientError, Subscription, SubscriptionClientT};
     #[cfg(feature = "websocket-notify")]
     use jsonrpsee::types::error::ErrorCode;
@@ -1,22 +1,38 @@
 //! Listener for incoming requests types.
 
+use crate::runner::file::ParsedWorkflow;
 use faststr::FastStr;
-use homestar_core::{ipld::DagJson, Workflow};
-use homestar_wasm::io::Arg;
+use libipld::{json::DagJsonCodec, prelude::Codec, Ipld};
 use names::{Generator, Name};
 use serde::{de, Deserialize, Deserializer, Serialize};
 use serde_json::value::RawValue;
 
 /// A [Workflow] run command via a WebSocket channel.
 ///
-/// Note: We leverage the [RawValue] type in order to use our [DagJson]
-/// implementation, which is not a direct [Deserialize] implementation.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// The workflow is parsed along with its top-level `meta` section and any
+/// bundled UCAN `proofs`, as from a workflow file.
+///
+/// Note: We leverage the [RawValue] type in order to decode the workflow as
+/// DAG-JSON, which is not a direct [Deserialize] implementation.
+///
+/// [Workflow]: homestar_core::Workflow
+#[derive(Debug, Clone, Deserialize)]
 pub(crate) struct Run<'a> {
-    #[serde(default = "default_name")]
-    pub(crate) name: FastStr,
+    #[serde(default)]
+    pub(crate) name: Option<FastStr>,
     #[serde(deserialize_with = "from_raw_value")]
-    pub(crate) workflow: Workflow<'a, Arg>,
+    pub(crate) workflow: ParsedWorkflow<'a>,
+}
+
+impl Run<'_> {
+    /// Name to run the workflow under: the one given with the command, or
+    /// else the workflow's own, or else a generated one.
+    pub(crate) fn name(&self) -> FastStr {
+        self.name
+            .clone()
+            .or_else(|| self.workflow.name.clone().map(FastStr::from))
+            .unwrap_or_else(default_name)
+    }
 }
 
 fn default_name() -> FastStr {
@@ -27,12 +43,15 @@ fn default_name() -> FastStr {
         .into()
 }
 
-fn from_raw_value<'a, 'de, D>(deserializer: D) -> Result<Workflow<'a, Arg>, D::Error>
+fn from_raw_value<'a, 'de, D>(deserializer: D) -> Result<ParsedWorkflow<'a>, D::Error>
 where
     D: Deserializer<'de>,
 {
     let raw_value: &RawValue = Deserialize::deserialize(deserializer)?;
-    Workflow::from_json(raw_value.get().as_bytes()).map_err(de::Error::custom)
+    let ipld: Ipld = DagJsonCodec
+        .decode(raw_value.get().as_bytes())
+        .map_err(de::Error::custom)?;
+    ParsedWorkflow::try_from(ipld).map_err(de::Error::custom)
 }
 
 /// Filter metrics by prefix.
@@ -44,10 +63,14 @@ pub(crate) struct MetricsPrefix {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::workflow;
     use homestar_core::{
+        ipld::DagJson,
         test_utils,
         workflow::{config::Resources, instruction::RunInstruction, prf::UcanPrf, Task},
+        Workflow,
     };
+    use homestar_wasm::io::Arg;
     use std::assert_eq;
 
     #[test]
@@ -68,18 +91,40 @@ mod test {
         );
 
         let workflow = Workflow::new(vec![task1.clone(), task2.clone()]);
-        let run = Run {
-            name: "test".into(),
-            workflow: workflow.clone(),
-        };
-
         let run_str = format!(
             r#"{{"name": "test","workflow": {}}}"#,
             workflow.to_json_string().unwrap()
         );
 
-        let post_run = serde_json::from_str(&run_str).unwrap();
+        let run: Run<'_> = serde_json::from_str(&run_str).unwrap();
+        assert_eq!(run.name(), FastStr::from("test"));
+        assert_eq!(run.workflow.workflow, workflow);
+        assert_eq!(run.workflow.settings, workflow::Settings::default());
+    }
+
+    #[test]
+    fn run_json_with_meta() {
+        let (instruction, _) = test_utils::workflow::wasm_instruction_with_nonce::<Arg>();
+        let task = Task::new(
+            RunInstruction::Expanded(instruction),
+            Resources::default().into(),
+            UcanPrf::default(),
+        );
+        let workflow = Workflow::new(vec![task]);
+
+        let mut workflow_json: serde_json::Value =
+            serde_json::from_str(&workflow.to_json_string().unwrap()).unwrap();
+        workflow_json["meta"] = serde_json::json!({"name": "add-one", "retries": 2});
+        let run_str = format!(r#"{{"workflow": {workflow_json}}}"#);
+
+        let run: Run<'_> = serde_json::from_str(&run_str).unwrap();
+        assert_eq!(run.name(), FastStr::from("add-one"));
+        assert_eq!(run.workflow.workflow, workflow);
+        assert_eq!(run.workflow.settings.retries, 2);
 
-        assert_eq!(run, post_run);
+        // Invalid settings are rejected.
+        workflow_json["meta"] = serde_json::json!({"retries": "two"});
+        let run_str = format!(r#"{{"workflow": {workflow_json}}}"#);
+        assert!(serde_json::from_str::<Run<'_>>(&run_str).is_err());
     }
 }
@@ -64,6 +64,10 @@ impl PrometheusData {
                 metric_lines.push(line)
             }
         }
+        // Last set, which no following set completes.
+        if num_comment_lines == 2 {
+            metrics.push(MetricFamily::from_raw(&metric_lines)?);
+        }
         Ok(PrometheusData { metrics })
     }
 }
@@ -430,5 +434,50 @@ homestar_system_load_average_percentage 6.26611328125"#;
             .unwrap();
 
         assert_eq!(check, &serde_json::Value::String("45969408".to_string()));
+        assert_eq!(
+            root.get("metrics").and_then(|v| v.as_array()).map(Vec::len),
+            Some(6)
+        );
+    }
+
+    #[test]
+    fn parse_labelled_runtime_metrics() {
+        let raw_data = r#"# HELP homestar_workflows_submitted_total The number of workflows submitted to run.
+# TYPE homestar_workflows_submitted_total counter
+homestar_workflows_submitted_total 2
+
+# HELP homestar_task_duration_seconds The wall-clock duration of task executions, by op and function.
+# TYPE homestar_task_duration_seconds histogram
+homestar_task_duration_seconds_bucket{op="wasm/run",function="add_two",le="0.005"} 1
+homestar_task_duration_seconds_bucket{op="wasm/run",function="add_two",le="+Inf"} 2
+homestar_task_duration_seconds_sum{op="wasm/run",function="add_two"} 0.012
+homestar_task_duration_seconds_count{op="wasm/run",function="add_two"} 2
+
+# HELP homestar_dht_quorum_failures_total The number of DHT record puts and gets that failed.
+# TYPE homestar_dht_quorum_failures_total counter
+homestar_dht_quorum_failures_total{operation="put",record="ipvm/receipt"} 1"#;
+
+        let prom_data = PrometheusData::from_string(raw_data).unwrap();
+        assert_eq!(prom_data.metrics.len(), 3);
+        assert_eq!(MetricType::Histogram, prom_data.metrics[1].metric_type);
+        assert_eq!(prom_data.metrics[1].data.len(), 1);
+        assert_eq!(
+            prom_data.metrics[2].metric_name,
+            "homestar_dht_quorum_failures_total"
+        );
+
+        let histogram = Histogram::from_raw(
+            "homestar_task_duration_seconds",
+            &raw_data.lines().skip(6).take(4).collect(),
+        )
+        .unwrap();
+        assert_eq!(histogram.count, "2");
+        assert_eq!(
+            histogram.labels,
+            Some(hashmap! {
+                "op".to_string() => "wasm/run".to_string(),
+                "function".to_string() => "add_two".to_string(),
+            })
+        );
     }
 }
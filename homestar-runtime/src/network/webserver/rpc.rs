@@ -47,6 +47,12 @@ pub(crate) const HEALTH_ENDPOINT: &str = "health";
 pub(crate) const METRICS_ENDPOINT: &str = "metrics";
 /// Node information endpoint.
 pub(crate) const NODE_INFO_ENDPOINT: &str = "node";
+/// Cancel a running or queued workflow, given its cid or local name.
+pub(crate) const CANCEL_WORKFLOW_ENDPOINT: &str = "cancel_workflow";
+/// Pause a running or queued workflow, given its cid or local name.
+pub(crate) const PAUSE_WORKFLOW_ENDPOINT: &str = "pause_workflow";
+/// Resume a paused workflow, given its cid or local name.
+pub(crate) const RESUME_WORKFLOW_ENDPOINT: &str = "resume_workflow";
 /// Run a workflow and subscribe to that workflow's events.
 #[cfg(feature = "websocket-notify")]
 pub(crate) const SUBSCRIBE_RUN_WORKFLOW_ENDPOINT: &str = "subscribe_run_workflow";
@@ -206,6 +212,36 @@ where
             }
         })?;
 
+        module.register_async_method(CANCEL_WORKFLOW_ENDPOINT, |params, ctx| async move {
+            let workflow = params.one::<String>()?;
+            Self::manage_workflow(
+                &ctx,
+                Message::CancelWorkflow(workflow.into()),
+                CANCEL_WORKFLOW_ENDPOINT,
+            )
+            .await
+        })?;
+
+        module.register_async_method(PAUSE_WORKFLOW_ENDPOINT, |params, ctx| async move {
+            let workflow = params.one::<String>()?;
+            Self::manage_workflow(
+                &ctx,
+                Message::PauseWorkflow(workflow.into()),
+                PAUSE_WORKFLOW_ENDPOINT,
+            )
+            .await
+        })?;
+
+        module.register_async_method(RESUME_WORKFLOW_ENDPOINT, |params, ctx| async move {
+            let workflow = params.one::<String>()?;
+            Self::manage_workflow(
+                &ctx,
+                Message::ResumeWorkflow(workflow.into()),
+                RESUME_WORKFLOW_ENDPOINT,
+            )
+            .await
+        })?;
+
         #[cfg(feature = "websocket-notify")]
         module.register_subscription(
             SUBSCRIBE_NETWORK_EVENTS_ENDPOINT,
@@ -232,11 +268,13 @@ where
             UNSUBSCRIBE_RUN_WORKFLOW_ENDPOINT,
             |params, pending, ctx| async move {
                 match params.one::<listener::Run<'_>>() {
-                    Ok(listener::Run { name, workflow }) => {
+                    Ok(run) => {
+                        let name = run.name();
+                        let workflow = run.workflow.workflow.clone();
                         let (tx, rx) = AsyncChannel::oneshot();
                         ctx.runner_sender
                             .send_async((
-                                Message::RunWorkflow((name.clone(), workflow.clone())),
+                                Message::RunWorkflow((name.clone(), run.workflow)),
                                 Some(tx),
                             ))
                             .await?;
@@ -281,14 +319,48 @@ where
         Ok(module)
     }
 
+    /// Send a workflow management message to the [Runner], responding with
+    /// the workflow's resulting status.
+    ///
+    /// [Runner]: crate::Runner
+    async fn manage_workflow(
+        ctx: &Context<DB>,
+        msg: Message,
+        endpoint: &str,
+    ) -> Result<serde_json::Value, ErrorObject<'static>> {
+        let (tx, rx) = crate::channel::AsyncChannel::oneshot();
+        ctx.runner_sender
+            .send_async((msg, Some(tx)))
+            .await
+            .map_err(|err| internal_err(err.to_string()))?;
+
+        match rx.recv_deadline(std::time::Instant::now() + ctx.receiver_timeout) {
+            Ok(Message::AckWorkflowStatus(status)) => {
+                serde_json::to_value(status).map_err(|err| internal_err(err.to_string()))
+            }
+            Ok(Message::WorkflowErr(err)) => Err(internal_err(err.to_string())),
+            _ => {
+                error!(
+                    subject = "call.workflow",
+                    category = "jsonrpc.call",
+                    method = endpoint,
+                    "did not acknowledge message in time"
+                );
+                Err(internal_err(format!("{endpoint} was not acknowledged")))
+            }
+        }
+    }
+
     #[cfg(feature = "websocket-notify")]
     async fn handle_event_subscription(
         mut sink: SubscriptionSink,
         mut stream: BroadcastStream<notifier::Message>,
         subscription_type: String,
     ) -> Result<()> {
+        let subscriber = crate::metrics::Subscriber::new(subscription_type.clone());
         let rt_hdl = Handle::current();
         rt_hdl.spawn(async move {
+            let _subscriber = subscriber;
             loop {
                 select! {
                     _ = sink.closed() => {
@@ -339,8 +411,10 @@ where
         mut stream: BroadcastStream<notifier::Message>,
         ctx: Arc<Context<DB>>,
     ) -> Result<()> {
+        let subscriber = crate::metrics::Subscriber::new(SUBSCRIBE_RUN_WORKFLOW_ENDPOINT);
         let rt_hdl = Handle::current();
         rt_hdl.spawn(async move {
+        let _subscriber = subscriber;
         loop {
             select! {
                 _ = sink.closed() => {
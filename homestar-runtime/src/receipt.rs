@@ -4,6 +4,7 @@
 //! [Invocation]: homestar_core::workflow::Invocation
 
 use anyhow::anyhow;
+use chrono::NaiveDateTime;
 use diesel::{
     backend::Backend,
     deserialize::{self, FromSql},
@@ -23,6 +24,12 @@ use semver::Version;
 use serde::{Deserialize, Serialize};
 use std::{collections::BTreeMap, fmt};
 
+pub(crate) mod issuer;
+pub(crate) mod stats;
+
+pub(crate) use issuer::{Signer, Verifier, VerifyError};
+pub(crate) use stats::Stats;
+
 /// General version key for receipts.
 pub const VERSION_KEY: &str = "version";
 /// [Receipt] header tag, for sharing over libp2p.
@@ -35,6 +42,8 @@ const OUT_KEY: &str = "out";
 const ISSUER_KEY: &str = "iss";
 const METADATA_KEY: &str = "meta";
 const PROOF_KEY: &str = "prf";
+/// Key for the issuer's signature within a [Receipt] capsule.
+pub(crate) const SIGNATURE_KEY: &str = "sig";
 
 /// Receipt for [Invocation], including it's own [Cid] and a [Cid] for an [Instruction].
 ///
@@ -54,6 +63,12 @@ pub struct Receipt {
     issuer: Option<Issuer>,
     prf: UcanPrf,
     version: String,
+    sig: Option<Vec<u8>>,
+    started_at: Option<NaiveDateTime>,
+    finished_at: Option<NaiveDateTime>,
+    duration: Option<i64>,
+    fuel: Option<i64>,
+    memory: Option<i64>,
 }
 
 impl fmt::Display for Receipt {
@@ -73,16 +88,24 @@ impl Receipt {
         instruction: Pointer,
         invocation_receipt: &InvocationReceipt<Ipld>,
     ) -> Self {
-        Self {
+        let mut receipt = Self {
             cid: Pointer::new(cid),
             ran: invocation_receipt.ran().to_owned(),
             instruction,
             out: invocation_receipt.out().to_owned(),
-            meta: LocalIpld(invocation_receipt.meta().to_owned()),
+            meta: LocalIpld(Ipld::Null),
             issuer: invocation_receipt.issuer().to_owned(),
             prf: invocation_receipt.prf().to_owned(),
             version: consts::INVOCATION_VERSION.to_string(),
-        }
+            sig: None,
+            started_at: None,
+            finished_at: None,
+            duration: None,
+            fuel: None,
+            memory: None,
+        };
+        receipt.set_meta(invocation_receipt.meta().to_owned());
+        receipt
     }
 
     /// Return a runtime [Receipt] given an [Instruction] [Pointer] and
@@ -98,6 +121,29 @@ impl Receipt {
         Ok(Receipt::new(cid, instruction, invocation_receipt))
     }
 
+    /// Return a runtime [Receipt] given an [Instruction] [Pointer] and
+    /// [UCAN Invocation Receipt], issued and signed by the given [Signer].
+    ///
+    /// [Instruction]: homestar_core::workflow::Instruction
+    /// [UCAN Invocation Receipt]: homestar_core::workflow::Receipt
+    pub(crate) fn try_signed(
+        instruction: Pointer,
+        invocation_receipt: InvocationReceipt<Ipld>,
+        signer: &Signer,
+    ) -> anyhow::Result<Self> {
+        let invocation_receipt = InvocationReceipt::new(
+            invocation_receipt.ran().to_owned(),
+            invocation_receipt.out().to_owned(),
+            invocation_receipt.meta().to_owned(),
+            Some(signer.issuer().to_owned()),
+            invocation_receipt.prf().to_owned(),
+        );
+        let sig = signer.sign(&instruction, &invocation_receipt)?;
+        let mut receipt = Receipt::try_with(instruction, &invocation_receipt)?;
+        receipt.sig = Some(sig);
+        Ok(receipt)
+    }
+
     /// Capsule-wrapper for [InvocationReceipt] to to be shared over libp2p as
     /// [DagCbor] encoded bytes.
     ///
@@ -119,16 +165,51 @@ impl Receipt {
         DagCborCodec.encode(&capsule)
     }
 
+    /// Capsule-wrapper for a [Receipt] to be shared over libp2p as [DagCbor]
+    /// encoded bytes, carrying its issuer's signature alongside the
+    /// [InvocationReceipt] when signed.
+    ///
+    /// [DagCbor]: DagCborCodec
+    pub fn capsule(&self) -> anyhow::Result<Vec<u8>> {
+        let receipt_ipld = Ipld::from(InvocationReceipt::from(self));
+        let capsule = if let Ipld::Map(mut map) = receipt_ipld {
+            map.insert(VERSION_KEY.into(), consts::INVOCATION_VERSION.into());
+            if let Some(sig) = &self.sig {
+                map.insert(SIGNATURE_KEY.into(), Ipld::Bytes(sig.to_owned()));
+            }
+            Ok(Ipld::Map(BTreeMap::from([(
+                RECEIPT_TAG.into(),
+                Ipld::Map(map),
+            )])))
+        } else {
+            Err(anyhow!("receipt to Ipld conversion is not a map"))
+        }?;
+
+        DagCborCodec.encode(&capsule)
+    }
+
     /// Get [Ipld] metadata on a [Receipt].
     pub fn meta(&self) -> &Ipld {
         self.meta.inner()
     }
 
-    /// Set [Ipld] metadata on a [Receipt].
+    /// Set [Ipld] metadata on a [Receipt], along with any execution
+    /// [Stats] it carries.
     pub fn set_meta(&mut self, meta: Ipld) {
+        let stats = Stats::from_meta(&meta);
+        self.started_at = stats.map(|stats| stats.started_at);
+        self.finished_at = stats.map(|stats| stats.finished_at);
+        self.duration = stats.map(|stats| stats.duration_ms());
+        self.fuel = stats.map(|stats| stats.fuel_i64());
+        self.memory = stats.map(|stats| stats.memory_i64());
         self.meta = LocalIpld(meta)
     }
 
+    /// Get execution [Stats] recorded on a [Receipt], if any.
+    pub(crate) fn stats(&self) -> Option<Stats> {
+        Stats::from_meta(self.meta.inner())
+    }
+
     /// Get unique identifier of receipt.
     pub fn cid(&self) -> Cid {
         self.cid.cid()
@@ -185,6 +266,21 @@ impl Receipt {
     pub fn version(&self) -> Result<Version, semver::Error> {
         Version::parse(&self.version)
     }
+
+    /// Get the [Issuer] of the [Receipt], if any.
+    pub fn issuer(&self) -> Option<&Issuer> {
+        self.issuer.as_ref()
+    }
+
+    /// Get the issuer's signature over the [Receipt], if signed.
+    pub fn signature(&self) -> Option<&[u8]> {
+        self.sig.as_deref()
+    }
+
+    /// Set the issuer's signature over the [Receipt].
+    pub fn set_signature(&mut self, sig: Option<Vec<u8>>) {
+        self.sig = sig
+    }
 }
 
 impl TryFrom<Receipt> for Vec<u8> {
@@ -246,6 +342,10 @@ impl From<Receipt> for Ipld {
             ),
             (PROOF_KEY.into(), receipt.prf.into()),
             (VERSION_KEY.into(), receipt.version.into()),
+            (
+                SIGNATURE_KEY.into(),
+                receipt.sig.map(Ipld::Bytes).unwrap_or(Ipld::Null),
+            ),
         ]))
     }
 }
@@ -290,17 +390,29 @@ impl TryFrom<Ipld> for Receipt {
                 .ok_or_else(|| anyhow!("missing {VERSION_KEY}"))?
                 .to_owned(),
         )?;
+        let sig = match map.get(SIGNATURE_KEY) {
+            Some(Ipld::Bytes(sig)) => Some(sig.to_owned()),
+            _ => None,
+        };
 
-        Ok(Receipt {
+        let mut receipt = Receipt {
             cid: Pointer::new(cid),
             ran,
             instruction,
             out: InstructionResult::try_from(out)?,
-            meta: LocalIpld(meta.to_owned()),
+            meta: LocalIpld(Ipld::Null),
             issuer,
             prf: UcanPrf::try_from(prf)?,
             version,
-        })
+            sig,
+            started_at: None,
+            finished_at: None,
+            duration: None,
+            fuel: None,
+            memory: None,
+        };
+        receipt.set_meta(meta.to_owned());
+        Ok(receipt)
     }
 }
 
@@ -403,7 +515,7 @@ mod test {
         assert_eq!(
             receipt.to_json_string().unwrap(),
             format!(
-                r#"{{"cid":{{"/":"{}"}},"instruction":{{"/":"{}"}},"iss":null,"meta":null,"out":["ok",true],"prf":[],"ran":{{"/":"{}"}},"version":"{}"}}"#,
+                r#"{{"cid":{{"/":"{}"}},"instruction":{{"/":"{}"}},"iss":null,"meta":null,"out":["ok",true],"prf":[],"ran":{{"/":"{}"}},"sig":null,"version":"{}"}}"#,
                 receipt.cid(),
                 receipt.instruction(),
                 receipt.ran(),
@@ -420,4 +532,74 @@ mod test {
 
         assert_eq!(receipt, from_bytes);
     }
+
+    #[test]
+    fn signed_receipt_roundtrip() {
+        let signer = Signer::new(libp2p::identity::Keypair::generate_ed25519()).unwrap();
+        let (invocation, receipt) = test_utils::receipt::receipts();
+        let signed =
+            Receipt::try_signed(receipt.instruction().to_owned(), invocation, &signer).unwrap();
+
+        assert_eq!(signed.issuer, Some(signer.issuer().to_owned()));
+        assert!(signed.signature().is_some());
+        assert_ne!(signed.cid(), receipt.cid());
+
+        let bytes: Vec<u8> = signed.clone().try_into().unwrap();
+        assert_eq!(Receipt::try_from(bytes).unwrap(), signed);
+    }
+
+    #[test]
+    fn receipt_with_stats() {
+        let mut conn = MemoryDb::setup_connection_pool(Settings::load().unwrap().node(), None)
+            .unwrap()
+            .conn()
+            .unwrap();
+        let (_, mut receipt) = test_utils::receipt::receipts();
+        assert_eq!(receipt.stats(), None);
+
+        let started_at = NaiveDateTime::from_timestamp_millis(1_700_000_000_000).unwrap();
+        let stats = Stats {
+            started_at,
+            finished_at: started_at + chrono::Duration::milliseconds(12),
+            fuel: 100,
+            memory: 1024,
+        };
+        let mut meta = BTreeMap::new();
+        stats.extend_meta(&mut meta);
+        receipt.set_meta(Ipld::Map(meta));
+        assert_eq!(receipt.stats(), Some(stats));
+
+        diesel::insert_into(schema::receipts::table)
+            .values(&receipt)
+            .execute(&mut conn)
+            .unwrap();
+        let (duration, fuel) = schema::receipts::table
+            .select((schema::receipts::duration, schema::receipts::fuel))
+            .first::<(Option<i64>, Option<i64>)>(&mut conn)
+            .unwrap();
+        assert_eq!(duration, Some(12));
+        assert_eq!(fuel, Some(100));
+
+        let bytes: Vec<u8> = receipt.clone().try_into().unwrap();
+        assert_eq!(Receipt::try_from(bytes).unwrap(), receipt);
+    }
+
+    #[test]
+    fn receipt_with_saturated_stats() {
+        let (_, mut receipt) = test_utils::receipt::receipts();
+        let started_at = NaiveDateTime::from_timestamp_millis(1_700_000_000_000).unwrap();
+        let stats = Stats {
+            started_at,
+            finished_at: started_at,
+            fuel: u64::MAX,
+            memory: i64::MAX as u64 + 1,
+        };
+        let mut meta = BTreeMap::new();
+        stats.extend_meta(&mut meta);
+        receipt.set_meta(Ipld::Map(meta));
+
+        assert_eq!(receipt.stats(), Some(stats));
+        assert_eq!(receipt.fuel, Some(i64::MAX));
+        assert_eq!(receipt.memory, Some(i64::MAX));
+    }
 }
@@ -0,0 +1,332 @@
+//! Signing of [Receipt]s with a node's libp2p [Keypair], identifying the
+//! node as a `did:key` [Issuer], and verification of [Receipt]s received
+//! from other nodes.
+
+use super::INSTRUCTION_KEY;
+use crate::{settings, Receipt};
+use anyhow::{anyhow, Result};
+use fnv::FnvHashSet;
+use homestar_core::{
+    ipld::DagCborRef,
+    workflow::{Issuer, Pointer, Receipt as InvocationReceipt},
+};
+use libipld::{
+    cbor::DagCborCodec,
+    cid::multibase::{self, Base},
+    prelude::Codec,
+    Cid, Ipld,
+};
+use libp2p::identity::{ed25519, secp256k1, Keypair, PublicKey};
+
+/// DID method prefix for `did:key` identifiers.
+const DID_KEY_PREFIX: &str = "did:key:";
+/// Varint-encoded multicodec prefix for ed25519 public keys.
+const ED25519_PUB: [u8; 2] = [0xed, 0x01];
+/// Varint-encoded multicodec prefix for (compressed) secp256k1 public keys.
+const SECP256K1_PUB: [u8; 2] = [0xe7, 0x01];
+
+/// Signs [InvocationReceipt]s on behalf of the node, as its `did:key`
+/// [Issuer].
+#[derive(Clone, Debug)]
+pub(crate) struct Signer {
+    keypair: Keypair,
+    issuer: Issuer,
+}
+
+impl Signer {
+    /// Create a [Signer] from the node's [Keypair].
+    pub(crate) fn new(keypair: Keypair) -> Result<Self> {
+        let did = did_key(&keypair.public())?;
+        let issuer = Issuer::try_from(Ipld::String(did))?;
+        Ok(Self { keypair, issuer })
+    }
+
+    /// The `did:key` [Issuer] receipts are signed as.
+    pub(crate) fn issuer(&self) -> &Issuer {
+        &self.issuer
+    }
+
+    /// Sign the DAG-CBOR encoding of an [InvocationReceipt], which is
+    /// expected to name this [Signer]'s [Issuer], bound to the [Cid] of the
+    /// [Instruction] it's a receipt for.
+    ///
+    /// [Instruction]: homestar_core::workflow::Instruction
+    pub(crate) fn sign(
+        &self,
+        instruction: &Pointer,
+        invocation_receipt: &InvocationReceipt<Ipld>,
+    ) -> Result<Vec<u8>> {
+        let bytes = signing_bytes(instruction, invocation_receipt)?;
+        self.keypair
+            .sign(&bytes)
+            .map_err(|err| anyhow!("failed to sign receipt: {err}"))
+    }
+}
+
+/// Error verifying a [Receipt] received from the network.
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub(crate) enum VerifyError {
+    /// [Receipt] carries no issuer or signature, but one is required.
+    #[error("receipt {0} is not signed")]
+    Unsigned(Cid),
+    /// [Receipt] issuer is not among the configured trusted issuers.
+    #[error("receipt {0} is issued by untrusted issuer {1}")]
+    UntrustedIssuer(Cid, String),
+    /// [Receipt] signature does not match its issuer and contents,
+    /// including the [Instruction] it's a receipt for.
+    ///
+    /// [Instruction]: homestar_core::workflow::Instruction
+    #[error("receipt {0} has an invalid signature from issuer {1}")]
+    InvalidSignature(Cid, String),
+    /// [Receipt] contents cannot be checked, e.g. its [Cid] doesn't match
+    /// or its issuer is not a `did:key`.
+    #[error("receipt {0} cannot be verified: {1}")]
+    Malformed(Cid, String),
+}
+
+/// Verifies [Receipt]s received over gossip or the DHT against their
+/// issuer's signature.
+#[derive(Clone, Debug)]
+pub(crate) struct Verifier {
+    require_signature: bool,
+    trusted_issuers: FnvHashSet<String>,
+}
+
+impl Verifier {
+    /// Create a [Verifier] from node settings.
+    pub(crate) fn new(settings: &settings::ReceiptVerification) -> Self {
+        Self {
+            require_signature: settings.require_signature,
+            trusted_issuers: settings.trusted_issuers.iter().cloned().collect(),
+        }
+    }
+
+    /// Verify a [Receipt], rejecting it if it's unsigned (when signatures
+    /// or trusted issuers are required), issued by an untrusted issuer, or
+    /// carries a signature that doesn't match.
+    ///
+    /// The signature covers the receipt's [Instruction] [Cid], so a signed
+    /// receipt re-keyed under another instruction, whether as a DHT record
+    /// or within a gossiped payload, is rejected.
+    ///
+    /// [Instruction]: homestar_core::workflow::Instruction
+    pub(crate) fn verify(&self, receipt: &Receipt) -> Result<(), VerifyError> {
+        let cid = receipt.cid();
+        let invocation_receipt = InvocationReceipt::from(receipt);
+        let computed = invocation_receipt
+            .to_cid()
+            .map_err(|err| VerifyError::Malformed(cid, err.to_string()))?;
+        if computed != cid {
+            return Err(VerifyError::Malformed(
+                cid,
+                format!("contents hash to {computed}"),
+            ));
+        }
+
+        let (issuer, sig) = match (receipt.issuer(), receipt.signature()) {
+            (Some(issuer), Some(sig)) => (issuer.to_string(), sig),
+            _ if self.require_signature || !self.trusted_issuers.is_empty() => {
+                return Err(VerifyError::Unsigned(cid))
+            }
+            _ => return Ok(()),
+        };
+
+        if !self.trusted_issuers.is_empty() && !self.trusted_issuers.contains(&issuer) {
+            return Err(VerifyError::UntrustedIssuer(cid, issuer));
+        }
+
+        let public =
+            public_key(&issuer).map_err(|err| VerifyError::Malformed(cid, err.to_string()))?;
+        let bytes = signing_bytes(receipt.instruction(), &invocation_receipt)
+            .map_err(|err| VerifyError::Malformed(cid, err.to_string()))?;
+
+        if public.verify(&bytes, sig) {
+            Ok(())
+        } else {
+            Err(VerifyError::InvalidSignature(cid, issuer))
+        }
+    }
+}
+
+/// Bytes of an [InvocationReceipt] covered by its signature, i.e. its
+/// DAG-CBOR encoding along with the [Cid] of its [Instruction].
+///
+/// [Instruction]: homestar_core::workflow::Instruction
+pub(crate) fn signing_bytes(
+    instruction: &Pointer,
+    invocation_receipt: &InvocationReceipt<Ipld>,
+) -> Result<Vec<u8>> {
+    let Ipld::Map(mut map) = Ipld::from(invocation_receipt) else {
+        return Err(anyhow!("receipt to Ipld conversion is not a map"));
+    };
+    map.insert(INSTRUCTION_KEY.into(), instruction.to_owned().into());
+    DagCborCodec.encode(&Ipld::Map(map))
+}
+
+/// Encode a libp2p [PublicKey] as a `did:key` identifier.
+pub(crate) fn did_key(public: &PublicKey) -> Result<String> {
+    let bytes = if let Ok(key) = public.clone().try_into_ed25519() {
+        [ED25519_PUB.as_slice(), key.to_bytes().as_slice()].concat()
+    } else if let Ok(key) = public.clone().try_into_secp256k1() {
+        [SECP256K1_PUB.as_slice(), key.to_bytes().as_slice()].concat()
+    } else {
+        return Err(anyhow!("unsupported key type for did:key: {public:?}"));
+    };
+
+    Ok(format!(
+        "{DID_KEY_PREFIX}{}",
+        multibase::encode(Base::Base58Btc, bytes)
+    ))
+}
+
+/// Decode a `did:key` identifier into a libp2p [PublicKey].
+pub(crate) fn public_key(did: &str) -> Result<PublicKey> {
+    let encoded = did
+        .strip_prefix(DID_KEY_PREFIX)
+        .ok_or_else(|| anyhow!("not a did:key identifier: {did}"))?;
+    let (_, bytes) = multibase::decode(encoded)?;
+
+    match bytes.split_at(ED25519_PUB.len().min(bytes.len())) {
+        (prefix, key) if prefix == ED25519_PUB => {
+            Ok(ed25519::PublicKey::try_from_bytes(key)?.into())
+        }
+        (prefix, key) if prefix == SECP256K1_PUB => {
+            Ok(secp256k1::PublicKey::try_from_bytes(key)?.into())
+        }
+        _ => Err(anyhow!("unsupported key type for did:key: {did}")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils;
+
+    #[test]
+    fn did_key_roundtrip() {
+        let ed25519 = Keypair::generate_ed25519();
+        let did = did_key(&ed25519.public()).unwrap();
+        assert!(did.starts_with("did:key:z6Mk"));
+        assert_eq!(public_key(&did).unwrap(), ed25519.public());
+
+        let secp256k1 = Keypair::generate_secp256k1();
+        let did = did_key(&secp256k1.public()).unwrap();
+        assert!(did.starts_with("did:key:zQ3s"));
+        assert_eq!(public_key(&did).unwrap(), secp256k1.public());
+
+        assert!(public_key("did:example:alice").is_err());
+    }
+
+    #[test]
+    fn sign_receipt() {
+        let keypair = Keypair::generate_ed25519();
+        let signer = Signer::new(keypair.clone()).unwrap();
+        assert_eq!(
+            signer.issuer().to_string(),
+            did_key(&keypair.public()).unwrap()
+        );
+
+        let (invocation_receipt, receipt) = test_utils::receipt::receipts();
+        let sig = signer
+            .sign(receipt.instruction(), &invocation_receipt)
+            .unwrap();
+        let bytes = signing_bytes(receipt.instruction(), &invocation_receipt).unwrap();
+        assert!(keypair.public().verify(&bytes, &sig));
+
+        let other = Pointer::new(Cid::try_from(receipt.ran()).unwrap());
+        let bytes = signing_bytes(&other, &invocation_receipt).unwrap();
+        assert!(!keypair.public().verify(&bytes, &sig));
+    }
+
+    #[test]
+    fn verify_receipt() {
+        let signer = Signer::new(Keypair::generate_ed25519()).unwrap();
+        let (invocation_receipt, unsigned) = test_utils::receipt::receipts();
+        let signed = Receipt::try_signed(
+            unsigned.instruction().to_owned(),
+            invocation_receipt,
+            &signer,
+        )
+        .unwrap();
+
+        let settings = settings::ReceiptVerification::default();
+        let verifier = Verifier::new(&settings);
+        assert!(verifier.verify(&signed).is_ok());
+        assert_eq!(
+            verifier.verify(&unsigned),
+            Err(VerifyError::Unsigned(unsigned.cid()))
+        );
+
+        let lenient = Verifier::new(&settings::ReceiptVerification {
+            require_signature: false,
+            ..Default::default()
+        });
+        assert!(lenient.verify(&unsigned).is_ok());
+
+        let mut forged = signed.clone();
+        forged.set_signature(Some(vec![0; 64]));
+        assert!(matches!(
+            lenient.verify(&forged),
+            Err(VerifyError::InvalidSignature(..))
+        ));
+
+        let trusted = Verifier::new(&settings::ReceiptVerification {
+            require_signature: false,
+            trusted_issuers: vec![signer.issuer().to_string()],
+        });
+        assert!(trusted.verify(&signed).is_ok());
+        assert!(trusted.verify(&unsigned).is_err());
+
+        let other = Signer::new(Keypair::generate_ed25519()).unwrap();
+        let (invocation_receipt, _) = test_utils::receipt::receipts();
+        let untrusted = Receipt::try_signed(
+            unsigned.instruction().to_owned(),
+            invocation_receipt,
+            &other,
+        )
+        .unwrap();
+        assert!(matches!(
+            trusted.verify(&untrusted),
+            Err(VerifyError::UntrustedIssuer(..))
+        ));
+    }
+
+    #[test]
+    fn reject_rekeyed_receipt() {
+        let signer = Signer::new(Keypair::generate_ed25519()).unwrap();
+        let (invocation_receipt, unsigned) = test_utils::receipt::receipts();
+        let signed = Receipt::try_signed(
+            unsigned.instruction().to_owned(),
+            invocation_receipt,
+            &signer,
+        )
+        .unwrap();
+        let verifier = Verifier::new(&settings::ReceiptVerification::default());
+        assert!(verifier.verify(&signed).is_ok());
+
+        // Stored under another record key, as decoded from the DHT.
+        let other = Pointer::new(Cid::try_from(signed.ran()).unwrap());
+        let mut rekeyed =
+            Receipt::try_with(other.clone(), &InvocationReceipt::from(&signed)).unwrap();
+        rekeyed.set_signature(signed.signature().map(|sig| sig.to_vec()));
+        assert_eq!(rekeyed.cid(), signed.cid());
+        assert_eq!(
+            verifier.verify(&rekeyed),
+            Err(VerifyError::InvalidSignature(
+                signed.cid(),
+                signer.issuer().to_string()
+            ))
+        );
+
+        // Carrying another instruction within a gossiped payload.
+        let Ipld::Map(mut map) = Ipld::from(signed.clone()) else {
+            panic!("receipt to Ipld conversion is not a map");
+        };
+        map.insert(INSTRUCTION_KEY.into(), other.into());
+        let rekeyed = Receipt::try_from(Ipld::Map(map)).unwrap();
+        assert!(matches!(
+            verifier.verify(&rekeyed),
+            Err(VerifyError::InvalidSignature(..))
+        ));
+    }
+}
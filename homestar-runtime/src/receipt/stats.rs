@@ -0,0 +1,107 @@
+//! Execution statistics recorded within a [Receipt]'s metadata and stored
+//! alongside it, e.g. for finding slow functions or billing.
+//!
+//! [Receipt]: crate::Receipt
+
+use chrono::NaiveDateTime;
+use homestar_core::workflow::receipt::metadata::{
+    DURATION_KEY, FINISHED_AT_KEY, FUEL_KEY, MEMORY_KEY, STARTED_AT_KEY,
+};
+use libipld::Ipld;
+use std::collections::BTreeMap;
+
+/// Timing and resource usage of a single task execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Stats {
+    /// When execution started.
+    pub(crate) started_at: NaiveDateTime,
+    /// When execution finished.
+    pub(crate) finished_at: NaiveDateTime,
+    /// Fuel consumed by execution.
+    pub(crate) fuel: u64,
+    /// Peak memory, in bytes, used by execution.
+    pub(crate) memory: u64,
+}
+
+impl Stats {
+    /// Wall-clock duration of execution, in milliseconds.
+    pub(crate) fn duration_ms(&self) -> i64 {
+        (self.finished_at - self.started_at)
+            .num_milliseconds()
+            .max(0)
+    }
+
+    /// Fuel consumed, saturated to fit a database integer column.
+    pub(crate) fn fuel_i64(&self) -> i64 {
+        i64::try_from(self.fuel).unwrap_or(i64::MAX)
+    }
+
+    /// Peak memory, in bytes, saturated to fit a database integer column.
+    pub(crate) fn memory_i64(&self) -> i64 {
+        i64::try_from(self.memory).unwrap_or(i64::MAX)
+    }
+
+    /// Read [Stats] from a receipt's [Ipld] metadata, if all are present.
+    pub(crate) fn from_meta(meta: &Ipld) -> Option<Self> {
+        let Ipld::Map(map) = meta else {
+            return None;
+        };
+        let int = |key: &str| match map.get(key) {
+            Some(Ipld::Integer(i)) => Some(*i),
+            _ => None,
+        };
+
+        Some(Self {
+            started_at: NaiveDateTime::from_timestamp_millis(
+                i64::try_from(int(STARTED_AT_KEY)?).ok()?,
+            )?,
+            finished_at: NaiveDateTime::from_timestamp_millis(
+                i64::try_from(int(FINISHED_AT_KEY)?).ok()?,
+            )?,
+            fuel: u64::try_from(int(FUEL_KEY)?).ok()?,
+            memory: u64::try_from(int(MEMORY_KEY)?).ok()?,
+        })
+    }
+
+    /// Add [Stats] to a receipt's metadata map.
+    pub(crate) fn extend_meta(&self, meta: &mut BTreeMap<String, Ipld>) {
+        meta.extend([
+            (
+                STARTED_AT_KEY.into(),
+                Ipld::from(self.started_at.timestamp_millis()),
+            ),
+            (
+                FINISHED_AT_KEY.into(),
+                Ipld::from(self.finished_at.timestamp_millis()),
+            ),
+            (DURATION_KEY.into(), Ipld::from(self.duration_ms())),
+            (FUEL_KEY.into(), Ipld::from(self.fuel)),
+            (MEMORY_KEY.into(), Ipld::from(self.memory)),
+        ]);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stats_meta_roundtrip() {
+        let started_at = NaiveDateTime::from_timestamp_millis(1_700_000_000_000).unwrap();
+        let stats = Stats {
+            started_at,
+            finished_at: started_at + chrono::Duration::milliseconds(250),
+            fuel: 42,
+            memory: 65_536,
+        };
+        assert_eq!(stats.duration_ms(), 250);
+
+        let mut meta = BTreeMap::from([("op".to_string(), Ipld::String("wasm/run".into()))]);
+        stats.extend_meta(&mut meta);
+        assert_eq!(meta.get(DURATION_KEY), Some(&Ipld::Integer(250)));
+
+        let meta = Ipld::Map(meta);
+        assert_eq!(Stats::from_meta(&meta), Some(stats));
+        assert_eq!(Stats::from_meta(&Ipld::Null), None);
+    }
+}
@@ -1,46 +1,67 @@
 //! General [Runner] interface for working across multiple workers
 //! and executing workflows.
 
+#[cfg(feature = "websocket-notify")]
+use crate::event_handler::notification::{self, WorkflowNotificationTyp};
 #[cfg(feature = "ipfs")]
 use crate::network::IpfsCli;
 use crate::{
+    blockstore::Blockstore,
     channel::{AsyncChannel, AsyncChannelReceiver, AsyncChannelSender},
-    db::Database,
+    db::{Connection, Database},
     event_handler::{Event, EventHandler},
-    network::{rpc, swarm, webserver},
+    network::{rpc, swarm, webserver, BlockExchange},
+    receipt::Signer,
     settings,
     tasks::Fetch,
     worker::WorkerMessage,
-    workflow::{self, Resource},
+    workflow::{self, Authorizer, Resource},
     Db, Receipt, Settings, Worker,
 };
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use atomic_refcell::AtomicRefCell;
 use chrono::NaiveDateTime;
 use dashmap::DashMap;
 use faststr::FastStr;
 use fnv::FnvHashSet;
-use futures::{future::poll_fn, FutureExt};
+use futures::{
+    future::{poll_fn, BoxFuture},
+    FutureExt,
+};
 use homestar_core::{workflow::Pointer, Workflow};
-use homestar_wasm::io::Arg;
+use homestar_wasm::{io::Arg, wasmtime::ComponentCache};
 use jsonrpsee::server::ServerHandle;
 use libipld::Cid;
 use metrics_exporter_prometheus::PrometheusHandle;
 #[cfg(not(test))]
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::{collections::HashMap, ops::ControlFlow, rc::Rc, sync::Arc, task::Poll, time::Instant};
+use std::sync::atomic::AtomicUsize;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    ops::ControlFlow,
+    rc::Rc,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::Poll,
+    time::{Duration, Instant},
+};
 #[cfg(not(windows))]
 use tokio::signal::unix::{signal, SignalKind};
 #[cfg(windows)]
 use tokio::signal::windows;
 use tokio::{
     runtime, select,
+    sync::Semaphore,
     task::{AbortHandle, JoinHandle},
     time,
 };
 use tokio_util::time::{delay_queue, DelayQueue};
 use tracing::{debug, error, info, warn};
 
+mod car;
 mod error;
 pub(crate) mod file;
 mod nodeinfo;
@@ -90,6 +111,36 @@ pub(crate) type WsReceiver = AsyncChannelReceiver<(
     Option<AsyncChannelSender<webserver::Message>>,
 )>;
 
+/// Worker run held in the [Runner]'s admission queue until a running worker
+/// slot frees up.
+struct QueuedWorker {
+    cid: Cid,
+    timeout: Duration,
+    run: BoxFuture<'static, Result<()>>,
+}
+
+impl fmt::Debug for QueuedWorker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueuedWorker")
+            .field("cid", &self.cid)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+/// Workflow run started by the [Runner], kept so that it can be paused and
+/// later resumed from its stored receipts.
+#[derive(Debug)]
+struct WorkflowRun {
+    workflow: Workflow<'static, Arg>,
+    settings: workflow::Settings,
+    name: FastStr,
+    /// Signal for the run's worker to stop scheduling tasks.
+    pause: Arc<AtomicBool>,
+    /// Whether the run has been paused.
+    paused: bool,
+}
+
 impl ModifiedSet for RunningTaskSet {
     fn append_or_insert(&self, cid: Cid, mut handles: Vec<AbortHandle>) {
         self.entry(cid)
@@ -106,6 +157,11 @@ impl ModifiedSet for RunningTaskSet {
 /// [Workflows]: homestar_core::Workflow
 #[derive(Debug)]
 pub struct Runner {
+    admission_queue: Rc<AtomicRefCell<VecDeque<QueuedWorker>>>,
+    authorizer: Option<Authorizer>,
+    block_exchange: BlockExchange,
+    blockstore: Blockstore,
+    component_cache: ComponentCache,
     event_sender: Arc<AsyncChannelSender<Event>>,
     expiration_queue: Rc<AtomicRefCell<DelayQueue<Cid>>>,
     node_info: StaticNodeInfo,
@@ -113,7 +169,10 @@ pub struct Runner {
     running_workers: RunningWorkerSet,
     pub(crate) runtime: tokio::runtime::Runtime,
     pub(crate) settings: Arc<Settings>,
+    signer: Signer,
+    task_permits: Arc<Semaphore>,
     webserver: Arc<webserver::Server>,
+    workflow_runs: DashMap<Cid, WorkflowRun>,
 }
 
 impl Runner {
@@ -169,10 +228,18 @@ impl Runner {
         db: impl Database + 'static,
         runtime: tokio::runtime::Runtime,
     ) -> Result<Self> {
-        let swarm = runtime.block_on(swarm::new(settings.node().network()))?;
+        let keypair = settings
+            .node()
+            .network()
+            .keypair_config
+            .keypair()
+            .with_context(|| "failed to generate/import keypair for libp2p".to_string())?;
+        let signer = Signer::new(keypair.clone())?;
+        let swarm = runtime.block_on(swarm::new(keypair, settings.node().network()))?;
         let peer_id = *swarm.local_peer_id();
 
         let webserver = webserver::Server::new(settings.node().network().webserver())?;
+        let blockstore = Blockstore::new(&settings.node.blockstore);
 
         #[cfg(feature = "websocket-notify")]
         let (ws_msg_tx, ws_evt_tx) = {
@@ -183,12 +250,31 @@ impl Runner {
         };
 
         #[cfg(feature = "websocket-notify")]
-        let event_handler =
-            EventHandler::new(swarm, db, settings.node().network(), ws_evt_tx, ws_msg_tx);
+        let event_handler = EventHandler::new(
+            swarm,
+            db,
+            blockstore.clone(),
+            settings.node().network(),
+            ws_evt_tx,
+            ws_msg_tx,
+        );
         #[cfg(not(feature = "websocket-notify"))]
-        let event_handler = EventHandler::new(swarm, db, settings.node().network());
+        let event_handler =
+            EventHandler::new(swarm, db, blockstore.clone(), settings.node().network());
 
         let event_sender = event_handler.sender();
+        let authorizer = settings.node.enforce_ucan_proofs.then(|| {
+            Authorizer::new(
+                signer.issuer().to_string(),
+                &settings.node.trusted_ucan_issuers,
+                blockstore.clone(),
+            )
+        });
+        let block_exchange = BlockExchange::new(
+            blockstore.clone(),
+            event_sender.clone(),
+            settings.node.network().libp2p().dht(),
+        );
 
         #[cfg(feature = "ipfs")]
         let _event_handler_hdl = runtime.spawn({
@@ -199,7 +285,18 @@ impl Runner {
         #[cfg(not(feature = "ipfs"))]
         let _event_handler_hdl = runtime.spawn(event_handler.start());
 
+        let task_permits = Semaphore::new(settings.node.max_concurrent_tasks.get());
+        let component_cache = ComponentCache::new(
+            settings.node.wasm_cache_dir.clone(),
+            settings.node.wasm_cache_capacity,
+        )?;
+
         Ok(Self {
+            admission_queue: Rc::new(AtomicRefCell::new(VecDeque::new())),
+            authorizer,
+            block_exchange,
+            blockstore,
+            component_cache,
             event_sender,
             expiration_queue: Rc::new(AtomicRefCell::new(DelayQueue::new())),
             node_info: StaticNodeInfo::new(peer_id),
@@ -207,7 +304,10 @@ impl Runner {
             running_workers: DashMap::new(),
             runtime,
             settings: settings.into(),
+            signer,
+            task_permits: task_permits.into(),
             webserver: webserver.into(),
+            workflow_runs: DashMap::new(),
         })
     }
 
@@ -243,6 +343,18 @@ impl Runner {
         let rpc_sender = rpc_server.sender();
         self.runtime.block_on(rpc_server.spawn())?;
 
+        if self.settings.node.resume_unfinished_workflows {
+            if let Err(err) = self
+                .runtime
+                .block_on(self.resume_unfinished_workflows(runner_worker_tx.clone(), db.clone()))
+            {
+                error!(subject = "workflow.resume.err",
+                       category = "workflow",
+                       err=?err,
+                       "failed to resume unfinished workflows");
+            }
+        }
+
         let shutdown_time_left = self.runtime.block_on(async {
             let mut gc_interval = tokio::time::interval(self.settings.node.gc_interval);
             loop {
@@ -266,7 +378,12 @@ impl Runner {
                         match handle {
                             Ok(ControlFlow::Break(())) => break now.elapsed(),
                             Ok(ControlFlow::Continue(rpc::ServerMessage::Skip)) => {},
-                            Ok(ControlFlow::Continue(msg @ rpc::ServerMessage::RunAck(_))) => {
+                            Ok(ControlFlow::Continue(
+                                msg @ (rpc::ServerMessage::RunAck(_)
+                                    | rpc::ServerMessage::ListWorkflowsAck(_)
+                                    | rpc::ServerMessage::WorkflowStatusAck(_)
+                                    | rpc::ServerMessage::WorkflowErr(_)),
+                            )) => {
                                 debug!(subject = "rpc.ack",
                                        category = "rpc",
                                        "sending message to rpc server");
@@ -284,15 +401,13 @@ impl Runner {
                     }
                     Ok(msg) = ws_receiver.recv_async() => {
                         match msg {
-                            (webserver::Message::RunWorkflow((name, workflow)), Some(oneshot_tx)) => {
+                            (webserver::Message::RunWorkflow((name, parsed)), Some(oneshot_tx)) => {
                                 info!(subject = "workflow",
                                       category = "workflow.run",
                                       "running workflow: {}", name);
-                                // TODO: Parse this from the workflow data itself.
-                                let workflow_settings = workflow::Settings::default();
-                                match self.run_worker(
-                                    workflow,
-                                    workflow_settings,
+                                crate::metrics::record_workflow_submitted();
+                                match self.run_parsed_workflow(
+                                    parsed,
                                     self.settings.node.network().libp2p().dht(),
                                     Some(name),
                                     runner_worker_tx.clone(),
@@ -314,6 +429,45 @@ impl Runner {
                                 }
 
                             }
+                            (webserver::Message::CancelWorkflow(workflow), Some(oneshot_tx)) => {
+                                let msg = match db.conn().and_then(|mut conn| self.cancel_workflow(&workflow, &mut conn)) {
+                                    Ok(status) => webserver::Message::AckWorkflowStatus(Box::new(status)),
+                                    Err(err) => {
+                                        error!(subject = "jsonrpc.err",
+                                               category = "jsonrpc",
+                                               err=?err,
+                                               "error cancelling workflow");
+                                        webserver::Message::WorkflowErr(err.into())
+                                    }
+                                };
+                                let _ = oneshot_tx.send_async(msg).await;
+                            }
+                            (webserver::Message::PauseWorkflow(workflow), Some(oneshot_tx)) => {
+                                let msg = match db.conn().and_then(|mut conn| self.pause_workflow(&workflow, &mut conn)) {
+                                    Ok(status) => webserver::Message::AckWorkflowStatus(Box::new(status)),
+                                    Err(err) => {
+                                        error!(subject = "jsonrpc.err",
+                                               category = "jsonrpc",
+                                               err=?err,
+                                               "error pausing workflow");
+                                        webserver::Message::WorkflowErr(err.into())
+                                    }
+                                };
+                                let _ = oneshot_tx.send_async(msg).await;
+                            }
+                            (webserver::Message::ResumeWorkflow(workflow), Some(oneshot_tx)) => {
+                                let msg = match self.resume_workflow(&workflow, runner_worker_tx.clone(), db.clone()).await {
+                                    Ok(status) => webserver::Message::AckWorkflowStatus(Box::new(status)),
+                                    Err(err) => {
+                                        error!(subject = "jsonrpc.err",
+                                               category = "jsonrpc",
+                                               err=?err,
+                                               "error resuming workflow");
+                                        webserver::Message::WorkflowErr(err.into())
+                                    }
+                                };
+                                let _ = oneshot_tx.send_async(msg).await;
+                            }
                             (webserver::Message::GetNodeInfo, Some(oneshot_tx)) => {
                                 debug!(subject = "jsonrpc.nodeinfo",
                                        category = "jsonrpc",
@@ -337,6 +491,9 @@ impl Runner {
                             WorkerMessage::Dropped(cid) => {
                                 let _ = self.abort_worker(cid);
                             },
+                            WorkerMessage::Paused(cid) => {
+                                let _ = self.release_paused_worker(cid);
+                            },
                         }
                     }
                     // Handle GC interval tick.
@@ -350,10 +507,16 @@ impl Runner {
                             Err(_) => Poll::Pending,
                         }
                     ) => {
+                        let cid = *expired.get_ref();
                         info!(subject = "worker.expired",
                               category = "worker",
+                              cid = cid.to_string(),
                               "worker expired, aborting");
-                        let _ = self.abort_worker(*expired.get_ref());
+                        let _ = self.abort_worker(cid);
+                        let _ = db.conn().and_then(|mut conn| {
+                            let status = workflow::Status::TimedOut;
+                            Ok(Db::update_workflow_status(cid, status, None, &mut conn)?)
+                        });
                     },
                     // Handle shutdown signal.
                     _ = Self::shutdown_signal() => {
@@ -408,7 +571,8 @@ impl Runner {
     }
 
     /// Garbage-collect task [AbortHandle]s in the [RunningTaskSet] and
-    /// workers in the [RunningWorkerSet].
+    /// workers in the [RunningWorkerSet], admitting queued workers into
+    /// freed up slots.
     #[allow(dead_code)]
     fn gc(&self) -> Result<()> {
         self.running_tasks.retain(|_cid, handles| {
@@ -416,21 +580,104 @@ impl Runner {
             !handles.is_empty()
         });
 
-        let mut expiration_q = self
-            .expiration_queue
-            .try_borrow_mut()
-            .map_err(|e| anyhow!("failed to borrow expiration queue: {e}"))?;
+        {
+            let mut expiration_q = self
+                .expiration_queue
+                .try_borrow_mut()
+                .map_err(|e| anyhow!("failed to borrow expiration queue: {e}"))?;
 
-        for worker in self.running_workers.iter_mut() {
-            let (handle, delay_key) = worker.value();
-            if handle.is_finished() {
-                let _ = expiration_q.try_remove(delay_key);
+            for worker in self.running_workers.iter_mut() {
+                let (handle, delay_key) = worker.value();
+                if handle.is_finished() {
+                    let _ = expiration_q.try_remove(delay_key);
+                }
             }
         }
 
         self.running_workers
             .retain(|_cid, (handle, _delay_key)| !handle.is_finished());
 
+        {
+            let queue = self
+                .admission_queue
+                .try_borrow()
+                .map_err(|e| anyhow!("failed to borrow admission queue: {e}"))?;
+
+            // Paused runs are kept around until they're resumed.
+            self.workflow_runs.retain(|cid, run| {
+                run.paused
+                    || self.running_workers.contains_key(cid)
+                    || queue.iter().any(|worker| worker.cid == *cid)
+            });
+        }
+
+        self.admit_queued_workers()
+    }
+
+    /// Spawn a worker if there's a free running worker slot, otherwise
+    /// holding it in the admission queue.
+    ///
+    /// Returns `true` if the worker was queued.
+    fn admit_worker(&self, worker: QueuedWorker) -> Result<bool> {
+        if self.running_workers.len() < self.settings.node.max_running_workers.get() {
+            self.spawn_worker(worker)?;
+            Ok(false)
+        } else {
+            info!(
+                subject = "worker.queued",
+                category = "worker",
+                cid = worker.cid.to_string(),
+                "max running workers reached, queueing workflow"
+            );
+            self.admission_queue
+                .try_borrow_mut()
+                .map_err(|e| anyhow!("failed to borrow admission queue: {e}"))?
+                .push_back(worker);
+            Ok(true)
+        }
+    }
+
+    /// Spawn queued workers, in order, into free running worker slots.
+    fn admit_queued_workers(&self) -> Result<()> {
+        while self.running_workers.len() < self.settings.node.max_running_workers.get() {
+            let next = self
+                .admission_queue
+                .try_borrow_mut()
+                .map_err(|e| anyhow!("failed to borrow admission queue: {e}"))?
+                .pop_front();
+
+            match next {
+                Some(worker) => {
+                    info!(
+                        subject = "worker.admitted",
+                        category = "worker",
+                        cid = worker.cid.to_string(),
+                        "starting queued workflow"
+                    );
+                    self.spawn_worker(worker)?
+                }
+                None => break,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Spawn a worker run, tracking it in the [RunningWorkerSet] and
+    /// expiration queue.
+    fn spawn_worker(&self, worker: QueuedWorker) -> Result<()> {
+        let handle = self.runtime.spawn(worker.run);
+
+        // Add Cid to expirations timing wheel
+        let delay_key = self
+            .expiration_queue
+            .try_borrow_mut()
+            .map_err(|e| anyhow!("failed to borrow expiration queue: {e}"))?
+            .insert(worker.cid, worker.timeout);
+
+        // Insert handle into running workers map
+        self.running_workers.insert(worker.cid, (handle, delay_key));
+
         Ok(())
     }
 
@@ -443,9 +690,12 @@ impl Runner {
         Ok(())
     }
 
-    /// Abort all workers.
+    /// Abort all workers, dropping those still queued.
     #[allow(dead_code)]
     fn abort_workers(&self) {
+        if let Ok(mut queue) = self.admission_queue.try_borrow_mut() {
+            queue.clear();
+        }
         self.running_workers.iter_mut().for_each(|data| {
             let (handle, _delay_key) = data.value();
             handle.abort()
@@ -483,21 +733,45 @@ impl Runner {
     }
 
     /// Aborts and removes a specific worker's [JoinHandle] and
-    /// set of task [AbortHandle]s given a [Cid].
+    /// set of task [AbortHandle]s given a [Cid], admitting a queued worker
+    /// into its slot.
     #[allow(dead_code)]
     fn abort_worker(&self, cid: Cid) -> Result<()> {
-        let mut expiration_q = self
-            .expiration_queue
-            .try_borrow_mut()
-            .map_err(|e| anyhow!("failed to borrow expiration queue: {e}"))?;
+        {
+            let mut expiration_q = self
+                .expiration_queue
+                .try_borrow_mut()
+                .map_err(|e| anyhow!("failed to borrow expiration queue: {e}"))?;
 
-        if let Some((cid, (handle, delay_key))) = self.running_workers.remove(&cid) {
-            let _ = expiration_q.try_remove(&delay_key);
-            handle.abort();
-            self.abort_worker_tasks(cid);
+            if let Some((cid, (handle, delay_key))) = self.running_workers.remove(&cid) {
+                let _ = expiration_q.try_remove(&delay_key);
+                handle.abort();
+                self.abort_worker_tasks(cid);
+            }
         }
+        self.workflow_runs.remove(&cid);
 
-        Ok(())
+        self.admit_queued_workers()
+    }
+
+    /// Free up the running worker slot of a paused workflow, whose worker
+    /// has stopped, keeping its run to be resumed.
+    ///
+    /// The worker's expiration is dropped along with it, so the paused
+    /// workflow can't time out.
+    fn release_paused_worker(&self, cid: Cid) -> Result<()> {
+        // A resumed run has a new worker, which is left running.
+        if self.workflow_runs.get(&cid).map_or(false, |run| run.paused) {
+            if let Some((_cid, (_handle, delay_key))) = self.running_workers.remove(&cid) {
+                let _ = self
+                    .expiration_queue
+                    .try_borrow_mut()
+                    .map_err(|e| anyhow!("failed to borrow expiration queue: {e}"))?
+                    .try_remove(&delay_key);
+            }
+        }
+
+        self.admit_queued_workers()
     }
 
     /// Abort a specific worker's tasks given a [Cid].
@@ -509,6 +783,321 @@ impl Runner {
         }
     }
 
+    /// Status of all workflows stored in the database, most recently
+    /// created first.
+    fn list_workflows(&self, conn: &mut Connection) -> Result<response::WorkflowList> {
+        Db::select_workflows(conn)?
+            .into_iter()
+            .map(|stored| self.status_of(stored, conn))
+            .collect::<Result<Vec<_>>>()
+            .map(response::WorkflowList)
+    }
+
+    /// Status of a workflow, given its [Cid] or local name.
+    fn workflow_status(
+        &self,
+        workflow: &str,
+        conn: &mut Connection,
+    ) -> Result<response::WorkflowStatus> {
+        let stored = Self::find_workflow(workflow, conn)?;
+        self.status_of(stored, conn)
+    }
+
+    /// Cancel a running or queued workflow, given its [Cid] or local name,
+    /// aborting its worker and tasks and recording it as cancelled.
+    ///
+    /// Subscribers to the workflow are notified of the cancellation.
+    fn cancel_workflow(
+        &self,
+        workflow: &str,
+        conn: &mut Connection,
+    ) -> Result<response::WorkflowStatus> {
+        let stored = Self::find_workflow(workflow, conn)?;
+        let cid = stored.cid.cid();
+
+        let dequeued = {
+            let mut queue = self
+                .admission_queue
+                .try_borrow_mut()
+                .map_err(|e| anyhow!("failed to borrow admission queue: {e}"))?;
+            let len = queue.len();
+            queue.retain(|worker| worker.cid != cid);
+            queue.len() < len
+        };
+        if !dequeued
+            && !self.running_workers.contains_key(&cid)
+            && !self.workflow_runs.contains_key(&cid)
+            && stored.status != workflow::Status::Paused
+        {
+            bail!("workflow {cid} is not running");
+        }
+
+        info!(
+            subject = "worker.cancel",
+            category = "worker",
+            cid = cid.to_string(),
+            "cancelling workflow"
+        );
+        self.abort_worker(cid)?;
+        Db::update_workflow_status(cid, workflow::Status::Cancelled, None, conn)?;
+
+        #[cfg(feature = "websocket-notify")]
+        notification::emit_workflow(
+            self.webserver.workflow_msg_notifier(),
+            cid,
+            WorkflowNotificationTyp::Cancelled,
+        );
+
+        let stored = Db::select_workflow(cid, conn)?;
+        self.status_of(stored, conn)
+    }
+
+    /// Pause a running or queued workflow, given its [Cid] or local name.
+    ///
+    /// Its worker lets in-flight tasks complete, storing their receipts,
+    /// but schedules no further tasks. Queued workflows are held back from
+    /// admission.
+    ///
+    /// Subscribers to the workflow are notified that it's paused.
+    fn pause_workflow(
+        &self,
+        workflow: &str,
+        conn: &mut Connection,
+    ) -> Result<response::WorkflowStatus> {
+        let stored = Self::find_workflow(workflow, conn)?;
+        let cid = stored.cid.cid();
+
+        {
+            let mut run = self
+                .workflow_runs
+                .get_mut(&cid)
+                .ok_or_else(|| anyhow!("workflow {cid} is not running"))?;
+            if run.paused {
+                bail!("workflow {cid} is already paused");
+            }
+            run.paused = true;
+            run.pause.store(true, Ordering::Release);
+        }
+
+        self.admission_queue
+            .try_borrow_mut()
+            .map_err(|e| anyhow!("failed to borrow admission queue: {e}"))?
+            .retain(|worker| worker.cid != cid);
+        Db::update_workflow_status(cid, workflow::Status::Paused, None, conn)?;
+
+        info!(
+            subject = "worker.pause",
+            category = "worker",
+            cid = cid.to_string(),
+            "pausing workflow"
+        );
+
+        #[cfg(feature = "websocket-notify")]
+        notification::emit_workflow(
+            self.webserver.workflow_msg_notifier(),
+            cid,
+            WorkflowNotificationTyp::Paused,
+        );
+
+        let stored = Db::select_workflow(cid, conn)?;
+        self.status_of(stored, conn)
+    }
+
+    /// Resume a paused workflow, given its [Cid] or local name.
+    ///
+    /// A new worker is started for the workflow, whose [TaskScheduler]
+    /// picks up from the receipts stored before it was paused. Workflows
+    /// paused before the node restarted are started from their stored body.
+    ///
+    /// Subscribers to the workflow are notified that it's resumed.
+    ///
+    /// [TaskScheduler]: crate::TaskScheduler
+    async fn resume_workflow(
+        &self,
+        workflow: &str,
+        runner_sender: AsyncChannelSender<WorkerMessage>,
+        db: impl Database + 'static,
+    ) -> Result<response::WorkflowStatus> {
+        let stored = Self::find_workflow(workflow, &mut db.conn()?)?;
+        let cid = stored.cid.cid();
+
+        // Clear out the paused worker, if it's done, so it won't expire
+        // the resumed one.
+        if let Some((_cid, (_handle, delay_key))) = self
+            .running_workers
+            .remove_if(&cid, |_cid, (handle, _delay_key)| handle.is_finished())
+        {
+            let _ = self
+                .expiration_queue
+                .try_borrow_mut()
+                .map_err(|e| anyhow!("failed to borrow expiration queue: {e}"))?
+                .try_remove(&delay_key);
+        }
+        if self.running_workers.contains_key(&cid) {
+            bail!("workflow {cid} is still completing its in-flight tasks");
+        }
+
+        let run = self
+            .workflow_runs
+            .remove_if(&cid, |_cid, run| run.paused)
+            .map(|(_cid, run)| run);
+        let (workflow, settings, name) = match &run {
+            Some(run) => (
+                run.workflow.clone(),
+                run.settings.clone(),
+                Some(run.name.clone()),
+            ),
+            None if stored.status == workflow::Status::Paused
+                && !self.workflow_runs.contains_key(&cid) =>
+            {
+                let body = stored
+                    .body
+                    .clone()
+                    .ok_or_else(|| anyhow!("workflow {cid} has no stored body to resume"))?;
+                let parsed = file::EncodedWorkflow::from_dag_cbor(body).validate_and_parse()?;
+                (
+                    parsed.workflow,
+                    parsed.settings,
+                    stored.name.clone().map(FastStr::from),
+                )
+            }
+            None => bail!("workflow {cid} is not paused"),
+        };
+
+        info!(
+            subject = "worker.resume",
+            category = "worker",
+            cid = cid.to_string(),
+            "resuming workflow"
+        );
+
+        if let Err(err) = self
+            .run_worker(
+                workflow,
+                settings,
+                self.settings.node.network().libp2p().dht(),
+                name,
+                runner_sender,
+                db.clone(),
+            )
+            .await
+        {
+            if let Some(run) = run {
+                self.workflow_runs.insert(cid, run);
+            }
+            let status = workflow::Status::Paused;
+            let _ = Db::update_workflow_status(cid, status, None, &mut db.conn()?);
+            return Err(err);
+        }
+
+        #[cfg(feature = "websocket-notify")]
+        notification::emit_workflow(
+            self.webserver.workflow_msg_notifier(),
+            cid,
+            WorkflowNotificationTyp::Resumed,
+        );
+
+        let stored = Db::select_workflow(cid, &mut db.conn()?)?;
+        self.status_of(stored, &mut db.conn()?)
+    }
+
+    /// Resume workflows left unfinished when the node last stopped, i.e.
+    /// neither completed nor cancelled, with receipts outstanding.
+    ///
+    /// Each workflow's worker is started from its stored body, and its
+    /// [TaskScheduler] picks up from the receipts stored before the node
+    /// stopped. Workflows that fail to resume are logged and skipped.
+    ///
+    /// [TaskScheduler]: crate::TaskScheduler
+    async fn resume_unfinished_workflows(
+        &self,
+        runner_sender: AsyncChannelSender<WorkerMessage>,
+        db: impl Database + 'static,
+    ) -> Result<()> {
+        for stored in Db::select_unfinished_workflows(&mut db.conn()?)? {
+            let cid = stored.cid.cid();
+            let (_name, info) = Db::get_workflow_info(cid, &mut db.conn()?)?;
+            if info.progress_count >= info.num_tasks {
+                continue;
+            }
+            let Some(body) = stored.body else {
+                continue;
+            };
+
+            let resumed = async {
+                let parsed = file::EncodedWorkflow::from_dag_cbor(body).validate_and_parse()?;
+                self.run_worker(
+                    parsed.workflow,
+                    parsed.settings,
+                    self.settings.node.network().libp2p().dht(),
+                    stored.name,
+                    runner_sender.clone(),
+                    db.clone(),
+                )
+                .await
+            }
+            .await;
+
+            match resumed {
+                Ok(_) => info!(
+                    subject = "workflow.resume",
+                    category = "workflow",
+                    cid = cid.to_string(),
+                    "resuming unfinished workflow"
+                ),
+                Err(err) => warn!(
+                    subject = "workflow.resume.err",
+                    category = "workflow",
+                    cid = cid.to_string(),
+                    err=?err,
+                    "failed to resume unfinished workflow"
+                ),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Find a stored workflow given its [Cid] or local name.
+    ///
+    /// Workflows looked up by name resolve to the most recently created
+    /// workflow with that name.
+    fn find_workflow(workflow: &str, conn: &mut Connection) -> Result<workflow::Stored> {
+        match Cid::from_str(workflow) {
+            Ok(cid) => Db::select_workflow(cid, conn),
+            Err(_) => Db::select_workflow_by_name(workflow, conn),
+        }
+        .map_err(|_| anyhow!("no workflow found for {workflow}"))
+    }
+
+    /// Status of a [workflow::Stored] workflow, including whether it's
+    /// held by a running worker or waiting in the admission queue.
+    fn status_of(
+        &self,
+        stored: workflow::Stored,
+        conn: &mut Connection,
+    ) -> Result<response::WorkflowStatus> {
+        let cid = stored.cid.cid();
+        let (_name, info) = Db::get_workflow_info(cid, conn)?;
+        let queued = self
+            .admission_queue
+            .try_borrow()
+            .map_err(|e| anyhow!("failed to borrow admission queue: {e}"))?
+            .iter()
+            .any(|worker| worker.cid == cid);
+
+        let paused = self.workflow_runs.get(&cid).map_or(false, |run| run.paused)
+            || stored.status == workflow::Status::Paused;
+
+        Ok(response::WorkflowStatus::new(
+            info,
+            &stored,
+            self.running_workers.contains_key(&cid),
+            queued,
+            paused,
+        ))
+    }
+
     /// Captures shutdown signals for [Runner].
     #[allow(dead_code)]
     #[cfg(not(windows))]
@@ -628,16 +1217,15 @@ impl Runner {
                     }
                 }
             }
-            rpc::ServerMessage::Run((name, workflow_file)) => {
-                let (workflow, workflow_settings) =
-                    workflow_file.validate_and_parse().await.with_context(|| {
-                        format!("failed to validate/parse workflow @ path: {workflow_file}",)
-                    })?;
+            rpc::ServerMessage::Run((name, workflow)) => {
+                crate::metrics::record_workflow_submitted();
+                let parsed = workflow
+                    .validate_and_parse()
+                    .with_context(|| "failed to validate/parse workflow".to_string())?;
 
                 let data = self
-                    .run_worker(
-                        workflow,
-                        workflow_settings,
+                    .run_parsed_workflow(
+                        parsed,
                         network_settings,
                         name,
                         channels.runner,
@@ -649,11 +1237,51 @@ impl Runner {
                     response::AckWorkflow::new(
                         data.info,
                         data.replayed_receipt_info,
+                        data.external_promises,
                         data.name,
                         data.timestamp,
+                        data.queued,
                     ),
                 ))))
             }
+            rpc::ServerMessage::ListWorkflows => {
+                let msg = match self.list_workflows(&mut db.conn()?) {
+                    Ok(list) => rpc::ServerMessage::ListWorkflowsAck(list),
+                    Err(err) => rpc::ServerMessage::WorkflowErr(err.into()),
+                };
+                Ok(ControlFlow::Continue(msg))
+            }
+            rpc::ServerMessage::WorkflowStatus(workflow) => {
+                let msg = match self.workflow_status(&workflow, &mut db.conn()?) {
+                    Ok(status) => rpc::ServerMessage::WorkflowStatusAck(Box::new(status)),
+                    Err(err) => rpc::ServerMessage::WorkflowErr(err.into()),
+                };
+                Ok(ControlFlow::Continue(msg))
+            }
+            rpc::ServerMessage::CancelWorkflow(workflow) => {
+                let msg = match self.cancel_workflow(&workflow, &mut db.conn()?) {
+                    Ok(status) => rpc::ServerMessage::WorkflowStatusAck(Box::new(status)),
+                    Err(err) => rpc::ServerMessage::WorkflowErr(err.into()),
+                };
+                Ok(ControlFlow::Continue(msg))
+            }
+            rpc::ServerMessage::PauseWorkflow(workflow) => {
+                let msg = match self.pause_workflow(&workflow, &mut db.conn()?) {
+                    Ok(status) => rpc::ServerMessage::WorkflowStatusAck(Box::new(status)),
+                    Err(err) => rpc::ServerMessage::WorkflowErr(err.into()),
+                };
+                Ok(ControlFlow::Continue(msg))
+            }
+            rpc::ServerMessage::ResumeWorkflow(workflow) => {
+                let msg = match self
+                    .resume_workflow(&workflow, channels.runner, db.clone())
+                    .await
+                {
+                    Ok(status) => rpc::ServerMessage::WorkflowStatusAck(Box::new(status)),
+                    Err(err) => rpc::ServerMessage::WorkflowErr(err.into()),
+                };
+                Ok(ControlFlow::Continue(msg))
+            }
             msg => {
                 warn!(
                     subject = "rpc.command",
@@ -666,15 +1294,71 @@ impl Runner {
         }
     }
 
+    /// Run a [ParsedWorkflow] with its own settings, after loading its
+    /// bundled proofs, instructions and blocks into the local store, where
+    /// they're resolved from when scheduling and authorizing the workflow's
+    /// tasks.
+    ///
+    /// A name given on submission takes precedence over the workflow's own.
+    ///
+    /// [ParsedWorkflow]: file::ParsedWorkflow
+    async fn run_parsed_workflow(
+        &self,
+        parsed: file::ParsedWorkflow<'static>,
+        network_settings: &settings::Dht,
+        name: Option<FastStr>,
+        runner_sender: AsyncChannelSender<WorkerMessage>,
+        db: impl Database + 'static,
+    ) -> Result<WorkflowData> {
+        if !parsed.proofs.is_empty() {
+            Db::store_proofs(parsed.proofs, &mut db.conn()?)?;
+        }
+        for instruction in parsed.instructions.into_iter() {
+            Db::store_instruction(instruction, &mut db.conn()?)?;
+        }
+        for (cid, block) in parsed.blocks.iter() {
+            self.blockstore.put(*cid, block).await?;
+        }
+
+        self.run_worker(
+            parsed.workflow,
+            parsed.settings,
+            network_settings,
+            name.or(parsed.name.map(FastStr::from)),
+            runner_sender,
+            db,
+        )
+        .await
+    }
+
     async fn run_worker<S: Into<FastStr>>(
         &self,
         workflow: Workflow<'static, Arg>,
-        workflow_settings: workflow::Settings,
+        mut workflow_settings: workflow::Settings,
         network_settings: &settings::Dht,
         name: Option<S>,
         runner_sender: AsyncChannelSender<WorkerMessage>,
         db: impl Database + 'static,
     ) -> Result<WorkflowData> {
+        // Resolve tasks given as instruction pointers before scheduling.
+        let pointers = workflow::instruction::pointers(&workflow);
+        if !pointers.is_empty() {
+            workflow::StoredInstruction::resolve(
+                pointers,
+                db.clone(),
+                network_settings,
+                self.event_sender(),
+                #[cfg(feature = "ipfs")]
+                IpfsCli::new(self.settings.node.network.ipfs())?,
+            )
+            .await?;
+        }
+
+        workflow_settings
+            .scheduler_mode
+            .get_or_insert(self.settings.node.scheduler_mode);
+        let resumable = (workflow.clone(), workflow_settings.clone());
+
         let worker = {
             Worker::new(
                 workflow,
@@ -683,6 +1367,10 @@ impl Runner {
                 name,
                 self.event_sender(),
                 runner_sender,
+                self.task_permits.clone(),
+                self.component_cache.clone(),
+                self.signer.clone(),
+                self.authorizer.clone(),
                 db.clone(),
             )
             .await?
@@ -695,6 +1383,15 @@ impl Runner {
         let workflow_name = worker.workflow_name.clone();
         let workflow_settings = worker.workflow_settings.clone();
         let timestamp = worker.workflow_started;
+        let external_promises = worker.graph.external_promises.clone();
+        let pause = Arc::clone(&worker.paused);
+
+        // Persist the workflow, so it can be resumed after a restart, and
+        // queue it to run.
+        let (workflow, settings) = resumable;
+        let body = file::EncodedWorkflow::try_with(workflow.clone(), &settings)?;
+        Db::store_workflow_body(initial_info.cid, body.into_bytes(), &mut db.conn()?)?;
+        Db::queue_workflow(initial_info.cid, &mut db.conn()?)?;
 
         // Spawn worker, which initializees the scheduler and runs
         // the workflow.
@@ -719,30 +1416,47 @@ impl Runner {
         let fetch_fn = {
             let settings = Arc::clone(&self.settings);
             let ipfs = IpfsCli::new(settings.node.network.ipfs())?;
+            let files = settings.node.file_resources_dir.clone();
+            let limits = settings.node.resource_limits;
+            let exchange = self.block_exchange.clone();
             move |rscs: FnvHashSet<Resource>| {
-                async move { Fetch::get_resources(rscs, workflow_settings, ipfs).await }.boxed()
+                async move {
+                    Fetch::get_resources(rscs, workflow_settings, files, limits, exchange, ipfs)
+                        .await
+                }
+                .boxed()
             }
         };
 
         #[cfg(not(feature = "ipfs"))]
-        let fetch_fn = |rscs: FnvHashSet<Resource>| {
-            async move { Fetch::get_resources(rscs, workflow_settings).await }.boxed()
+        let fetch_fn = {
+            let files = self.settings.node.file_resources_dir.clone();
+            let limits = self.settings.node.resource_limits;
+            let exchange = self.block_exchange.clone();
+            move |rscs: FnvHashSet<Resource>| {
+                async move {
+                    Fetch::get_resources(rscs, workflow_settings, files, limits, exchange).await
+                }
+                .boxed()
+            }
         };
 
-        let handle = self
-            .runtime
-            .spawn(worker.run(self.running_tasks(), fetch_fn));
+        let queued = self.admit_worker(QueuedWorker {
+            cid: initial_info.cid,
+            timeout: workflow_timeout,
+            run: worker.run(self.running_tasks(), fetch_fn).boxed(),
+        })?;
 
-        // Add Cid to expirations timing wheel
-        let delay_key = self
-            .expiration_queue
-            .try_borrow_mut()
-            .map_err(|e| anyhow!("failed to borrow expiration queue: {e}"))?
-            .insert(initial_info.cid, workflow_timeout);
-
-        // Insert handle into running workers map
-        self.running_workers
-            .insert(initial_info.cid, (handle, delay_key));
+        self.workflow_runs.insert(
+            initial_info.cid,
+            WorkflowRun {
+                workflow,
+                settings,
+                name: workflow_name.clone(),
+                pause,
+                paused: false,
+            },
+        );
 
         // Gather receipt info
         let receipt_pointers = initial_info
@@ -757,6 +1471,8 @@ impl Runner {
             name: workflow_name,
             timestamp,
             replayed_receipt_info,
+            external_promises,
+            queued,
         })
     }
 }
@@ -790,6 +1506,8 @@ struct WorkflowData {
     name: FastStr,
     timestamp: NaiveDateTime,
     replayed_receipt_info: Vec<WorkflowReceiptInfo>,
+    external_promises: Vec<Cid>,
+    queued: bool,
 }
 
 #[derive(Debug)]
@@ -805,9 +1523,13 @@ mod test {
         network::rpc::Client,
         test_utils::{db::MemoryDb, WorkerBuilder},
     };
-    use homestar_core::test_utils as core_test_utils;
+    use homestar_core::{
+        test_utils as core_test_utils,
+        workflow::{config::Resources, instruction::RunInstruction, prf::UcanPrf, Task},
+    };
+    use libipld::multihash::{Code, MultihashDigest};
     use rand::thread_rng;
-    use std::net::SocketAddr;
+    use std::{net::SocketAddr, num::NonZeroUsize};
     use tarpc::context;
     use tokio::net::TcpStream;
 
@@ -956,6 +1678,409 @@ mod test {
         assert!(runner.running_tasks.is_empty());
     }
 
+    #[homestar_runtime_proc_macro::runner_test]
+    fn queue_workers_over_max_running() {
+        let TestRunner {
+            mut runner,
+            settings,
+        } = TestRunner::start();
+        Arc::make_mut(&mut runner.settings).node.max_running_workers =
+            NonZeroUsize::new(1).unwrap();
+
+        let (first, second) = runner.runtime.block_on(async {
+            let builder = WorkerBuilder::new(settings.node.clone());
+            let fetch_fn = builder.fetch_fn();
+            let worker = builder.build().await;
+            let first = QueuedWorker {
+                cid: worker.workflow_info.cid,
+                timeout: worker.workflow_settings.timeout,
+                run: worker.run(runner.running_tasks(), fetch_fn).boxed(),
+            };
+
+            let (instruction, _) = core_test_utils::workflow::wasm_instruction_with_nonce::<Arg>();
+            let builder = WorkerBuilder::new(settings.node).with_tasks(vec![Task::new(
+                RunInstruction::Expanded(instruction),
+                Resources::default().into(),
+                UcanPrf::default(),
+            )]);
+            let fetch_fn = builder.fetch_fn();
+            let worker = builder.build().await;
+            let second = QueuedWorker {
+                cid: worker.workflow_info.cid,
+                timeout: worker.workflow_settings.timeout,
+                run: worker.run(runner.running_tasks(), fetch_fn).boxed(),
+            };
+
+            (first, second)
+        });
+
+        let (first_cid, second_cid) = (first.cid, second.cid);
+        let guard = runner.runtime.enter();
+        assert!(!runner.admit_worker(first).unwrap());
+        assert!(runner.admit_worker(second).unwrap());
+        assert!(runner.running_workers.contains_key(&first_cid));
+        assert!(!runner.running_workers.contains_key(&second_cid));
+        assert_eq!(runner.admission_queue.try_borrow().unwrap().len(), 1);
+
+        // Freeing up the running slot admits the queued worker.
+        runner.abort_worker(first_cid).unwrap();
+        assert!(runner.running_workers.contains_key(&second_cid));
+        assert!(runner.admission_queue.try_borrow().unwrap().is_empty());
+        drop(guard);
+
+        runner.runtime.block_on(async {
+            let (_, (handle, _)) = runner.running_workers.remove(&second_cid).unwrap();
+            handle.await.unwrap().unwrap();
+        });
+    }
+
+    #[homestar_runtime_proc_macro::runner_test]
+    fn release_paused_worker_slot() {
+        let TestRunner {
+            mut runner,
+            settings,
+        } = TestRunner::start();
+        Arc::make_mut(&mut runner.settings).node.max_running_workers =
+            NonZeroUsize::new(1).unwrap();
+
+        let (paused, queued) = runner.runtime.block_on(async {
+            let builder = WorkerBuilder::new(settings.node.clone());
+            let workflow = builder.workflow();
+            let fetch_fn = builder.fetch_fn();
+            let worker = builder.build().await;
+            let pause = Arc::clone(&worker.paused);
+            pause.store(true, Ordering::Release);
+            runner.workflow_runs.insert(
+                worker.workflow_info.cid,
+                WorkflowRun {
+                    workflow,
+                    settings: workflow::Settings::default(),
+                    name: worker.workflow_info.cid.to_string().into(),
+                    pause,
+                    paused: true,
+                },
+            );
+            let paused = QueuedWorker {
+                cid: worker.workflow_info.cid,
+                timeout: worker.workflow_settings.timeout,
+                run: worker.run(runner.running_tasks(), fetch_fn).boxed(),
+            };
+
+            let (instruction, _) = core_test_utils::workflow::wasm_instruction_with_nonce::<Arg>();
+            let builder = WorkerBuilder::new(settings.node).with_tasks(vec![Task::new(
+                RunInstruction::Expanded(instruction),
+                Resources::default().into(),
+                UcanPrf::default(),
+            )]);
+            let fetch_fn = builder.fetch_fn();
+            let worker = builder.build().await;
+            let queued = QueuedWorker {
+                cid: worker.workflow_info.cid,
+                timeout: worker.workflow_settings.timeout,
+                run: worker.run(runner.running_tasks(), fetch_fn).boxed(),
+            };
+
+            (paused, queued)
+        });
+
+        let (paused_cid, queued_cid) = (paused.cid, queued.cid);
+        let guard = runner.runtime.enter();
+        assert!(!runner.admit_worker(paused).unwrap());
+        assert!(runner.admit_worker(queued).unwrap());
+
+        // The paused worker's slot and expiration go to the queued worker,
+        // and its run is kept to be resumed.
+        runner.release_paused_worker(paused_cid).unwrap();
+        assert!(!runner.running_workers.contains_key(&paused_cid));
+        assert!(runner.running_workers.contains_key(&queued_cid));
+        assert_eq!(runner.expiration_queue.try_borrow().unwrap().len(), 1);
+        assert!(runner.workflow_runs.get(&paused_cid).unwrap().paused);
+
+        // Running workers of runs that aren't paused are left alone.
+        runner.release_paused_worker(queued_cid).unwrap();
+        assert!(runner.running_workers.contains_key(&queued_cid));
+        drop(guard);
+
+        runner.runtime.block_on(async {
+            let (_, (handle, _)) = runner.running_workers.remove(&queued_cid).unwrap();
+            handle.await.unwrap().unwrap();
+        });
+    }
+
+    #[homestar_runtime_proc_macro::runner_test]
+    fn cancel_running_workflow() {
+        let TestRunner { runner, settings } = TestRunner::start();
+
+        let (workflow_cid, db) = runner.runtime.block_on(async {
+            let builder = WorkerBuilder::new(settings.node);
+            let db = builder.db();
+            let fetch_fn = builder.fetch_fn();
+            let worker = builder.build().await;
+            let workflow_cid = worker.workflow_info.cid;
+            let workflow_timeout = worker.workflow_settings.timeout;
+            let handle = runner
+                .runtime
+                .spawn(worker.run(runner.running_tasks(), fetch_fn));
+            let delay_key = runner
+                .expiration_queue
+                .try_borrow_mut()
+                .unwrap()
+                .insert(workflow_cid, workflow_timeout);
+            runner
+                .running_workers
+                .insert(workflow_cid, (handle, delay_key));
+
+            (workflow_cid, db)
+        });
+
+        let mut conn = db.conn().unwrap();
+        let status = runner
+            .workflow_status(&workflow_cid.to_string(), &mut conn)
+            .unwrap();
+        assert!(status.running);
+        assert!(!status.status.is_finished());
+
+        let guard = runner.runtime.enter();
+        let status = runner
+            .cancel_workflow(&workflow_cid.to_string(), &mut conn)
+            .unwrap();
+        drop(guard);
+        assert!(!status.running);
+        assert_eq!(status.status, workflow::Status::Cancelled);
+        assert!(status.finished_at.is_some());
+        assert!(runner.running_workers.is_empty());
+
+        // Nothing left to cancel.
+        assert!(runner
+            .cancel_workflow(&workflow_cid.to_string(), &mut conn)
+            .is_err());
+    }
+
+    /// Store workflows under the given local names, as runs awaiting
+    /// their worker, returning their [Cid]s.
+    fn named_runs(runner: &Runner, builder: &WorkerBuilder<'static>, names: &[&str]) -> Vec<Cid> {
+        let mut conn = builder.db().conn().unwrap();
+        names
+            .iter()
+            .map(|name| {
+                let cid = Cid::new_v1(0x71, Code::Sha2_256.digest(name.as_bytes()));
+                MemoryDb::store_workflow(
+                    workflow::Stored::new(
+                        Pointer::new(cid),
+                        Some(name.to_string()),
+                        1,
+                        workflow::IndexedResources::default(),
+                        chrono::Utc::now().naive_utc(),
+                    ),
+                    &mut conn,
+                )
+                .unwrap();
+                runner.workflow_runs.insert(
+                    cid,
+                    WorkflowRun {
+                        workflow: builder.workflow(),
+                        settings: workflow::Settings::default(),
+                        name: name.to_string().into(),
+                        pause: Arc::new(AtomicBool::new(false)),
+                        paused: false,
+                    },
+                );
+
+                cid
+            })
+            .collect()
+    }
+
+    #[homestar_runtime_proc_macro::runner_test]
+    fn cancel_workflow_by_name() {
+        let TestRunner { runner, settings } = TestRunner::start();
+        let builder = WorkerBuilder::new(settings.node);
+        let cids = named_runs(&runner, &builder, &["add-one", "add-two"]);
+        let mut conn = builder.db().conn().unwrap();
+
+        let guard = runner.runtime.enter();
+        let status = runner.cancel_workflow("add-two", &mut conn).unwrap();
+        drop(guard);
+        assert_eq!(status.cid, cids[1]);
+        assert_eq!(status.status, workflow::Status::Cancelled);
+        assert!(!runner.workflow_runs.contains_key(&cids[1]));
+
+        // The other named workflow is left running.
+        let status = runner.workflow_status("add-one", &mut conn).unwrap();
+        assert_eq!(status.cid, cids[0]);
+        assert!(!status.status.is_finished());
+        assert!(runner.workflow_runs.contains_key(&cids[0]));
+    }
+
+    #[homestar_runtime_proc_macro::runner_test]
+    fn pause_running_workflow() {
+        let TestRunner { runner, settings } = TestRunner::start();
+
+        let (workflow_cid, pause, db) = runner.runtime.block_on(async {
+            let builder = WorkerBuilder::new(settings.node);
+            let db = builder.db();
+            let workflow = builder.workflow();
+            let fetch_fn = builder.fetch_fn();
+            let worker = builder.build().await;
+            let workflow_cid = worker.workflow_info.cid;
+            let workflow_timeout = worker.workflow_settings.timeout;
+            let pause = Arc::clone(&worker.paused);
+            runner.workflow_runs.insert(
+                workflow_cid,
+                WorkflowRun {
+                    workflow,
+                    settings: workflow::Settings::default(),
+                    name: workflow_cid.to_string().into(),
+                    pause: Arc::clone(&pause),
+                    paused: false,
+                },
+            );
+            let handle = runner
+                .runtime
+                .spawn(worker.run(runner.running_tasks(), fetch_fn));
+            let delay_key = runner
+                .expiration_queue
+                .try_borrow_mut()
+                .unwrap()
+                .insert(workflow_cid, workflow_timeout);
+            runner
+                .running_workers
+                .insert(workflow_cid, (handle, delay_key));
+
+            (workflow_cid, pause, db)
+        });
+
+        let mut conn = db.conn().unwrap();
+        let status = runner
+            .pause_workflow(&workflow_cid.to_string(), &mut conn)
+            .unwrap();
+        assert!(status.paused);
+        assert_eq!(status.status, workflow::Status::Paused);
+        assert!(pause.load(Ordering::Acquire));
+
+        // Already paused.
+        assert!(runner
+            .pause_workflow(&workflow_cid.to_string(), &mut conn)
+            .is_err());
+
+        // Paused workflows can still be cancelled, dropping the run.
+        let guard = runner.runtime.enter();
+        let status = runner
+            .cancel_workflow(&workflow_cid.to_string(), &mut conn)
+            .unwrap();
+        drop(guard);
+        assert!(!status.paused);
+        assert_eq!(status.status, workflow::Status::Cancelled);
+        assert!(runner.workflow_runs.is_empty());
+    }
+
+    #[homestar_runtime_proc_macro::runner_test]
+    fn pause_and_resume_workflow_by_name() {
+        let TestRunner { runner, settings } = TestRunner::start();
+        let (runner_tx, _runner_rx) = Runner::setup_worker_channel(1);
+        let builder = WorkerBuilder::new(settings.node);
+        let db = builder.db();
+        let cids = named_runs(&runner, &builder, &["add-one", "add-two"]);
+        let mut conn = db.conn().unwrap();
+
+        let status = runner.pause_workflow("add-two", &mut conn).unwrap();
+        assert_eq!(status.cid, cids[1]);
+        assert!(status.paused);
+        assert!(runner.workflow_runs.get(&cids[1]).unwrap().paused);
+        assert!(!runner.workflow_runs.get(&cids[0]).unwrap().paused);
+
+        // Only the paused workflow can be resumed.
+        let err = runner
+            .runtime
+            .block_on(runner.resume_workflow("add-one", runner_tx, db.clone()))
+            .unwrap_err();
+        assert!(err.to_string().contains(&cids[0].to_string()));
+        assert!(runner.workflow_runs.get(&cids[1]).unwrap().paused);
+
+        let status = runner.workflow_status("add-one", &mut conn).unwrap();
+        assert_eq!(status.cid, cids[0]);
+        assert!(!status.paused);
+    }
+
+    #[homestar_runtime_proc_macro::runner_test]
+    fn resume_paused_workflow_after_restart() {
+        let TestRunner { runner, settings } = TestRunner::start();
+        let (runner_tx, _runner_rx) = Runner::setup_worker_channel(1);
+
+        runner.runtime.block_on(async {
+            let builder = WorkerBuilder::new(settings.node);
+            let db = builder.db();
+            let workflow = builder.workflow();
+            let worker = builder.build().await;
+            let workflow_cid = worker.workflow_info.cid;
+            let mut conn = db.conn().unwrap();
+
+            let body =
+                file::EncodedWorkflow::try_with(workflow, &worker.workflow_settings).unwrap();
+            MemoryDb::store_workflow_body(workflow_cid, body.into_bytes(), &mut conn).unwrap();
+            MemoryDb::update_workflow_status(
+                workflow_cid,
+                workflow::Status::Paused,
+                None,
+                &mut conn,
+            )
+            .unwrap();
+
+            // Paused workflows aren't resumed on startup.
+            runner
+                .resume_unfinished_workflows(runner_tx.clone(), db.clone())
+                .await
+                .unwrap();
+            assert!(runner.workflow_runs.is_empty());
+            let status = runner
+                .workflow_status(&workflow_cid.to_string(), &mut conn)
+                .unwrap();
+            assert!(status.paused);
+
+            let status = runner
+                .resume_workflow(&workflow_cid.to_string(), runner_tx, db.clone())
+                .await
+                .unwrap();
+            assert!(!status.paused);
+            assert_ne!(status.status, workflow::Status::Paused);
+            assert!(runner.workflow_runs.contains_key(&workflow_cid));
+            assert!(runner.running_workers.contains_key(&workflow_cid));
+        });
+    }
+
+    #[homestar_runtime_proc_macro::runner_test]
+    fn resume_unfinished_workflow() {
+        let TestRunner { runner, settings } = TestRunner::start();
+        let (runner_tx, _runner_rx) = Runner::setup_worker_channel(1);
+
+        runner.runtime.block_on(async {
+            let builder = WorkerBuilder::new(settings.node);
+            let db = builder.db();
+            let workflow = builder.workflow();
+            let worker = builder.build().await;
+            let workflow_cid = worker.workflow_info.cid;
+
+            // Not resumed without a stored body.
+            runner
+                .resume_unfinished_workflows(runner_tx.clone(), db.clone())
+                .await
+                .unwrap();
+            assert!(runner.workflow_runs.is_empty());
+
+            let body =
+                file::EncodedWorkflow::try_with(workflow, &worker.workflow_settings).unwrap();
+            MemoryDb::store_workflow_body(workflow_cid, body.into_bytes(), &mut db.conn().unwrap())
+                .unwrap();
+
+            runner
+                .resume_unfinished_workflows(runner_tx, db.clone())
+                .await
+                .unwrap();
+            assert!(runner.workflow_runs.contains_key(&workflow_cid));
+            assert!(runner.running_workers.contains_key(&workflow_cid));
+        });
+    }
+
     #[homestar_runtime_proc_macro::runner_test]
     fn gc_while_workers_still_running() {
         let TestRunner { runner, settings } = TestRunner::start();
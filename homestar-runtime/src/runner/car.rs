@@ -0,0 +1,165 @@
+//! Reading of [CARv1] archives, bundling a workflow root with the blocks it
+//! references.
+//!
+//! [CARv1]: <https://ipld.io/specs/transport/car/carv1/>
+
+use crate::tasks::unixfs;
+use anyhow::{anyhow, bail, ensure, Result};
+use libipld::{cbor::DagCborCodec, prelude::Codec, serde::from_ipld, Cid, Ipld};
+use std::{collections::BTreeMap, io::Cursor};
+
+const VERSION_KEY: &str = "version";
+const ROOTS_KEY: &str = "roots";
+
+/// Maximum number of bytes in an unsigned LEB128 varint encoding a `u64`.
+const MAX_VARINT_LEN: usize = 10;
+
+/// Contents of a CAR archive: its root [Cid]s and every block, in order.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Car {
+    /// Root [Cid]s given in the archive's header.
+    pub(crate) roots: Vec<Cid>,
+    /// Blocks in the archive, keyed by [Cid], each verified against it.
+    pub(crate) blocks: Vec<(Cid, Vec<u8>)>,
+}
+
+impl Car {
+    /// Read a CARv1 archive, verifying each block against its [Cid].
+    pub(crate) fn read(data: &[u8]) -> Result<Self> {
+        let mut rest = data;
+
+        let header: Ipld = DagCborCodec.decode(section(&mut rest)?)?;
+        let header = from_ipld::<BTreeMap<String, Ipld>>(header)
+            .map_err(|_| anyhow!("car header must be a map"))?;
+        ensure!(
+            header.get(VERSION_KEY) == Some(&Ipld::Integer(1)),
+            "unsupported car version, expected 1"
+        );
+        let roots = match header.get(ROOTS_KEY) {
+            Some(Ipld::List(roots)) => roots
+                .iter()
+                .map(|root| match root {
+                    Ipld::Link(cid) => Ok(*cid),
+                    _ => Err(anyhow!("car roots must be links")),
+                })
+                .collect::<Result<Vec<_>>>()?,
+            _ => bail!("car header is missing its roots"),
+        };
+
+        let mut blocks = vec![];
+        while !rest.is_empty() {
+            let mut cursor = Cursor::new(section(&mut rest)?);
+            let cid = Cid::read_bytes(&mut cursor)?;
+            let block = cursor.get_ref()[cursor.position() as usize..].to_vec();
+            unixfs::verify(cid, &block)?;
+            blocks.push((cid, block));
+        }
+
+        Ok(Self { roots, blocks })
+    }
+
+    /// Split off the block for the archive's single root [Cid].
+    pub(crate) fn take_root(&mut self) -> Result<(Cid, Vec<u8>)> {
+        let root = match self.roots.as_slice() {
+            [root] => *root,
+            roots => bail!("car must have exactly one root, found {}", roots.len()),
+        };
+        let idx = self
+            .blocks
+            .iter()
+            .position(|(cid, _)| *cid == root)
+            .ok_or_else(|| anyhow!("car is missing its root block {root}"))?;
+
+        Ok(self.blocks.remove(idx))
+    }
+}
+
+/// Split a varint length-prefixed section off the front of `data`.
+fn section<'a>(data: &mut &'a [u8]) -> Result<&'a [u8]> {
+    let mut len: u64 = 0;
+    let mut read = 0;
+    loop {
+        ensure!(read < MAX_VARINT_LEN, "car section length overflows");
+        let byte = *data
+            .get(read)
+            .ok_or_else(|| anyhow!("car ends within a section length"))?;
+        len |= u64::from(byte & 0x7f) << (7 * read);
+        read += 1;
+        if byte & 0x80 == 0 {
+            break;
+        }
+    }
+
+    let len = usize::try_from(len)?;
+    let rest = &data[read..];
+    ensure!(rest.len() >= len, "car ends within a section");
+    let (section, rest) = rest.split_at(len);
+    *data = rest;
+
+    Ok(section)
+}
+
+#[cfg(test)]
+pub(crate) mod test_utils {
+    use libipld::{cbor::DagCborCodec, prelude::Codec, Cid, Ipld};
+    use std::collections::BTreeMap;
+
+    /// Write a CARv1 archive with the given roots and blocks.
+    pub(crate) fn write(roots: &[Cid], blocks: &[(Cid, Vec<u8>)]) -> Vec<u8> {
+        let header = Ipld::Map(BTreeMap::from([
+            ("version".into(), Ipld::Integer(1)),
+            (
+                "roots".into(),
+                Ipld::List(roots.iter().map(|cid| Ipld::Link(*cid)).collect()),
+            ),
+        ]));
+
+        let mut data = vec![];
+        push_section(&mut data, &DagCborCodec.encode(&header).unwrap());
+        for (cid, block) in blocks {
+            push_section(&mut data, &[cid.to_bytes(), block.to_owned()].concat());
+        }
+        data
+    }
+
+    fn push_section(data: &mut Vec<u8>, section: &[u8]) {
+        let mut len = section.len();
+        while len >= 0x80 {
+            data.push((len as u8 & 0x7f) | 0x80);
+            len >>= 7;
+        }
+        data.push(len as u8);
+        data.extend_from_slice(section);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use libipld::multihash::{Code, MultihashDigest};
+
+    #[test]
+    fn read_car() {
+        let root_block = DagCborCodec.encode(&Ipld::String("root".into())).unwrap();
+        let root = Cid::new_v1(0x71, Code::Sha2_256.digest(&root_block));
+        let leaf_block = vec![7; 300];
+        let leaf = Cid::new_v1(0x55, Code::Sha2_256.digest(&leaf_block));
+
+        let data = test_utils::write(
+            &[root],
+            &[(leaf, leaf_block.clone()), (root, root_block.clone())],
+        );
+        let mut car = Car::read(&data).unwrap();
+        assert_eq!(car.roots, vec![root]);
+        assert_eq!(car.take_root().unwrap(), (root, root_block));
+        assert_eq!(car.blocks, vec![(leaf, leaf_block.clone())]);
+
+        let tampered = test_utils::write(&[root], &[(leaf, vec![8; 300])]);
+        assert!(Car::read(&tampered).is_err());
+
+        let rootless = test_utils::write(&[], &[(leaf, leaf_block)]);
+        assert!(Car::read(&rootless).unwrap().take_root().is_err());
+
+        assert!(Car::read(&data[..data.len() - 1]).is_err());
+    }
+}
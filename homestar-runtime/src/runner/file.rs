@@ -2,13 +2,25 @@
 //!
 //! [Workflow]: homestar_core::Workflow
 
-use super::Error;
-use crate::workflow;
-use homestar_core::{ipld::DagJson, Workflow};
+use super::{car::Car, Error};
+use crate::workflow::{self, StoredInstruction, StoredProof};
+use anyhow::{anyhow, bail};
+use homestar_core::{consts::DAG_CBOR, Workflow};
 use homestar_wasm::io::Arg;
+use libipld::{
+    cbor::DagCborCodec, json::DagJsonCodec, prelude::Codec, serde::from_ipld, Cid, Ipld,
+};
 use serde::{Deserialize, Serialize};
-use std::{ffi::OsStr, fmt, path::PathBuf, str::FromStr};
+use std::{collections::BTreeMap, ffi::OsStr, fmt, path::PathBuf, str::FromStr};
 use tokio::fs;
+use ucan::Ucan;
+
+const META_KEY: &str = "meta";
+const PROOFS_KEY: &str = "proofs";
+/// Multicodec code for DAG-JSON blocks.
+const DAG_JSON: u64 = 0x0129;
+/// Multicodec code for raw blocks.
+const RAW: u64 = 0x55;
 
 /// Data structure for a workflow file path.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +29,102 @@ pub struct ReadWorkflow {
     file: PathBuf,
 }
 
+/// [Workflow] read from a file, along with its workflow-level settings,
+/// name, and the UCAN proofs and blocks bundled with it.
+#[derive(Debug, Clone)]
+pub(crate) struct ParsedWorkflow<'a> {
+    /// The [Workflow] itself.
+    pub(crate) workflow: Workflow<'a, Arg>,
+    /// [workflow::Settings] for the run, given by the workflow's `meta`
+    /// section merged over defaults.
+    pub(crate) settings: workflow::Settings,
+    /// Human-readable name given by the workflow's `meta` section.
+    pub(crate) name: Option<String>,
+    /// UCAN proofs bundled under the workflow's `proofs` key, as encoded
+    /// JWTs keyed by their [Cid], or as blocks of a CAR archive.
+    pub(crate) proofs: Vec<StoredProof>,
+    /// Instructions referenced by pointer, bundled in a CAR archive.
+    pub(crate) instructions: Vec<StoredInstruction>,
+    /// Other blocks bundled in a CAR archive, e.g. task inputs, keyed by
+    /// [Cid].
+    pub(crate) blocks: Vec<(Cid, Vec<u8>)>,
+}
+
+impl ParsedWorkflow<'_> {
+    /// Parse a workflow from a CAR archive, whose single root is the
+    /// workflow, as DAG-CBOR or DAG-JSON.
+    ///
+    /// The archive's other blocks are sorted into instructions (DAG-CBOR
+    /// blocks decoding to an instruction), UCAN proofs (raw blocks holding
+    /// an encoded UCAN), and everything else, e.g. input blocks.
+    pub(crate) fn try_from_car(data: &[u8]) -> anyhow::Result<Self> {
+        let mut car = Car::read(data)?;
+        let (root, block) = car.take_root()?;
+        let ipld: Ipld = match root.codec() {
+            DAG_CBOR => DagCborCodec.decode(&block)?,
+            DAG_JSON => DagJsonCodec.decode(&block)?,
+            codec => bail!("unsupported codec {codec:#x} for workflow root {root}"),
+        };
+
+        let mut parsed = Self::try_from(ipld)?;
+        for (cid, block) in car.blocks.into_iter() {
+            match cid.codec() {
+                DAG_CBOR => match StoredInstruction::try_with(cid, block.clone()) {
+                    Ok(instruction) => parsed.instructions.push(instruction),
+                    Err(_) => parsed.blocks.push((cid, block)),
+                },
+                RAW => match String::from_utf8(block) {
+                    Ok(token) if Ucan::from_str(&token).is_ok() => {
+                        parsed.proofs.push(StoredProof::try_with(cid, token)?)
+                    }
+                    Ok(token) => parsed.blocks.push((cid, token.into_bytes())),
+                    Err(err) => parsed.blocks.push((cid, err.into_bytes())),
+                },
+                _ => parsed.blocks.push((cid, block)),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+impl TryFrom<Ipld> for ParsedWorkflow<'_> {
+    type Error = anyhow::Error;
+
+    fn try_from(ipld: Ipld) -> Result<Self, Self::Error> {
+        let mut map = from_ipld::<BTreeMap<String, Ipld>>(ipld)
+            .map_err(|_| anyhow!("workflow must be a map"))?;
+
+        let metadata = map
+            .remove(META_KEY)
+            .map(workflow::settings::Metadata::try_from)
+            .transpose()?
+            .unwrap_or_default();
+        let settings = workflow::Settings::default().merge(&metadata)?;
+
+        let proofs = map
+            .remove(PROOFS_KEY)
+            .map(from_ipld::<BTreeMap<String, String>>)
+            .transpose()
+            .map_err(|_| anyhow!("workflow {PROOFS_KEY} must map cids to encoded UCANs"))?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(cid, token)| StoredProof::try_with(Cid::from_str(&cid)?, token))
+            .collect::<anyhow::Result<_>>()?;
+
+        let workflow = Workflow::try_from(Ipld::Map(map)).map_err(anyhow::Error::new)?;
+
+        Ok(Self {
+            workflow,
+            settings,
+            name: metadata.name,
+            proofs,
+            instructions: vec![],
+            blocks: vec![],
+        })
+    }
+}
+
 impl FromStr for ReadWorkflow {
     type Err = String;
 
@@ -34,25 +142,99 @@ impl fmt::Display for ReadWorkflow {
 }
 
 impl ReadWorkflow {
-    /// Validate and parse the workflow file.
+    /// Read the workflow file, validating that it parses, for sending its
+    /// contents on to a (possibly remote) runner.
     ///
-    /// Validation is currently limited to checking the file extension,
-    /// or attempting to treat the file as JSON if no extension is provided.
-    pub(crate) async fn validate_and_parse<'a>(
-        &self,
-    ) -> Result<(Workflow<'a, Arg>, workflow::Settings), Error> {
-        match self.file.extension().and_then(OsStr::to_str) {
-            None | Some("json") => {
-                let data = fs::read_to_string(&self.file.canonicalize()?).await?;
-                // TODO: Parse this from the workflow data/file itself.
-                let workflow_settings = workflow::Settings::default();
-                Ok((
-                    DagJson::from_json_string(data).map_err(anyhow::Error::new)?,
-                    workflow_settings,
-                ))
-            }
+    /// Workflows are read by file extension, as:
+    ///   * DAG-JSON, for `.json` files or if no extension is provided;
+    ///   * DAG-CBOR, for `.cbor` files;
+    ///   * CARv1 archives, for `.car` files, bundling the workflow root with
+    ///     the instructions, input blocks and UCAN proofs it references.
+    pub(crate) async fn read(&self) -> Result<EncodedWorkflow, Error> {
+        let format = match self.file.extension().and_then(OsStr::to_str) {
+            None | Some("json") => WorkflowFormat::DagJson,
+            Some("cbor") => WorkflowFormat::DagCbor,
+            Some("car") => WorkflowFormat::Car,
+            Some(ext) => return Err(Error::UnsupportedWorkflow(ext.to_string())),
+        };
+
+        let bytes = fs::read(&self.file.canonicalize()?).await?;
+        let encoded = EncodedWorkflow { format, bytes };
+        encoded.validate_and_parse()?;
+
+        Ok(encoded)
+    }
+}
+
+/// Encoding of a workflow's contents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WorkflowFormat {
+    /// DAG-JSON encoded workflow.
+    DagJson,
+    /// DAG-CBOR encoded workflow.
+    DagCbor,
+    /// CARv1 archive rooted at a DAG-CBOR or DAG-JSON encoded workflow.
+    Car,
+}
+
+/// Encoded contents of a workflow file, as sent from the CLI to a runner.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EncodedWorkflow {
+    /// Encoding of the workflow's bytes.
+    format: WorkflowFormat,
+    /// Workflow file contents.
+    bytes: Vec<u8>,
+}
+
+impl EncodedWorkflow {
+    /// Encode a [Workflow] as DAG-CBOR, with its [workflow::Settings] as its
+    /// `meta` section, for persisting it and running it again later.
+    pub(crate) fn try_with(
+        workflow: Workflow<'_, Arg>,
+        settings: &workflow::Settings,
+    ) -> anyhow::Result<Self> {
+        let mut map = from_ipld::<BTreeMap<String, Ipld>>(Ipld::from(workflow))
+            .map_err(|_| anyhow!("workflow must be a map"))?;
+        map.insert(
+            META_KEY.into(),
+            workflow::settings::Metadata::from(settings).into(),
+        );
+
+        Ok(Self {
+            format: WorkflowFormat::DagCbor,
+            bytes: DagCborCodec.encode(&Ipld::Map(map))?,
+        })
+    }
+
+    /// Wrap a DAG-CBOR encoded workflow, as persisted in the database.
+    pub(crate) fn from_dag_cbor(bytes: Vec<u8>) -> Self {
+        Self {
+            format: WorkflowFormat::DagCbor,
+            bytes,
+        }
+    }
 
-            Some(ext) => Err(Error::UnsupportedWorkflow(ext.to_string())),
+    /// Return the workflow's encoded bytes.
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Validate and parse the workflow, along with its top-level `meta`
+    /// section and any bundled UCAN `proofs`.
+    ///
+    /// Validation is currently limited to checking the encoding, and
+    /// checking the workflow's metadata and proofs.
+    pub(crate) fn validate_and_parse<'a>(&self) -> Result<ParsedWorkflow<'a>, Error> {
+        match self.format {
+            WorkflowFormat::DagJson => {
+                let ipld: Ipld = DagJsonCodec.decode(&self.bytes)?;
+                Ok(ParsedWorkflow::try_from(ipld)?)
+            }
+            WorkflowFormat::DagCbor => {
+                let ipld: Ipld = DagCborCodec.decode(&self.bytes)?;
+                Ok(ParsedWorkflow::try_from(ipld)?)
+            }
+            WorkflowFormat::Car => Ok(ParsedWorkflow::try_from_car(&self.bytes)?),
         }
     }
 }
@@ -60,10 +242,13 @@ impl ReadWorkflow {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::runner::car;
     use homestar_core::{
+        ipld::{DagCbor, DagJson},
         test_utils::workflow as workflow_test_utils,
-        workflow::{config::Resources, instruction::RunInstruction, prf::UcanPrf, Task},
+        workflow::{config::Resources, instruction::RunInstruction, prf::UcanPrf, Pointer, Task},
     };
+    use libipld::multihash::{Code, MultihashDigest};
 
     #[tokio::test]
     async fn validate_and_parse_workflow() {
@@ -89,9 +274,16 @@ mod test {
         workflow.to_file(path.display().to_string()).unwrap();
         let workflow_file = ReadWorkflow { file: path.clone() };
 
-        let (validated_workflow, _settings) = workflow_file.validate_and_parse().await.unwrap();
+        let parsed = workflow_file
+            .read()
+            .await
+            .unwrap()
+            .validate_and_parse()
+            .unwrap();
 
-        assert_eq!(workflow, validated_workflow);
+        assert_eq!(workflow, parsed.workflow);
+        assert_eq!(parsed.settings, workflow::Settings::default());
+        assert!(parsed.proofs.is_empty());
 
         // rename file extension
         fs::rename(path, "./fixtures/test.txt").await.unwrap();
@@ -99,7 +291,7 @@ mod test {
         let workflow_file = ReadWorkflow {
             file: new_path.clone(),
         };
-        let error = workflow_file.validate_and_parse().await;
+        let error = workflow_file.read().await;
         assert_eq!(
             error.unwrap_err().to_string(),
             "unsupported workflow file type: txt"
@@ -111,8 +303,132 @@ mod test {
         let workflow_file = ReadWorkflow {
             file: new_path.clone(),
         };
-        let (newly_validated_workflow, _settings) =
-            workflow_file.validate_and_parse().await.unwrap();
-        assert_eq!(workflow, newly_validated_workflow);
+        let newly_parsed = workflow_file
+            .read()
+            .await
+            .unwrap()
+            .validate_and_parse()
+            .unwrap();
+        assert_eq!(workflow, newly_parsed.workflow);
+    }
+
+    #[test]
+    fn parse_workflow_meta_and_proofs() {
+        let (instruction, _) = workflow_test_utils::wasm_instruction_with_nonce::<Arg>();
+        let task = Task::new(
+            RunInstruction::Expanded(instruction),
+            Resources::default().into(),
+            UcanPrf::default(),
+        );
+        let workflow = Workflow::new(vec![task]);
+
+        let token = "eyJhbGciOiJFZERTQSIsInR5cCI6IkpXVCJ9.e30.c2ln";
+        let cid = Cid::new_v1(0x55, Code::Sha2_256.digest(token.as_bytes()));
+
+        let mut map = from_ipld::<BTreeMap<String, Ipld>>(Ipld::from(workflow.clone())).unwrap();
+        map.insert(
+            META_KEY.into(),
+            Ipld::Map(BTreeMap::from([
+                ("name".into(), Ipld::String("crop-images".into())),
+                ("retries".into(), Ipld::Integer(2)),
+            ])),
+        );
+        map.insert(
+            PROOFS_KEY.into(),
+            Ipld::Map(BTreeMap::from([(
+                cid.to_string(),
+                Ipld::String(token.into()),
+            )])),
+        );
+
+        let parsed = ParsedWorkflow::try_from(Ipld::Map(map.clone())).unwrap();
+        assert_eq!(parsed.workflow, workflow);
+        assert_eq!(parsed.name, Some("crop-images".to_string()));
+        assert_eq!(parsed.settings.retries, 2);
+        assert_eq!(parsed.proofs.len(), 1);
+        assert_eq!(parsed.proofs[0].cid(), cid);
+
+        map.insert(
+            META_KEY.into(),
+            Ipld::Map(BTreeMap::from([("retries".into(), Ipld::Integer(-2))])),
+        );
+        assert!(ParsedWorkflow::try_from(Ipld::Map(map)).is_err());
+    }
+
+    #[tokio::test]
+    async fn validate_and_parse_cbor_workflow() {
+        let path = PathBuf::from("./fixtures/test_cbor.cbor");
+        let (instruction, _) = workflow_test_utils::wasm_instruction_with_nonce::<Arg>();
+        let task = Task::new(
+            RunInstruction::Expanded(instruction),
+            Resources::default().into(),
+            UcanPrf::default(),
+        );
+        let workflow = Workflow::new(vec![task]);
+
+        fs::write(&path, workflow.clone().to_cbor().unwrap())
+            .await
+            .unwrap();
+        let encoded = ReadWorkflow { file: path.clone() }.read().await.unwrap();
+        assert_eq!(encoded.format, WorkflowFormat::DagCbor);
+        let parsed = encoded.validate_and_parse().unwrap();
+        assert_eq!(workflow, parsed.workflow);
+
+        fs::remove_file(path).await.unwrap();
+    }
+
+    #[test]
+    fn parse_car_workflow() {
+        let (instruction, _) = workflow_test_utils::wasm_instruction_with_nonce::<Arg>();
+        let instruction_cid = instruction.clone().to_cid().unwrap();
+        let task = Task::new(
+            RunInstruction::Ptr(Pointer::new(instruction_cid)),
+            Resources::default().into(),
+            UcanPrf::default(),
+        );
+        let workflow = Workflow::new(vec![task]);
+        let root = workflow.clone().to_cid().unwrap();
+
+        let input = b"not a ucan".to_vec();
+        let input_cid = Cid::new_v1(RAW, Code::Sha2_256.digest(&input));
+
+        let data = car::test_utils::write(
+            &[root],
+            &[
+                (root, workflow.clone().to_cbor().unwrap()),
+                (instruction_cid, instruction.to_cbor().unwrap()),
+                (input_cid, input.clone()),
+            ],
+        );
+
+        let parsed = ParsedWorkflow::try_from_car(&data).unwrap();
+        assert_eq!(parsed.workflow, workflow);
+        assert_eq!(parsed.instructions.len(), 1);
+        assert_eq!(parsed.instructions[0].cid(), instruction_cid);
+        assert_eq!(parsed.blocks, vec![(input_cid, input)]);
+        assert!(parsed.proofs.is_empty());
+    }
+
+    #[test]
+    fn encode_workflow_with_settings() {
+        let (instruction, _) = workflow_test_utils::wasm_instruction_with_nonce::<Arg>();
+        let task = Task::new(
+            RunInstruction::Expanded(instruction),
+            Resources::default().into(),
+            UcanPrf::default(),
+        );
+        let workflow = Workflow::new(vec![task]);
+        let settings = workflow::Settings {
+            retries: 4,
+            ..Default::default()
+        };
+
+        let encoded = EncodedWorkflow::try_with(workflow.clone(), &settings).unwrap();
+        let parsed = EncodedWorkflow::from_dag_cbor(encoded.into_bytes())
+            .validate_and_parse()
+            .unwrap();
+        assert_eq!(parsed.workflow, workflow);
+        assert_eq!(parsed.settings, settings);
+        assert_eq!(parsed.name, None);
     }
 }
@@ -10,6 +10,7 @@ use chrono::NaiveDateTime;
 use faststr::FastStr;
 use libipld::Cid;
 use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
 use std::{fmt, net::SocketAddr, sync::Arc};
 use tabled::{
     builder::Builder,
@@ -30,15 +31,21 @@ pub struct AckWorkflow {
     pub(crate) resources: IndexedResources,
     #[tabled(skip)]
     pub(crate) replayed_receipt_info: Vec<WorkflowReceiptInfo>,
+    /// Instructions outside of the workflow awaited on by its tasks.
+    #[tabled(skip)]
+    pub(crate) external_promises: Vec<Cid>,
     pub(crate) timestamp: String,
+    /// Whether the workflow is waiting in the admission queue for a running
+    /// worker slot.
+    pub(crate) queued: bool,
 }
 
 impl fmt::Display for AckWorkflow {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "cid: {}, progress: {}/{}, timestamp: {}",
-            self.cid, self.progress_count, self.num_tasks, self.timestamp
+            "cid: {}, progress: {}/{}, timestamp: {}, queued: {}",
+            self.cid, self.progress_count, self.num_tasks, self.timestamp, self.queued
         )
     }
 }
@@ -48,8 +55,10 @@ impl AckWorkflow {
     pub(crate) fn new(
         workflow_info: Arc<workflow::Info>,
         replayed_receipt_info: Vec<WorkflowReceiptInfo>,
+        external_promises: Vec<Cid>,
         name: FastStr,
         timestamp: NaiveDateTime,
+        queued: bool,
     ) -> Self {
         Self {
             cid: workflow_info.cid,
@@ -58,7 +67,9 @@ impl AckWorkflow {
             progress_count: workflow_info.progress_count,
             resources: workflow_info.resources.clone(),
             replayed_receipt_info,
+            external_promises,
             timestamp: timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+            queued,
         }
     }
 }
@@ -109,7 +120,20 @@ impl show::ConsoleTable for AckWorkflow {
 
         let receipt_table = receipt_table_builder.build();
 
-        let tbl = col![table, resource_table, receipt_table].default();
+        let mut promise_table_builder = Builder::default();
+        promise_table_builder.push_record(["External Promise".to_string()]);
+        for cid in &self.external_promises {
+            promise_table_builder.push_record([cid.to_string()]);
+        }
+
+        // If there are no external promises, add a placeholder row.
+        if promise_table_builder.count_rows() == 1 {
+            promise_table_builder.push_record(["<none>".to_string()]);
+        };
+
+        let promise_table = promise_table_builder.build();
+
+        let tbl = col![table, resource_table, receipt_table, promise_table].default();
 
         tbl.echo()
     }
@@ -138,3 +162,180 @@ impl show::ConsoleTable for Ping {
         self.table().echo()
     }
 }
+
+/// Status of a [Workflow] known to the runner, for response / display upon
+/// listing workflows or requesting a workflow's status.
+///
+/// [Workflow]: homestar_core::Workflow
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tabled)]
+pub struct WorkflowStatus {
+    #[serde_as(as = "DisplayFromStr")]
+    pub(crate) cid: Cid,
+    pub(crate) name: FastStr,
+    pub(crate) num_tasks: u32,
+    pub(crate) progress_count: u32,
+    /// [Receipt] [Cid]s of completed tasks.
+    ///
+    /// [Receipt]: crate::Receipt
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    #[tabled(skip)]
+    pub(crate) progress: Vec<Cid>,
+    /// Unique resources used by the workflow's tasks.
+    #[tabled(skip)]
+    pub(crate) resources: Vec<String>,
+    pub(crate) created_at: String,
+    /// Stored lifecycle status of the workflow.
+    pub(crate) status: workflow::Status,
+    #[tabled(display_with = "display_optional")]
+    pub(crate) finished_at: Option<String>,
+    /// Error the workflow last failed with.
+    #[tabled(display_with = "display_optional")]
+    pub(crate) last_error: Option<String>,
+    /// Whether the workflow has a running worker.
+    pub(crate) running: bool,
+    /// Whether the workflow is waiting in the admission queue for a running
+    /// worker slot.
+    pub(crate) queued: bool,
+    /// Whether the workflow has been paused, no longer scheduling tasks until
+    /// resumed.
+    pub(crate) paused: bool,
+}
+
+impl fmt::Display for WorkflowStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cid: {}, progress: {}/{}, status: {}, running: {}, queued: {}, paused: {}",
+            self.cid,
+            self.progress_count,
+            self.num_tasks,
+            self.status,
+            self.running,
+            self.queued,
+            self.paused
+        )
+    }
+}
+
+impl WorkflowStatus {
+    /// Workflow status for response / display.
+    pub(crate) fn new(
+        workflow_info: workflow::Info,
+        stored: &workflow::Stored,
+        running: bool,
+        queued: bool,
+        paused: bool,
+    ) -> Self {
+        let format = |timestamp: NaiveDateTime| timestamp.format("%Y-%m-%d %H:%M:%S").to_string();
+        Self {
+            cid: workflow_info.cid,
+            name: workflow_info
+                .name
+                .unwrap_or_else(|| workflow_info.cid.to_string().into()),
+            num_tasks: workflow_info.num_tasks,
+            progress_count: workflow_info.progress_count,
+            resources: workflow_info
+                .resources
+                .iter()
+                .map(|v| v.to_string())
+                .collect(),
+            progress: workflow_info.progress,
+            created_at: format(stored.created_at),
+            status: stored.status,
+            finished_at: stored.finished_at.map(format),
+            last_error: stored.last_error.clone(),
+            running,
+            queued,
+            paused,
+        }
+    }
+}
+
+fn display_optional(value: &Option<String>) -> String {
+    value.clone().unwrap_or_else(|| "-".to_string())
+}
+
+impl show::ConsoleTable for WorkflowStatus {
+    fn table(&self) -> show::Output {
+        show::Output::new(Table::new(vec![self]).to_string())
+    }
+
+    fn echo_table(&self) -> Result<(), std::io::Error> {
+        let table = self.table();
+
+        let mut resource_table = Table::new(&self.resources);
+        resource_table
+            .with(Modify::new(Rows::first()).with(Format::content(|_s| "Resources".to_string())));
+
+        let mut progress_table_builder = Builder::default();
+        progress_table_builder.push_record(["Completed Receipt".to_string()]);
+        for cid in &self.progress {
+            progress_table_builder.push_record([cid.to_string()]);
+        }
+
+        // If no tasks have completed, add a placeholder row.
+        if progress_table_builder.count_rows() == 1 {
+            progress_table_builder.push_record(["<none>".to_string()]);
+        };
+
+        let progress_table = progress_table_builder.build();
+
+        let tbl = col![table, resource_table, progress_table].default();
+
+        tbl.echo()
+    }
+}
+
+/// Workflows known to the runner, most recently created first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowList(pub(crate) Vec<WorkflowStatus>);
+
+impl show::ConsoleTable for WorkflowList {
+    fn table(&self) -> show::Output {
+        Table::new(&self.0).default()
+    }
+
+    fn echo_table(&self) -> Result<(), std::io::Error> {
+        self.table().echo()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::workflow::Stored;
+    use homestar_core::workflow::Pointer;
+    use libipld::multihash::{Code, MultihashDigest};
+
+    #[test]
+    fn workflow_status_json() {
+        let cid = Cid::new_v1(0x71, Code::Sha2_256.digest(b"workflow"));
+        let receipt = Cid::new_v1(0x71, Code::Sha2_256.digest(b"receipt"));
+        let created_at = NaiveDateTime::from_timestamp_opt(1_700_000_000, 0).unwrap();
+        let stored = Stored::new(
+            Pointer::new(cid),
+            None,
+            2,
+            IndexedResources::default(),
+            created_at,
+        );
+        let info = workflow::Info::new(stored.clone(), vec![receipt]);
+
+        let status = WorkflowStatus::new(info, &stored, true, false, false);
+        assert_eq!(status.name, FastStr::from(cid.to_string()));
+        assert_eq!(status.progress_count, 1);
+
+        let json: serde_json::Value = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["cid"], serde_json::json!(cid.to_string()));
+        assert_eq!(json["progress"], serde_json::json!([receipt.to_string()]));
+        assert_eq!(json["created_at"], serde_json::json!("2023-11-14 22:13:20"));
+        assert_eq!(json["status"], serde_json::json!("queued"));
+        assert_eq!(json["finished_at"], serde_json::Value::Null);
+        assert_eq!(json["last_error"], serde_json::Value::Null);
+        assert_eq!(json["running"], serde_json::json!(true));
+
+        let table = WorkflowList(vec![status]).table().to_string();
+        assert!(table.contains(&cid.to_string()));
+    }
+}
@@ -17,6 +17,7 @@ use homestar_core::workflow::{InstructionResult, LinkMap, Pointer};
 use homestar_wasm::io::Arg;
 use indexmap::IndexMap;
 use libipld::Cid;
+use serde::{Deserialize, Serialize};
 use std::{ops::ControlFlow, str::FromStr, sync::Arc};
 use tokio::sync::RwLock;
 use tracing::debug;
@@ -26,6 +27,25 @@ use tracing::debug;
 /// [Dag]: dagga::Dag
 type Schedule<'a> = Vec<Vec<Node<Vertex<'a>, usize>>>;
 
+/// Mode in which a [Worker] dispatches scheduled tasks to run.
+///
+/// [Worker]: crate::Worker
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SchedulerMode {
+    /// Run tasks in [Dag] batches, waiting on a whole batch to complete
+    /// before starting the next.
+    ///
+    /// [Dag]: dagga::Dag
+    #[default]
+    Batch,
+    /// Start each task as soon as all the tasks it awaits on within the
+    /// [Workflow] have completed.
+    ///
+    /// [Workflow]: homestar_core::Workflow
+    Ready,
+}
+
 /// Type for [instruction]-based, batched, execution graph and set of task
 /// resources.
 ///
@@ -41,6 +61,12 @@ pub(crate) struct ExecutionGraph<'a> {
     /// [resources]: Resource
     /// [Workflow]: homestar_core::Workflow
     pub(crate) indexed_resources: IndexedResources,
+    /// [Instruction] [Cid]s of promises awaited on from tasks outside of the
+    /// [Workflow], resolved at runtime.
+    ///
+    /// [Instruction]: homestar_core::workflow::Instruction
+    /// [Workflow]: homestar_core::Workflow
+    pub(crate) external_promises: Vec<Cid>,
 }
 
 /// Scheduler for a series of tasks, including what's run,
@@ -1,5 +1,6 @@
 //! General runtime settings / configuration.
 
+use crate::scheduler::SchedulerMode;
 use config::{Config, ConfigError, Environment, File};
 use http::Uri;
 use serde::{Deserialize, Serialize};
@@ -9,6 +10,7 @@ use std::net::Ipv4Addr;
 use std::{
     env,
     net::{IpAddr, Ipv6Addr},
+    num::NonZeroUsize,
     path::PathBuf,
     time::Duration,
 };
@@ -51,12 +53,52 @@ pub struct Node {
     /// Database settings.
     #[serde(default)]
     pub(crate) db: Database,
+    /// Embedded blockstore settings.
+    #[serde(default)]
+    pub(crate) blockstore: Blockstore,
+    /// Limits on task resources retrieved by [Cid].
+    ///
+    /// [Cid]: libipld::Cid
+    #[serde(default)]
+    pub(crate) resource_limits: ResourceLimits,
     /// Garbage collection interval.
     #[serde_as(as = "DurationSeconds<u64>")]
     pub(crate) gc_interval: Duration,
     /// Shutdown timeout.
     #[serde_as(as = "DurationSeconds<u64>")]
     pub(crate) shutdown_timeout: Duration,
+    /// Mode for dispatching workflow tasks, either `batch` or `ready`.
+    pub(crate) scheduler_mode: SchedulerMode,
+    /// Maximum number of Wasm tasks executing at once across all workflows.
+    ///
+    /// Must be non-zero, as no task could ever run otherwise.
+    pub(crate) max_concurrent_tasks: NonZeroUsize,
+    /// Maximum number of workflows running at once. Workflows past this
+    /// limit wait in an admission queue.
+    ///
+    /// Must be non-zero, as no workflow would ever be admitted otherwise.
+    pub(crate) max_running_workers: NonZeroUsize,
+    /// Directory for caching precompiled Wasm components across restarts.
+    ///
+    /// Components are only cached in memory if not set.
+    pub(crate) wasm_cache_dir: Option<PathBuf>,
+    /// Maximum number of compiled Wasm components kept in memory, evicting
+    /// the least recently used ones first.
+    pub(crate) wasm_cache_capacity: NonZeroUsize,
+    /// Directory `file://` task resources may be read from, e.g. for local
+    /// development.
+    ///
+    /// `file://` resources are refused if not set.
+    pub(crate) file_resources_dir: Option<PathBuf>,
+    /// Whether tasks must be authorized by the UCAN proofs in their `prf`
+    /// field, delegated to the node, before being executed.
+    pub(crate) enforce_ucan_proofs: bool,
+    /// `did:key` issuers trusted to root the UCAN delegation chains that
+    /// authorize tasks, besides the node itself.
+    pub(crate) trusted_ucan_issuers: Vec<String>,
+    /// Whether workflows left unfinished when the node stopped are resumed
+    /// automatically on startup.
+    pub(crate) resume_unfinished_workflows: bool,
 }
 
 /// Database-related settings for a homestar node.
@@ -112,6 +154,21 @@ pub struct Network {
     pub(crate) ipfs: Ipfs,
     /// Webserver settings
     pub(crate) webserver: Webserver,
+    /// Verification settings for receipts received from peers.
+    pub(crate) receipt_verification: ReceiptVerification,
+}
+
+/// Verification settings for receipts received over gossip or the DHT.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct ReceiptVerification {
+    /// Reject receipts that aren't signed by their issuer.
+    pub(crate) require_signature: bool,
+    /// `did:key` issuers to accept receipts from.
+    ///
+    /// Receipts from any issuer with a valid signature are accepted if
+    /// empty.
+    pub(crate) trusted_issuers: Vec<String>,
 }
 
 /// IPFS Settings
@@ -126,6 +183,29 @@ pub(crate) struct Ipfs {
     pub(crate) port: u16,
 }
 
+/// Embedded blockstore settings.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct Blockstore {
+    /// Directory where blocks are stored, keyed by [Cid].
+    ///
+    /// [Cid]: libipld::Cid
+    pub(crate) path: PathBuf,
+}
+
+/// Limits on task resources, checked while they're fetched.
+///
+/// [Cid]: libipld::Cid
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct ResourceLimits {
+    /// Maximum total size of a resource's content, in bytes.
+    pub(crate) max_size: u64,
+    /// Maximum number of blocks making up a resource retrieved by [Cid],
+    /// e.g. the nodes of a UnixFS file.
+    pub(crate) max_nodes: usize,
+}
+
 /// Metrics settings.
 #[serde_as]
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -178,9 +258,20 @@ impl Default for Node {
         Self {
             gc_interval: Duration::from_secs(1800),
             shutdown_timeout: Duration::from_secs(20),
+            scheduler_mode: SchedulerMode::default(),
+            max_concurrent_tasks: NonZeroUsize::new(64).expect("non-zero"),
+            max_running_workers: NonZeroUsize::new(16).expect("non-zero"),
+            wasm_cache_dir: None,
+            wasm_cache_capacity: NonZeroUsize::new(128).expect("non-zero"),
+            file_resources_dir: None,
+            enforce_ucan_proofs: false,
+            trusted_ucan_issuers: Vec::new(),
+            resume_unfinished_workflows: true,
             monitoring: Default::default(),
             network: Default::default(),
             db: Default::default(),
+            blockstore: Default::default(),
+            resource_limits: Default::default(),
         }
     }
 }
@@ -242,6 +333,7 @@ impl Default for Network {
             #[cfg(feature = "ipfs")]
             ipfs: Default::default(),
             webserver: Webserver::default(),
+            receipt_verification: ReceiptVerification::default(),
         }
     }
 }
@@ -274,6 +366,32 @@ impl Default for Ipfs {
     }
 }
 
+impl Default for Blockstore {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("blocks"),
+        }
+    }
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self {
+            max_size: 256 * 1024 * 1024,
+            max_nodes: 100_000,
+        }
+    }
+}
+
+impl Default for ReceiptVerification {
+    fn default() -> Self {
+        Self {
+            require_signature: true,
+            trusted_issuers: Vec::new(),
+        }
+    }
+}
+
 impl Default for Metrics {
     fn default() -> Self {
         Self { port: 4000 }
@@ -408,6 +526,14 @@ mod test {
         assert_eq!(settings.node.db.max_pool_size, 1);
     }
 
+    #[test]
+    fn refuse_zero_limits() {
+        Settings::build(Some("fixtures/settings-zero-workers.toml".into()))
+            .expect_err("zero running workers");
+        Settings::build(Some("fixtures/settings-zero-tasks.toml".into()))
+            .expect_err("zero concurrent tasks");
+    }
+
     #[test]
     fn import_existing_key() {
         let settings = Settings::build(Some("fixtures/settings-import-ed25519.toml".into()))
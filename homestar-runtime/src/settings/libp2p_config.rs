@@ -55,6 +55,10 @@ pub(crate) struct Dht {
     /// Timeout for p2p provider workflow info lookups in milliseconds.
     #[serde_as(as = "DurationMilliSeconds<u64>")]
     pub(crate) p2p_provider_timeout: Duration,
+    /// Deadline for receipts awaited from tasks outside of a workflow, in
+    /// milliseconds.
+    #[serde_as(as = "DurationMilliSeconds<u64>")]
+    pub(crate) p2p_external_receipt_timeout: Duration,
     /// Quorum for receipt records on the DHT.
     pub(crate) receipt_quorum: usize,
     /// Quorum for [workflow::Info] records on the DHT.
@@ -162,6 +166,7 @@ impl Default for Dht {
             p2p_receipt_timeout: Duration::from_millis(500),
             p2p_workflow_info_timeout: Duration::from_millis(500),
             p2p_provider_timeout: Duration::from_millis(10000),
+            p2p_external_receipt_timeout: Duration::from_millis(60000),
             receipt_quorum: 2,
             workflow_quorum: 3,
         }
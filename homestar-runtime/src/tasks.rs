@@ -5,9 +5,12 @@ use async_trait::async_trait;
 use enum_assoc::Assoc;
 use std::path::PathBuf;
 
+mod failure;
 mod fetch;
+pub(crate) mod unixfs;
 mod wasm;
 
+pub(crate) use failure::*;
 pub(crate) use fetch::*;
 pub(crate) use wasm::*;
 
@@ -0,0 +1,105 @@
+//! Failures recorded as [InstructionResult::Error] receipt payloads.
+//!
+//! [InstructionResult::Error]: homestar_core::workflow::InstructionResult::Error
+
+use super::ResourceLimit;
+use homestar_core::workflow::pointer::{Await, AwaitResult};
+use libipld::Ipld;
+use std::{collections::BTreeMap, fmt};
+
+pub(crate) const ERROR_KEY: &str = "error";
+const SKIPPED_KEY: &str = "skipped";
+const BRANCH_KEY: &str = "branch";
+
+/// Reason a task did not produce a successful result.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TaskFailure {
+    /// Task tripped one of its configured [ResourceLimit]s.
+    Limit(ResourceLimit),
+    /// Task could not be resolved or executed.
+    Execution(String),
+    /// Task is not authorized by the UCAN proofs in its `prf` field.
+    Unauthorized(String),
+    /// Task was skipped, as the instruction it awaits on did not take the
+    /// awaited branch, e.g. it failed when awaiting on `await/ok`.
+    Skipped(Await),
+}
+
+impl fmt::Display for TaskFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskFailure::Limit(limit) => write!(f, "{limit}"),
+            TaskFailure::Execution(err) => write!(f, "{err}"),
+            TaskFailure::Unauthorized(err) => write!(f, "unauthorized: {err}"),
+            TaskFailure::Skipped(promise) => {
+                let outcome = match promise.result() {
+                    AwaitResult::Error => "fail",
+                    _ => "succeed",
+                };
+                write!(
+                    f,
+                    "skipped, as awaited instruction {} did not {outcome}",
+                    promise.instruction_cid()
+                )
+            }
+        }
+    }
+}
+
+impl From<ResourceLimit> for TaskFailure {
+    fn from(limit: ResourceLimit) -> Self {
+        TaskFailure::Limit(limit)
+    }
+}
+
+impl From<TaskFailure> for Ipld {
+    fn from(failure: TaskFailure) -> Self {
+        match failure {
+            TaskFailure::Limit(limit) => limit.into(),
+            TaskFailure::Execution(ref err) => Ipld::Map(BTreeMap::from([(
+                ERROR_KEY.into(),
+                Ipld::String(err.to_string()),
+            )])),
+            TaskFailure::Unauthorized(_) => Ipld::Map(BTreeMap::from([(
+                ERROR_KEY.into(),
+                Ipld::String(failure.to_string()),
+            )])),
+            TaskFailure::Skipped(ref promise) => Ipld::Map(BTreeMap::from([
+                (ERROR_KEY.into(), Ipld::String(failure.to_string())),
+                (SKIPPED_KEY.into(), Ipld::Link(promise.instruction_cid())),
+                (
+                    BRANCH_KEY.into(),
+                    Ipld::String(promise.result().branch().to_string()),
+                ),
+            ])),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use homestar_core::{test_utils::cid::generate_cid, workflow::Pointer};
+    use rand::thread_rng;
+
+    #[test]
+    fn skipped_to_ipld() {
+        let cid = generate_cid(&mut thread_rng());
+        let promise = Await::new(Pointer::new(cid), AwaitResult::Error);
+        let ipld = Ipld::from(TaskFailure::Skipped(promise));
+
+        assert_eq!(
+            ipld,
+            Ipld::Map(BTreeMap::from([
+                (
+                    ERROR_KEY.into(),
+                    Ipld::String(format!(
+                        "skipped, as awaited instruction {cid} did not fail"
+                    ))
+                ),
+                (SKIPPED_KEY.into(), Ipld::Link(cid)),
+                (BRANCH_KEY.into(), Ipld::String("await/error".into())),
+            ]))
+        );
+    }
+}
@@ -5,11 +5,24 @@
 
 #[cfg(feature = "ipfs")]
 use crate::network::IpfsCli;
-use crate::workflow::{self, Resource};
-use anyhow::Result;
+#[cfg(all(not(test), not(feature = "test-utils")))]
+use crate::tasks::unixfs;
+use crate::{
+    network::BlockExchange,
+    settings::ResourceLimits,
+    workflow::{self, Resource},
+};
+use anyhow::{anyhow, bail, ensure, Result};
 use fnv::FnvHashSet;
+use futures::{stream::FuturesUnordered, Future, TryStreamExt};
 use indexmap::IndexMap;
-use std::sync::Arc;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
+use tokio::io::AsyncReadExt;
+use url::Url;
 
 pub(crate) struct Fetch;
 
@@ -20,82 +33,57 @@ const CAT_CID: &str = "bafybeiejevluvtoevgk66plh5t6xiy3ikyuuxg3vgofuvpeckb6eadre
 
 impl Fetch {
     /// Gather resources from IPFS or elsewhere, leveraging an exponential backoff.
+    ///
+    /// Resources addressed by [Cid], including `ipfs://` and IPFS gateway
+    /// URLs, are fetched block-by-block, with each block verified against
+    /// its [Cid]. Blocks are looked up in the blockstore first, then on
+    /// IPFS, and then from peers providing them, via the [BlockExchange].
+    ///
+    /// `file://` resources are only read from within the `files` directory,
+    /// and refused if it's not set. All content is bounded by the given
+    /// [ResourceLimits].
+    ///
+    /// [Cid]: libipld::Cid
     #[cfg(all(feature = "ipfs", not(test), not(feature = "test-utils")))]
     #[cfg_attr(docsrs, doc(cfg(feature = "ipfs")))]
     pub(crate) async fn get_resources(
         resources: FnvHashSet<Resource>,
         settings: Arc<workflow::Settings>,
+        files: Option<PathBuf>,
+        limits: ResourceLimits,
+        exchange: BlockExchange,
         ipfs: IpfsCli,
     ) -> Result<IndexMap<Resource, Vec<u8>>> {
-        use futures::{stream::FuturesUnordered, TryStreamExt};
-        let settings = settings.as_ref();
-        let retries = settings.retries;
-        let tasks = FuturesUnordered::new();
-        for rsc in resources.iter() {
-            let task = tryhard::retry_fn(|| async {
-                tracing::info!(
-                    subject = "fetch_rsc",
-                    category = "fetch",
-                    rsc = rsc.to_string(),
-                    "attempting to fetch resource from IPFS"
-                );
-                Self::fetch(rsc.clone(), ipfs.clone()).await
-            })
-            .retries(retries)
-            .exponential_backoff(settings.retry_initial_delay)
-            .max_delay(settings.retry_max_delay)
-            .on_retry(|attempts, next_delay, error| {
-                let err = error.to_string();
-                async move {
-                    if attempts < retries {
-                        tracing::warn!(
-                            subject = "fetch_rsc.err",
-                            category = "fetch",
-                            err = err,
-                            attempts = attempts,
-                            "retrying fetch after error @ {}ms",
-                            next_delay.map(|d| d.as_millis()).unwrap_or(0)
-                        );
-                    } else {
-                        tracing::warn!(
-                            subject = "fetch_rsc.err",
-                            category = "fetch",
-                            err = err,
-                            attempts = attempts,
-                            "maxed out # of retries"
-                        );
-                    }
-                }
-            });
-            tasks.push(task);
-        }
-
-        tracing::info!(
-            subject = "fetch_rscs",
-            category = "fetch",
-            "fetching necessary resources from IPFS"
-        );
-        if let Ok(vec) = tasks.try_collect::<Vec<_>>().await {
-            vec.into_iter()
-                .try_fold(IndexMap::default(), |mut acc, res| {
-                    let answer = res.1?;
-                    acc.insert(res.0, answer);
-
-                    Ok::<_, anyhow::Error>(acc)
-                })
-        } else {
-            Err(anyhow::anyhow!("Failed to fetch resources from IPFS"))
-        }
+        Self::with_retries(resources, settings, |rsc| {
+            Self::fetch(rsc, files.clone(), limits, exchange.clone(), ipfs.clone())
+        })
+        .await
     }
 
-    /// Gather resources via URLs, leveraging an exponential backoff.
+    /// Gather resources via `http(s)://` and `file://` URLs, leveraging an
+    /// exponential backoff.
+    ///
+    /// Resources addressed by [Cid], including `ipfs://` and IPFS gateway
+    /// URLs, are gathered from the blockstore or from peers providing them,
+    /// via the [BlockExchange].
+    ///
+    /// `file://` resources are only read from within the `files` directory,
+    /// and refused if it's not set. All content is bounded by the given
+    /// [ResourceLimits].
+    ///
+    /// [Cid]: libipld::Cid
     #[cfg(all(not(feature = "ipfs"), not(test), not(feature = "test-utils")))]
-    #[allow(dead_code)]
-    pub(crate) async fn get_resources<T>(
-        _resources: FnvHashSet<Resource>,
-        _settings: Arc<workflow::Settings>,
-    ) -> Result<IndexMap<Resource, T>> {
-        Ok(IndexMap::default())
+    pub(crate) async fn get_resources(
+        resources: FnvHashSet<Resource>,
+        settings: Arc<workflow::Settings>,
+        files: Option<PathBuf>,
+        limits: ResourceLimits,
+        exchange: BlockExchange,
+    ) -> Result<IndexMap<Resource, Vec<u8>>> {
+        Self::with_retries(resources, settings, |rsc| {
+            Self::fetch(rsc, files.clone(), limits, exchange.clone())
+        })
+        .await
     }
 
     #[cfg(all(not(feature = "ipfs"), any(test, feature = "test-utils")))]
@@ -104,6 +92,9 @@ impl Fetch {
     pub(crate) async fn get_resources(
         _resources: FnvHashSet<Resource>,
         _settings: Arc<workflow::Settings>,
+        _files: Option<PathBuf>,
+        _limits: ResourceLimits,
+        _exchange: BlockExchange,
     ) -> Result<IndexMap<Resource, Vec<u8>>> {
         println!("Running in test mode");
         use crate::tasks::FileLoad;
@@ -133,6 +124,9 @@ impl Fetch {
     pub(crate) async fn get_resources(
         _resources: FnvHashSet<Resource>,
         _settings: Arc<workflow::Settings>,
+        _files: Option<PathBuf>,
+        _limits: ResourceLimits,
+        _exchange: BlockExchange,
         _ipfs: IpfsCli,
     ) -> Result<IndexMap<Resource, Vec<u8>>> {
         println!("Running in test mode");
@@ -157,42 +151,461 @@ impl Fetch {
         Ok(map)
     }
 
-    #[cfg(all(feature = "ipfs", not(test), not(feature = "test-utils")))]
-    async fn fetch(rsc: Resource, client: IpfsCli) -> Result<(Resource, Result<Vec<u8>>)> {
-        match rsc {
-            Resource::Url(url) => {
-                let bytes = match (url.scheme(), url.domain(), url.path()) {
-                    ("ipfs", Some(cid), _) => {
-                        let parsed_cid = libipld::Cid::try_from(cid)?;
-                        client.get_cid(parsed_cid).await
-                    }
-                    (_, Some("ipfs.io"), _) => client.get_resource(&url).await,
-                    (_, _, path) if path.contains("/ipfs/") || path.contains("/ipns/") => {
-                        client.get_resource(&url).await
-                    }
-                    (_, Some(domain), _) => {
-                        let split: Vec<&str> = domain.splitn(3, '.').collect();
-                        // subdomain-gateway case:
-                        // <https://bafybeiemxf5abjwjbikoz4mc3a3dla6ual3jsgpdr4cjr3oz3evfyavhwq.ipfs.dweb.link/wiki/>
-                        if let (Ok(_cid), "ipfs") = (libipld::Cid::try_from(split[0]), split[1]) {
-                            client.get_resource(&url).await
+    // Fetch each resource, retrying failed fetches with an exponential
+    // backoff.
+    #[cfg_attr(feature = "test-utils", allow(dead_code))]
+    async fn with_retries<F, Fut>(
+        resources: FnvHashSet<Resource>,
+        settings: Arc<workflow::Settings>,
+        fetch: F,
+    ) -> Result<IndexMap<Resource, Vec<u8>>>
+    where
+        F: Fn(Resource) -> Fut,
+        Fut: Future<Output = Result<Vec<u8>>>,
+    {
+        let settings = settings.as_ref();
+        let retries = settings.retries;
+        let fetch = &fetch;
+        let tasks = FuturesUnordered::new();
+        for rsc in resources.into_iter() {
+            let task = async move {
+                let started = Instant::now();
+                let fetched = tryhard::retry_fn(|| {
+                    tracing::info!(
+                        subject = "fetch_rsc",
+                        category = "fetch",
+                        rsc = rsc.to_string(),
+                        "attempting to fetch resource"
+                    );
+                    fetch(rsc.clone())
+                })
+                .retries(retries)
+                .exponential_backoff(settings.retry_initial_delay)
+                .max_delay(settings.retry_max_delay)
+                .on_retry(|attempts, next_delay, error| {
+                    let err = error.to_string();
+                    async move {
+                        if attempts < retries {
+                            tracing::warn!(
+                                subject = "fetch_rsc.err",
+                                category = "fetch",
+                                err = err,
+                                attempts = attempts,
+                                "retrying fetch after error @ {}ms",
+                                next_delay.map(|d| d.as_millis()).unwrap_or(0)
+                            );
                         } else {
-                            // TODO: reqwest call or error
-                            todo!()
+                            tracing::warn!(
+                                subject = "fetch_rsc.err",
+                                category = "fetch",
+                                err = err,
+                                attempts = attempts,
+                                "maxed out # of retries"
+                            );
                         }
                     }
-                    // TODO: reqwest call or error
-                    (_, _, _) => todo!(),
+                })
+                .await;
+                crate::metrics::record_resource_fetch(&rsc, started.elapsed(), fetched.is_ok());
+                let bytes =
+                    fetched.map_err(|err| anyhow!("failed to fetch resource {rsc}: {err}"))?;
+
+                Ok::<_, anyhow::Error>((rsc, bytes))
+            };
+            tasks.push(task);
+        }
+
+        tracing::info!(
+            subject = "fetch_rscs",
+            category = "fetch",
+            "fetching necessary resources"
+        );
+        tasks.try_collect::<IndexMap<_, _>>().await
+    }
+
+    #[cfg(all(feature = "ipfs", not(test), not(feature = "test-utils")))]
+    async fn fetch(
+        rsc: Resource,
+        files: Option<PathBuf>,
+        limits: ResourceLimits,
+        exchange: BlockExchange,
+        client: IpfsCli,
+    ) -> Result<Vec<u8>> {
+        let get_block = |cid| exchange.get_block(cid, &client);
+        match rsc {
+            Resource::Url(url) => match (url.scheme(), url.domain()) {
+                ("ipfs", Some(cid)) => {
+                    let parsed_cid = libipld::Cid::try_from(cid)?;
+                    unixfs::cat(parsed_cid, limits, get_block).await
+                }
+                _ => match gateway_cid(&url) {
+                    Some(cid) => unixfs::cat(cid?, limits, get_block).await,
+                    None => Self::fetch_url(&url, files.as_deref(), limits).await,
+                },
+            },
+            Resource::Cid(cid) => unixfs::cat(cid, limits, get_block).await,
+        }
+    }
+
+    #[cfg(all(not(feature = "ipfs"), not(test), not(feature = "test-utils")))]
+    async fn fetch(
+        rsc: Resource,
+        files: Option<PathBuf>,
+        limits: ResourceLimits,
+        exchange: BlockExchange,
+    ) -> Result<Vec<u8>> {
+        let get_block = |cid| exchange.get_block(cid);
+        match rsc {
+            Resource::Url(url) => match (url.scheme(), url.domain()) {
+                ("ipfs", Some(cid)) => {
+                    let parsed_cid = libipld::Cid::try_from(cid)?;
+                    unixfs::cat(parsed_cid, limits, get_block).await
+                }
+                _ => match gateway_cid(&url) {
+                    Some(cid) => unixfs::cat(cid?, limits, get_block).await,
+                    None => Self::fetch_url(&url, files.as_deref(), limits).await,
+                },
+            },
+            Resource::Cid(cid) => unixfs::cat(cid, limits, get_block).await,
+        }
+    }
+
+    /// Fetch a resource over `http(s)://` or from local disk via a
+    /// `file://` URL, which must resolve to a path within the `files`
+    /// directory.
+    ///
+    /// Resources larger than the `max_size` of the given [ResourceLimits]
+    /// are refused, without reading past the limit.
+    #[cfg_attr(feature = "test-utils", allow(dead_code))]
+    async fn fetch_url(url: &Url, files: Option<&Path>, limits: ResourceLimits) -> Result<Vec<u8>> {
+        let exceeds_limit = || {
+            anyhow!(
+                "resource {url} exceeds the limit of {} bytes",
+                limits.max_size
+            )
+        };
+
+        match url.scheme() {
+            "http" | "https" => {
+                let mut resp = reqwest::get(url.clone()).await?.error_for_status()?;
+                if resp
+                    .content_length()
+                    .map_or(false, |len| len > limits.max_size)
+                {
+                    return Err(exceeds_limit());
+                }
+
+                // The content length may be missing or wrong, so the body
+                // is still checked as it's read.
+                let mut content = Vec::new();
+                while let Some(chunk) = resp.chunk().await? {
+                    if (content.len() + chunk.len()) as u64 > limits.max_size {
+                        return Err(exceeds_limit());
+                    }
+                    content.extend_from_slice(&chunk);
+                }
+                Ok(content)
+            }
+            "file" => {
+                let files =
+                    files.ok_or_else(|| anyhow!("file resources are disabled, refusing {url}"))?;
+                let path = url
+                    .to_file_path()
+                    .map_err(|_| anyhow!("invalid file path for resource {url}"))?;
+
+                // Resolve symlinks and `..` components before checking the
+                // path is within the allowed directory.
+                let files = tokio::fs::canonicalize(files).await?;
+                let path = tokio::fs::canonicalize(path)
+                    .await
+                    .map_err(|err| anyhow!("cannot resolve resource {url}: {err}"))?;
+                ensure!(
+                    path.starts_with(&files),
+                    "resource {url} is outside of {}",
+                    files.display()
+                );
+
+                let file = tokio::fs::File::open(path).await?;
+                if file.metadata().await?.len() > limits.max_size {
+                    return Err(exceeds_limit());
+                }
+
+                // Bound the read as well, in case the file grew since.
+                let mut content = Vec::new();
+                file.take(limits.max_size + 1)
+                    .read_to_end(&mut content)
+                    .await?;
+                if content.len() as u64 > limits.max_size {
+                    return Err(exceeds_limit());
+                }
+                Ok(content)
+            }
+            scheme => bail!("unsupported scheme {scheme} for resource {url}"),
+        }
+    }
+}
+
+/// Extract the [Cid] of an IPFS gateway [Url], either path-based, e.g.
+/// `https://ipfs.io/ipfs/<cid>`, or subdomain-based, e.g.
+/// `https://<cid>.ipfs.dweb.link`, so its content can be fetched and
+/// verified like any other [Cid]-addressed resource, rather than trusting
+/// the gateway.
+///
+/// Returns `None` if the [Url] isn't a gateway one, and an error for
+/// gateway [Url]s whose content can't be verified against a [Cid], i.e.
+/// `/ipns/` names and paths into a directory.
+///
+/// [Cid]: libipld::Cid
+#[cfg_attr(feature = "test-utils", allow(dead_code))]
+fn gateway_cid(url: &Url) -> Option<Result<libipld::Cid>> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+
+    let unverifiable = || {
+        anyhow!("cannot verify content of gateway resource {url}, refer to it by its cid instead")
+    };
+    let mut segments = url
+        .path_segments()
+        .into_iter()
+        .flatten()
+        .filter(|segment| !segment.is_empty());
+    let labels: Vec<&str> = url
+        .domain()
+        .map_or_else(Vec::new, |domain| domain.splitn(3, '.').collect());
+
+    match (labels.as_slice(), segments.next()) {
+        ([cid, "ipfs", _], first) => {
+            let cid = libipld::Cid::try_from(*cid).ok()?;
+            Some(first.map_or(Ok(cid), |_| Err(unverifiable())))
+        }
+        ([_, "ipns", _], _) | (_, Some("ipns")) => Some(Err(unverifiable())),
+        (_, Some("ipfs")) => Some(
+            match (segments.next().map(libipld::Cid::try_from), segments.next()) {
+                (Some(Ok(cid)), None) => Ok(cid),
+                _ => Err(unverifiable()),
+            },
+        ),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    // Local HTTP stand-in serving `body` for every request, answering the
+    // first `failures` requests with a `503`.
+    async fn serve(body: &'static [u8], failures: usize) -> (Url, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0; 1024];
+                let _ = stream.read(&mut buf).await;
+                let status = if counter.fetch_add(1, Ordering::SeqCst) < failures {
+                    "503 Service Unavailable"
+                } else {
+                    "200 OK"
                 };
+                let head = format!(
+                    "HTTP/1.1 {status}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                    body.len()
+                );
+                let _ = stream.write_all(head.as_bytes()).await;
+                let _ = stream.write_all(body).await;
+            }
+        });
+
+        (
+            Url::parse(&format!("http://{addr}/add.wasm")).unwrap(),
+            requests,
+        )
+    }
 
-                Ok((Resource::Url(url), bytes))
+    fn fixtures() -> PathBuf {
+        std::fs::canonicalize(format!(
+            "{}/../homestar-wasm/fixtures",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap()
+    }
+
+    fn settings(retries: u32) -> Arc<workflow::Settings> {
+        Arc::new(workflow::Settings {
+            retries,
+            retry_initial_delay: Duration::from_millis(10),
+            retry_max_delay: Duration::from_millis(50),
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn fetch_over_http_and_file() {
+        let (http, _) = serve(b"beep boop", 0).await;
+        let files = fixtures();
+        let path = files.join("example_test.wasm");
+        let file = Url::from_file_path(std::fs::canonicalize(&path).unwrap()).unwrap();
+
+        let resources =
+            FnvHashSet::from_iter([Resource::Url(http.clone()), Resource::Url(file.clone())]);
+        let files = &files;
+        let fetched = Fetch::with_retries(resources, settings(0), |rsc| async move {
+            match rsc {
+                Resource::Url(url) => {
+                    Fetch::fetch_url(&url, Some(files), ResourceLimits::default()).await
+                }
+                Resource::Cid(cid) => bail!("unexpected resource {cid}"),
             }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(fetched.get(&Resource::Url(http)).unwrap(), b"beep boop");
+        assert_eq!(
+            fetched.get(&Resource::Url(file)).unwrap(),
+            &std::fs::read(path).unwrap()
+        );
+    }
 
-            Resource::Cid(cid) => {
-                // TODO: Check blockstore first.
-                let bytes = client.get_cid(cid).await;
-                Ok((Resource::Cid(cid), bytes))
+    #[tokio::test]
+    async fn retry_failed_http_fetch() {
+        let (http, requests) = serve(b"beep boop", 2).await;
+        let fetch = |rsc| async move {
+            match rsc {
+                Resource::Url(url) => Fetch::fetch_url(&url, None, ResourceLimits::default()).await,
+                Resource::Cid(cid) => bail!("unexpected resource {cid}"),
             }
+        };
+
+        let resources = FnvHashSet::from_iter([Resource::Url(http.clone())]);
+        let err = Fetch::with_retries(resources.clone(), settings(1), fetch)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains(http.as_str()));
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        requests.store(0, Ordering::SeqCst);
+        let fetched = Fetch::with_retries(resources, settings(2), fetch)
+            .await
+            .unwrap();
+        assert_eq!(fetched.get(&Resource::Url(http)).unwrap(), b"beep boop");
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn unsupported_scheme() {
+        let url = Url::parse("ftp://example.com/add.wasm").unwrap();
+        assert!(Fetch::fetch_url(&url, None, ResourceLimits::default())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn refuse_files_outside_of_root() {
+        let files = fixtures();
+        let file = Url::from_file_path(files.join("example_test.wasm")).unwrap();
+        assert!(
+            Fetch::fetch_url(&file, Some(&files), ResourceLimits::default())
+                .await
+                .is_ok()
+        );
+
+        // Disabled unless a root directory is set.
+        let err = Fetch::fetch_url(&file, None, ResourceLimits::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("disabled"));
+
+        // Traversing out of the root directory.
+        let traversal = Url::parse(&format!(
+            "file://{}/../../homestar-runtime/Cargo.toml",
+            files.display()
+        ))
+        .unwrap();
+        let err = Fetch::fetch_url(&traversal, Some(&files), ResourceLimits::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("outside of"));
+
+        let outside = Url::from_file_path(
+            std::fs::canonicalize(format!("{}/Cargo.toml", env!("CARGO_MANIFEST_DIR"))).unwrap(),
+        )
+        .unwrap();
+        assert!(
+            Fetch::fetch_url(&outside, Some(&files), ResourceLimits::default())
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn refuse_resources_over_size_limit() {
+        let (http, _) = serve(b"beep boop", 0).await;
+        let files = fixtures();
+        let path = files.join("example_test.wasm");
+        let file = Url::from_file_path(&path).unwrap();
+        let file_len = std::fs::metadata(&path).unwrap().len();
+
+        let limits = |max_size| ResourceLimits {
+            max_size,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            Fetch::fetch_url(&http, None, limits(9)).await.unwrap(),
+            b"beep boop"
+        );
+        let err = Fetch::fetch_url(&http, None, limits(8)).await.unwrap_err();
+        assert!(err.to_string().contains("exceeds the limit"));
+
+        assert!(Fetch::fetch_url(&file, Some(&files), limits(file_len))
+            .await
+            .is_ok());
+        let err = Fetch::fetch_url(&file, Some(&files), limits(file_len - 1))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("exceeds the limit"));
+    }
+
+    #[test]
+    fn gateway_urls_by_cid() {
+        let cid = libipld::Cid::try_from(CAT_CID).unwrap();
+        let gateway_cid = |url: &str| gateway_cid(&Url::parse(url).unwrap());
+
+        for url in [
+            format!("https://ipfs.io/ipfs/{CAT_CID}"),
+            format!("http://127.0.0.1:8080/ipfs/{CAT_CID}/"),
+            format!("https://{CAT_CID}.ipfs.dweb.link"),
+        ] {
+            assert_eq!(gateway_cid(&url).unwrap().unwrap(), cid);
         }
+
+        // Content that can't be verified against a cid is refused.
+        for url in [
+            format!("https://ipfs.io/ipfs/{CAT_CID}/wiki/"),
+            format!("https://{CAT_CID}.ipfs.dweb.link/wiki/"),
+            "https://ipfs.io/ipns/example.com".to_string(),
+            "https://example-com.ipns.dweb.link".to_string(),
+            "https://ipfs.io/ipfs/not-a-cid".to_string(),
+        ] {
+            assert!(gateway_cid(&url).unwrap().is_err());
+        }
+
+        // Anything else isn't a gateway url.
+        assert!(gateway_cid("https://ipfs.io/add.wasm").is_none());
+        assert!(gateway_cid("https://www.ipfs.example.com/add.wasm").is_none());
+        assert!(gateway_cid("file:///ipfs/add.wasm").is_none());
     }
 }
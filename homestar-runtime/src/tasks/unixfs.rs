@@ -0,0 +1,288 @@
+//! Verified retrieval of content addressed by [Cid], re-hashing every block
+//! against the multihash of the [Cid] it was fetched for.
+//!
+//! [UnixFS] files, i.e. `dag-pb` DAGs, are reassembled from their verified
+//! blocks, as file content, e.g. returned by `ipfs cat`, can't be checked
+//! against the root [Cid] directly.
+//!
+//! [UnixFS]: https://github.com/ipfs/specs/blob/main/UNIXFS.md
+
+use crate::settings::ResourceLimits;
+use anyhow::{anyhow, bail, ensure, Result};
+use futures::Future;
+use libipld::{
+    multihash::{Code, MultihashDigest},
+    Cid,
+};
+use quick_protobuf::BytesReader;
+
+const DAG_PB: u64 = 0x70;
+
+// Protobuf tags for the `dag-pb` and UnixFS fields used here, i.e.
+// `(field_number << 3) | wire_type`.
+const PB_NODE_DATA: u32 = 10;
+const PB_NODE_LINKS: u32 = 18;
+const PB_LINK_HASH: u32 = 10;
+const UNIXFS_TYPE: u32 = 8;
+const UNIXFS_DATA: u32 = 18;
+
+// UnixFS data-types holding file content.
+const UNIXFS_RAW: i32 = 0;
+const UNIXFS_FILE: i32 = 2;
+
+/// Verify that a block hashes to the multihash of its [Cid].
+pub(crate) fn verify(cid: Cid, block: &[u8]) -> Result<()> {
+    let code = Code::try_from(cid.hash().code())
+        .map_err(|_| anyhow!("unsupported multihash for block {cid}"))?;
+    ensure!(
+        code.digest(block) == *cid.hash(),
+        "block content does not match its cid {cid}"
+    );
+
+    Ok(())
+}
+
+/// Retrieve the content of a [Cid] through `get_block`, verifying each block
+/// along the way.
+///
+/// UnixFS files are reassembled from their leaves, in order; blocks of any
+/// other codec, e.g. `raw`, are returned whole. Retrieval stops as soon as
+/// the content would exceed the [ResourceLimits] on its size or number of
+/// nodes.
+#[cfg_attr(feature = "test-utils", allow(dead_code))]
+pub(crate) async fn cat<F, Fut>(cid: Cid, limits: ResourceLimits, get_block: F) -> Result<Vec<u8>>
+where
+    F: Fn(Cid) -> Fut,
+    Fut: Future<Output = Result<Vec<u8>>>,
+{
+    let root = cid;
+    let mut content = vec![];
+    let mut stack = vec![cid];
+    let mut nodes = 0;
+    while let Some(cid) = stack.pop() {
+        let block = get_block(cid).await?;
+        verify(cid, &block)?;
+
+        let data = match cid.codec() {
+            DAG_PB => {
+                let (data, links) = decode_file(&block)
+                    .map_err(|err| anyhow!("invalid UnixFS block {cid}: {err}"))?;
+                stack.extend(links.into_iter().rev());
+                data
+            }
+            _ => block.as_slice(),
+        };
+
+        nodes += 1;
+        ensure!(
+            nodes + stack.len() <= limits.max_nodes,
+            "content of {root} exceeds the limit of {} nodes",
+            limits.max_nodes
+        );
+        ensure!(
+            (content.len() + data.len()) as u64 <= limits.max_size,
+            "content of {root} exceeds the limit of {} bytes",
+            limits.max_size
+        );
+        content.extend_from_slice(data);
+    }
+
+    Ok(content)
+}
+
+// Decode a `dag-pb` block into its inline UnixFS file data and its child
+// links, in order.
+fn decode_file(block: &[u8]) -> Result<(&[u8], Vec<Cid>)> {
+    let mut reader = BytesReader::from_bytes(block);
+    let mut unixfs = None;
+    let mut links = vec![];
+    while !reader.is_eof() {
+        match reader.next_tag(block)? {
+            PB_NODE_DATA => unixfs = Some(reader.read_bytes(block)?),
+            PB_NODE_LINKS => links.push(decode_link(reader.read_bytes(block)?)?),
+            tag => reader.read_unknown(block, tag)?,
+        }
+    }
+
+    let unixfs = unixfs.ok_or_else(|| anyhow!("missing UnixFS data"))?;
+    let mut reader = BytesReader::from_bytes(unixfs);
+    let mut data_type = None;
+    let mut data: &[u8] = &[];
+    while !reader.is_eof() {
+        match reader.next_tag(unixfs)? {
+            UNIXFS_TYPE => data_type = Some(reader.read_enum::<i32>(unixfs)?),
+            UNIXFS_DATA => data = reader.read_bytes(unixfs)?,
+            tag => reader.read_unknown(unixfs, tag)?,
+        }
+    }
+
+    match data_type {
+        Some(UNIXFS_RAW | UNIXFS_FILE) => Ok((data, links)),
+        Some(data_type) => bail!("UnixFS node of type {data_type} is not a file"),
+        None => bail!("missing UnixFS data-type"),
+    }
+}
+
+fn decode_link(link: &[u8]) -> Result<Cid> {
+    let mut reader = BytesReader::from_bytes(link);
+    while !reader.is_eof() {
+        match reader.next_tag(link)? {
+            PB_LINK_HASH => return Ok(Cid::try_from(reader.read_bytes(link)?)?),
+            tag => reader.read_unknown(link, tag)?,
+        }
+    }
+
+    bail!("link is missing its hash")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quick_protobuf::Writer;
+    use std::collections::HashMap;
+
+    const RAW: u64 = 0x55;
+    const UNIXFS_DIRECTORY: i32 = 1;
+
+    fn pb_node(data_type: i32, data: &[u8], links: &[Cid]) -> Vec<u8> {
+        let mut unixfs = vec![];
+        {
+            let mut writer = Writer::new(&mut unixfs);
+            writer
+                .write_with_tag(UNIXFS_TYPE, |w| w.write_enum(data_type))
+                .unwrap();
+            if !data.is_empty() {
+                writer
+                    .write_with_tag(UNIXFS_DATA, |w| w.write_bytes(data))
+                    .unwrap();
+            }
+        }
+
+        let mut node = vec![];
+        {
+            let mut writer = Writer::new(&mut node);
+            for link in links {
+                let mut pb_link = vec![];
+                Writer::new(&mut pb_link)
+                    .write_with_tag(PB_LINK_HASH, |w| w.write_bytes(&link.to_bytes()))
+                    .unwrap();
+                writer
+                    .write_with_tag(PB_NODE_LINKS, |w| w.write_bytes(&pb_link))
+                    .unwrap();
+            }
+            writer
+                .write_with_tag(PB_NODE_DATA, |w| w.write_bytes(&unixfs))
+                .unwrap();
+        }
+
+        node
+    }
+
+    fn put(blocks: &mut HashMap<Cid, Vec<u8>>, codec: u64, block: Vec<u8>) -> Cid {
+        let cid = Cid::new_v1(codec, Code::Sha2_256.digest(&block));
+        blocks.insert(cid, block);
+        cid
+    }
+
+    async fn get(blocks: &HashMap<Cid, Vec<u8>>, cid: Cid) -> Result<Vec<u8>> {
+        blocks
+            .get(&cid)
+            .cloned()
+            .ok_or_else(|| anyhow!("missing block {cid}"))
+    }
+
+    #[tokio::test]
+    async fn cat_unixfs_file() {
+        let mut blocks = HashMap::new();
+        let leaf1 = put(&mut blocks, RAW, b"beep ".to_vec());
+        let leaf2 = put(&mut blocks, DAG_PB, pb_node(UNIXFS_FILE, b"boop", &[]));
+        let leaf3 = put(&mut blocks, RAW, b" bop".to_vec());
+        let inner = put(&mut blocks, DAG_PB, pb_node(UNIXFS_FILE, b"", &[leaf2]));
+        let root = put(
+            &mut blocks,
+            DAG_PB,
+            pb_node(UNIXFS_FILE, b"", &[leaf1, inner, leaf3]),
+        );
+
+        let content = cat(root, ResourceLimits::default(), |cid| get(&blocks, cid))
+            .await
+            .unwrap();
+        assert_eq!(content, b"beep boop bop");
+
+        let raw = cat(leaf1, ResourceLimits::default(), |cid| get(&blocks, cid))
+            .await
+            .unwrap();
+        assert_eq!(raw, b"beep ");
+    }
+
+    #[tokio::test]
+    async fn cat_rejects_tampered_block() {
+        let mut blocks = HashMap::new();
+        let leaf = put(&mut blocks, RAW, b"beep".to_vec());
+        let root = put(&mut blocks, DAG_PB, pb_node(UNIXFS_FILE, b"", &[leaf]));
+        blocks.insert(leaf, b"boop".to_vec());
+
+        let err = cat(root, ResourceLimits::default(), |cid| get(&blocks, cid))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("block content does not match its cid {leaf}")
+        );
+    }
+
+    #[tokio::test]
+    async fn cat_within_limits() {
+        let mut blocks = HashMap::new();
+        let leaf1 = put(&mut blocks, RAW, b"beep ".to_vec());
+        let leaf2 = put(&mut blocks, RAW, b"boop".to_vec());
+        let root = put(
+            &mut blocks,
+            DAG_PB,
+            pb_node(UNIXFS_FILE, b"", &[leaf1, leaf2]),
+        );
+
+        let limits = ResourceLimits {
+            max_size: 9,
+            max_nodes: 3,
+        };
+        let content = cat(root, limits, |cid| get(&blocks, cid)).await.unwrap();
+        assert_eq!(content, b"beep boop");
+
+        let too_large = ResourceLimits {
+            max_size: 8,
+            ..limits
+        };
+        let err = cat(root, too_large, |cid| get(&blocks, cid))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("content of {root} exceeds the limit of 8 bytes")
+        );
+
+        let too_many_nodes = ResourceLimits {
+            max_nodes: 2,
+            ..limits
+        };
+        let err = cat(root, too_many_nodes, |cid| get(&blocks, cid))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("content of {root} exceeds the limit of 2 nodes")
+        );
+    }
+
+    #[tokio::test]
+    async fn cat_rejects_directory() {
+        let mut blocks = HashMap::new();
+        let root = put(&mut blocks, DAG_PB, pb_node(UNIXFS_DIRECTORY, b"", &[]));
+
+        assert!(
+            cat(root, ResourceLimits::default(), |cid| get(&blocks, cid))
+                .await
+                .is_err()
+        );
+    }
+}
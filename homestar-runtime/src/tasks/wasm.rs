@@ -2,38 +2,156 @@
 //!
 //! [tasks]: homestar_core::workflow::Task
 
-use super::FileLoad;
+use super::{FileLoad, ERROR_KEY};
 use async_trait::async_trait;
 use homestar_core::workflow::input::Args;
 use homestar_wasm::{
     io::{Arg, Output},
-    wasmtime::{world::Env, Error as WasmRuntimeError, State, World},
+    wasmtime::{world::Env, ComponentCache, Error as WasmRuntimeError, State, World},
 };
+use libipld::{
+    multihash::{Code, MultihashDigest},
+    Cid, Ipld,
+};
+use std::{collections::BTreeMap, fmt, time::Duration};
+use url::Url;
+
+const LIMIT_KEY: &str = "limit";
+const MAX_KEY: &str = "max";
+const RAW: u64 = 0x55;
+
+/// Resource limit tripped while executing a Wasm-based task.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ResourceLimit {
+    /// Task ran out of its fuel quota.
+    Fuel(u64),
+    /// Task attempted to grow memory past its maximum, in bytes.
+    Memory(u64),
+    /// Task ran past its time budget.
+    Time(Duration),
+}
+
+impl ResourceLimit {
+    /// Name of the tripped limit, matching the key used within the
+    /// task's [Resources] metadata.
+    ///
+    /// [Resources]: homestar_core::workflow::config::Resources
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            ResourceLimit::Fuel(_) => "fuel",
+            ResourceLimit::Memory(_) => "memory",
+            ResourceLimit::Time(_) => "time",
+        }
+    }
+}
+
+impl fmt::Display for ResourceLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResourceLimit::Fuel(fuel) => write!(f, "exceeded fuel limit of {fuel}"),
+            ResourceLimit::Memory(bytes) => write!(f, "exceeded memory limit of {bytes} bytes"),
+            ResourceLimit::Time(time) => {
+                write!(f, "exceeded time limit of {}ms", time.as_millis())
+            }
+        }
+    }
+}
+
+impl From<ResourceLimit> for Ipld {
+    fn from(limit: ResourceLimit) -> Self {
+        let max = match limit {
+            ResourceLimit::Fuel(fuel) => Ipld::from(fuel),
+            ResourceLimit::Memory(bytes) => Ipld::from(bytes),
+            ResourceLimit::Time(time) => Ipld::from(time.as_millis() as i128),
+        };
+
+        Ipld::Map(BTreeMap::from([
+            (ERROR_KEY.into(), Ipld::String(limit.to_string())),
+            (LIMIT_KEY.into(), Ipld::String(limit.name().to_string())),
+            (MAX_KEY.into(), max),
+        ]))
+    }
+}
+
+/// [Cid] keying a Wasm resource within the [ComponentCache].
+///
+/// Resources given as `ipfs://<cid>` URLs are keyed by their [Cid]; any
+/// other resource is keyed by a raw [Cid] of its bytes.
+pub(crate) fn resource_cid(url: &Url, bytes: &[u8]) -> Cid {
+    match (url.scheme(), url.domain().map(Cid::try_from)) {
+        ("ipfs", Some(Ok(cid))) => cid,
+        _ => Cid::new_v1(RAW, Code::Sha3_256.digest(bytes)),
+    }
+}
 
 #[allow(dead_code)]
 #[allow(missing_debug_implementations)]
 pub(crate) struct WasmContext {
     env: Env<State>,
+    cache: ComponentCache,
 }
 
 impl WasmContext {
+    /// Create a new [WasmContext] on the [ComponentCache]'s shared engine.
     #[allow(dead_code)]
-    pub(crate) fn new(data: State) -> Result<Self, WasmRuntimeError> {
+    pub(crate) fn new(data: State, cache: ComponentCache) -> Result<Self, WasmRuntimeError> {
         Ok(Self {
-            env: World::default(data)?,
+            env: World::with_engine(cache.engine().clone(), data)?,
+            cache,
         })
     }
 
-    /// Instantiate environment via [World] and execute on [Args].
+    /// Instantiate environment via [World] and execute on [Args], compiling
+    /// the component only if it's not already cached under the resource
+    /// [Cid].
     #[allow(dead_code)]
     pub(crate) async fn run<'a>(
         &mut self,
+        rsc_cid: Cid,
         bytes: Vec<u8>,
         fun_name: &'a str,
         args: Args<Arg>,
     ) -> Result<Output, WasmRuntimeError> {
-        let env = World::instantiate_with_current_env(bytes, fun_name, &mut self.env).await?;
-        env.execute(args).await
+        let res = match self.cache.get_or_compile(rsc_cid, &bytes) {
+            Ok(component) => {
+                match World::instantiate_component_with_current_env(
+                    &component,
+                    fun_name,
+                    &mut self.env,
+                )
+                .await
+                {
+                    Ok(env) => env.execute(args).await,
+                    Err(err) => Err(err),
+                }
+            }
+            Err(err) => Err(err),
+        };
+
+        // Denied memory growth surfaces as a generic runtime error from
+        // wasmtime, so check the store's limiter to disambiguate.
+        let limits = self.env.store().data().limits();
+        match res {
+            Err(_) if limits.memory_limit_reached() => Err(WasmRuntimeError::MemoryLimitExceeded(
+                limits.max_memory_size().unwrap_or_default() as u64,
+            )),
+            res => res,
+        }
+    }
+
+    /// Fuel consumed so far by executions within this context's store.
+    pub(crate) fn fuel_consumed(&self) -> u64 {
+        let store = self.env.store();
+        store
+            .get_fuel()
+            .map(|left| store.data().fuel().saturating_sub(left))
+            .unwrap_or_default()
+    }
+
+    /// Memory, in bytes, grown so far by executions within this context's
+    /// store. Memory never shrinks, so this is also the peak.
+    pub(crate) fn memory_consumed(&self) -> u64 {
+        self.env.store().data().limits().memory_consumed()
     }
 }
 
@@ -52,6 +170,32 @@ mod test {
         ))
     }
 
+    #[test]
+    fn resource_limit_to_ipld() {
+        let ipld = Ipld::from(ResourceLimit::Time(Duration::from_millis(10)));
+        let map = libipld::serde::from_ipld::<BTreeMap<String, Ipld>>(ipld).unwrap();
+
+        assert_eq!(map.get(LIMIT_KEY).unwrap(), &Ipld::String("time".into()));
+        assert_eq!(map.get(MAX_KEY).unwrap(), &Ipld::Integer(10));
+        assert_eq!(
+            map.get(ERROR_KEY).unwrap(),
+            &Ipld::String("exceeded time limit of 10ms".into())
+        );
+    }
+
+    #[test]
+    fn resource_cid_from_url() {
+        let cid =
+            Cid::try_from("bafybeidbyqpmztqkeot33lz4ev2ftjhqrnbh67go56tlgbf7qmy5xyzvg4").unwrap();
+        let ipfs = Url::parse(&format!("ipfs://{cid}")).unwrap();
+        let file = Url::parse("file:///tmp/add.wasm").unwrap();
+
+        assert_eq!(resource_cid(&ipfs, b"beep"), cid);
+        assert_eq!(resource_cid(&file, b"beep"), resource_cid(&file, b"beep"));
+        assert_ne!(resource_cid(&file, b"beep"), resource_cid(&file, b"boop"));
+        assert_eq!(resource_cid(&file, b"beep").codec(), RAW);
+    }
+
     #[tokio::test]
     async fn load_wasm_file_as_bytes() {
         let wat = WasmContext::load(fixtures("example_add_component.wat"))
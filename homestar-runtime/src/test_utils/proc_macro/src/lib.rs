@@ -44,6 +44,7 @@ pub fn db_async_test(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 fn load() -> crate::Settings {
                     let mut settings = crate::Settings::load().unwrap();
                     settings.node.db.url = Some(format!("{}.db", #func_name_as_string));
+                    settings.node.blockstore.path = std::env::temp_dir().join(format!("homestar_{}_blocks", #func_name_as_string));
                     settings
                 }
             }
@@ -102,6 +103,7 @@ pub fn runner_test(_attr: TokenStream, item: TokenStream) -> TokenStream {
                     settings.node.network.rpc.port = ::homestar_core::test_utils::ports::get_port() as u16;
                     settings.node.network.metrics.port = ::homestar_core::test_utils::ports::get_port() as u16;
                     settings.node.db.url = Some(format!("{}.db", #func_name_as_string));
+                    settings.node.blockstore.path = std::env::temp_dir().join(format!("homestar_{}_blocks", #func_name_as_string));
                     settings.node.network.webserver.websocket_receiver_timeout = std::time::Duration::from_millis(500);
                     let db = crate::test_utils::db::MemoryDb::setup_connection_pool(&settings.node, None).unwrap();
                     let runner = crate::Runner::start(settings.clone(), db).unwrap();
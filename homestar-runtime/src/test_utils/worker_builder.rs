@@ -4,9 +4,12 @@ use super::{db::MemoryDb, event};
 #[cfg(feature = "ipfs")]
 use crate::network::IpfsCli;
 use crate::{
+    blockstore::Blockstore,
     channel::AsyncChannelSender,
     db::Database,
     event_handler::Event,
+    network::BlockExchange,
+    receipt::Signer,
     settings,
     tasks::Fetch,
     worker::WorkerMessage,
@@ -21,9 +24,11 @@ use homestar_core::{
     workflow::{config::Resources, instruction::RunInstruction, prf::UcanPrf, Task},
     Workflow,
 };
-use homestar_wasm::io::Arg;
+use homestar_wasm::{io::Arg, wasmtime::ComponentCache};
 use indexmap::IndexMap;
 use libipld::Cid;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
 
 /// Utility structure for building out [Worker]s for testing purposes.
 ///
@@ -48,6 +53,14 @@ pub(crate) struct WorkerBuilder<'a> {
     workflow_settings: workflow::Settings,
     /// Network settings.
     network_settings: settings::Dht,
+    /// Node-wide permits for concurrent Wasm executions.
+    task_permits: Arc<Semaphore>,
+    /// Node-wide cache of compiled Wasm components.
+    component_cache: ComponentCache,
+    /// Exchange of blocks with the embedded blockstore and peers.
+    block_exchange: BlockExchange,
+    /// Signer of the node's receipts.
+    signer: Signer,
 }
 
 /// Utility structure for building out [Worker]s for testing purposes.
@@ -69,6 +82,14 @@ pub(crate) struct WorkerBuilder<'a> {
     workflow_settings: workflow::Settings,
     /// Network settings.
     network_settings: settings::Dht,
+    /// Node-wide permits for concurrent Wasm executions.
+    task_permits: Arc<Semaphore>,
+    /// Node-wide cache of compiled Wasm components.
+    component_cache: ComponentCache,
+    /// Exchange of blocks with the embedded blockstore and peers.
+    block_exchange: BlockExchange,
+    /// Signer of the node's receipts.
+    signer: Signer,
 }
 
 impl<'a> WorkerBuilder<'a> {
@@ -100,12 +121,20 @@ impl<'a> WorkerBuilder<'a> {
             #[cfg(feature = "ipfs")]
             ipfs: ipfs.clone(),
             db: MemoryDb::setup_connection_pool(&settings, None).unwrap(),
-            event_sender: evt_tx,
+            event_sender: evt_tx.clone(),
             runner_sender: wk_tx,
             name: Some(workflow_cid.to_string()),
             workflow,
             workflow_settings: workflow::Settings::default(),
             network_settings: settings::Dht::default(),
+            task_permits: Arc::new(Semaphore::new(settings.max_concurrent_tasks.get())),
+            component_cache: ComponentCache::new(None, settings.wasm_cache_capacity).unwrap(),
+            block_exchange: BlockExchange::new(
+                Blockstore::new(&settings.blockstore),
+                evt_tx.clone().into(),
+                &settings::Dht::default(),
+            ),
+            signer: Signer::new(settings.network.keypair_config.keypair().unwrap()).unwrap(),
         }
     }
 
@@ -119,6 +148,10 @@ impl<'a> WorkerBuilder<'a> {
             self.name,
             self.event_sender.into(),
             self.runner_sender,
+            self.task_permits,
+            self.component_cache,
+            self.signer,
+            None,
             self.db,
         )
         .await
@@ -136,9 +169,21 @@ impl<'a> WorkerBuilder<'a> {
     ) -> impl FnOnce(FnvHashSet<Resource>) -> BoxFuture<'a, anyhow::Result<IndexMap<Resource, Vec<u8>>>>
     {
         let fetch_settings = self.workflow_settings.clone().into();
+        let exchange = self.block_exchange.clone();
         let ipfs = self.ipfs.clone();
         let fetch_fn = move |rscs: FnvHashSet<Resource>| {
-            async move { Fetch::get_resources(rscs, fetch_settings, ipfs).await }.boxed()
+            async move {
+                Fetch::get_resources(
+                    rscs,
+                    fetch_settings,
+                    None,
+                    settings::ResourceLimits::default(),
+                    exchange,
+                    ipfs,
+                )
+                .await
+            }
+            .boxed()
         };
 
         fetch_fn
@@ -155,8 +200,19 @@ impl<'a> WorkerBuilder<'a> {
     ) -> impl FnOnce(FnvHashSet<Resource>) -> BoxFuture<'a, anyhow::Result<IndexMap<Resource, Vec<u8>>>>
     {
         let fetch_settings = self.workflow_settings.clone().into();
-        let fetch_fn = |rscs: FnvHashSet<Resource>| {
-            async move { Fetch::get_resources(rscs, fetch_settings).await }.boxed()
+        let exchange = self.block_exchange.clone();
+        let fetch_fn = move |rscs: FnvHashSet<Resource>| {
+            async move {
+                Fetch::get_resources(
+                    rscs,
+                    fetch_settings,
+                    None,
+                    settings::ResourceLimits::default(),
+                    exchange,
+                )
+                .await
+            }
+            .boxed()
         };
 
         fetch_fn
@@ -168,6 +224,12 @@ impl<'a> WorkerBuilder<'a> {
         self.workflow.clone().to_cid().unwrap()
     }
 
+    /// Get the workflow from the builder state.
+    #[allow(dead_code)]
+    pub(crate) fn workflow(&self) -> Workflow<'a, Arg> {
+        self.workflow.clone()
+    }
+
     /// Get the length of the workflow from the builder state.
     #[allow(dead_code)]
     pub(crate) fn workflow_len(&self) -> u32 {
@@ -11,19 +11,20 @@ use crate::{
     db::Database,
     event_handler::{
         event::{Captured, QueryRecord},
-        swarm_event::{FoundEvent, ResponseEvent},
+        swarm_event::{FoundEvent, ReceiptEvent, ResponseEvent},
         Event,
     },
     network::swarm::CapsuleTag,
+    receipt::{Signer, Stats},
     runner::{ModifiedSet, RunningTaskSet},
-    scheduler::ExecutionGraph,
+    scheduler::{ExecutionGraph, SchedulerMode},
     settings,
-    tasks::{RegisteredTasks, WasmContext},
-    workflow::{self, Resource},
+    tasks::{self, RegisteredTasks, ResourceLimit, TaskFailure, WasmContext},
+    workflow::{self, Authorizer, Resource, Vertex},
     Db, Receipt, TaskScheduler,
 };
-use anyhow::{anyhow, Context, Result};
-use chrono::NaiveDateTime;
+use anyhow::{anyhow, Result};
+use chrono::{NaiveDateTime, Utc};
 use faststr::FastStr;
 use fnv::FnvHashSet;
 use futures::{future::BoxFuture, FutureExt};
@@ -32,6 +33,8 @@ use homestar_core::{
     ipld::DagCbor,
     workflow::{
         error::ResolveError,
+        input::Input,
+        pointer::AwaitResult,
         prf::UcanPrf,
         receipt::metadata::{OP_KEY, REPLAYED_KEY, WORKFLOW_KEY, WORKFLOW_NAME_KEY},
         InstructionResult, LinkMap, Pointer, Receipt as InvocationReceipt,
@@ -39,18 +42,35 @@ use homestar_core::{
     Workflow,
 };
 use homestar_wasm::{
-    io::{Arg, Output},
-    wasmtime::State,
+    io::Arg,
+    wasmtime::{ComponentCache, Error as WasmRuntimeError, State},
 };
 use indexmap::IndexMap;
 use libipld::{Cid, Ipld};
-use std::{collections::BTreeMap, sync::Arc, time::Instant};
-use tokio::{sync::RwLock, task::JoinSet};
-use tracing::{debug, error, info};
+use std::{
+    collections::BTreeMap,
+    mem,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::{
+    sync::{RwLock, Semaphore},
+    task::{AbortHandle, JoinError, JoinSet},
+    time::{timeout_at, Instant},
+};
+use tracing::{debug, error, info, warn};
+
+/// Output of a task run by a [Worker], as its result, instruction and
+/// invocation pointers, and receipt and additional metadata.
+type TaskOutput = (InstructionResult<Ipld>, Pointer, Pointer, Ipld, Ipld);
 
 /// [JoinSet] of tasks run by a [Worker].
 #[allow(dead_code)]
-pub(crate) type TaskSet = JoinSet<anyhow::Result<(Output, Pointer, Pointer, Ipld, Ipld)>>;
+pub(crate) type TaskSet = JoinSet<TaskOutput>;
 
 /// Messages sent to [Worker] from [Runner].
 ///
@@ -59,6 +79,8 @@ pub(crate) type TaskSet = JoinSet<anyhow::Result<(Output, Pointer, Pointer, Ipld
 pub(crate) enum WorkerMessage {
     /// Signal that the [Worker] has been dropped for a workflow run.
     Dropped(Cid),
+    /// Signal that the [Worker] has stopped for a paused workflow run.
+    Paused(Cid),
 }
 
 /// Worker that operates over a given [TaskScheduler].
@@ -75,6 +97,15 @@ pub(crate) struct Worker<'a, DB: Database> {
     ///
     /// [Runner]: crate::Runner
     pub(crate) runner_sender: AsyncChannelSender<WorkerMessage>,
+    /// Node-wide [Semaphore] bounding concurrent Wasm executions.
+    pub(crate) task_permits: Arc<Semaphore>,
+    /// Node-wide cache of compiled Wasm components.
+    pub(crate) component_cache: ComponentCache,
+    /// [Signer] issuing and signing [Receipt]s as the node.
+    pub(crate) signer: Signer,
+    /// [Authorizer] checking tasks' UCAN proofs before they're executed, if
+    /// enforced by the node.
+    pub(crate) authorizer: Option<Authorizer>,
     /// [Database] pool to pull connections from for the [Worker] run.
     pub(crate) db: DB,
     /// Local name of the [Workflow] being run.
@@ -87,6 +118,11 @@ pub(crate) struct Worker<'a, DB: Database> {
     pub(crate) network_settings: Arc<settings::Dht>,
     /// [NaiveDateTime] of when the [Workflow] was started.
     pub(crate) workflow_started: NaiveDateTime,
+    /// Signal to stop scheduling tasks once in-flight tasks complete, set
+    /// by the [Runner] to pause the [Workflow].
+    ///
+    /// [Runner]: crate::Runner
+    pub(crate) paused: Arc<AtomicBool>,
 }
 
 impl<'a, DB> Worker<'a, DB>
@@ -94,9 +130,8 @@ where
     DB: Database + 'static,
 {
     /// Instantiate a new [Worker] for a [Workflow].
-    ///
-    /// TODO: integrate settings within workflow
     #[allow(dead_code)]
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn new<S: Into<FastStr>>(
         workflow: Workflow<'a, Arg>,
         settings: workflow::Settings,
@@ -105,13 +140,27 @@ where
         name: Option<S>,
         event_sender: Arc<AsyncChannelSender<Event>>,
         runner_sender: AsyncChannelSender<WorkerMessage>,
+        task_permits: Arc<Semaphore>,
+        component_cache: ComponentCache,
+        signer: Signer,
+        authorizer: Option<Authorizer>,
         db: DB,
     ) -> Result<Worker<'a, DB>> {
         let workflow_len = workflow.len();
         // Need to take ownership here to get the cid.
         let workflow_cid = workflow.to_owned().to_cid()?;
 
-        let builder = workflow::Builder::new(workflow);
+        // Instruction pointers are resolved into the database ahead of
+        // time, see [workflow::StoredInstruction::resolve].
+        let pointers = workflow::instruction::pointers(&workflow);
+        let instructions = Db::find_stored_instructions(&pointers, &mut db.conn()?)?
+            .into_iter()
+            .map(|stored| Ok((stored.cid(), stored.instruction()?)))
+            .collect::<Result<IndexMap<_, _>>>()?;
+
+        let builder = workflow::Builder::new(workflow)
+            .with_instructions(instructions)
+            .with_resources(settings.resources.clone());
         let graph = builder.graph()?;
         let name = name
             .map(|n| n.into())
@@ -132,12 +181,17 @@ where
             graph: graph.into(),
             event_sender,
             runner_sender,
+            task_permits,
+            component_cache,
+            signer,
+            authorizer,
             db,
             workflow_name: name,
             workflow_info: workflow_info.into(),
             workflow_settings: settings.into(),
             workflow_started: timestamp,
             network_settings: network_settings.into(),
+            paused: Arc::new(AtomicBool::new(false)),
         })
     }
 
@@ -147,7 +201,9 @@ where
     /// This is the main entry point for running a workflow.
     ///
     /// Within this function, the [Worker] executes tasks and resolves
-    /// [Instruction] [Cid]s.
+    /// [Instruction] [Cid]s. Tasks are dispatched either batch-by-batch or
+    /// as soon as the tasks they await on complete, per the workflow's
+    /// [SchedulerMode].
     ///
     /// [Instruction] [Cid]s being awaited on are resolved via 3 lookups:
     ///   * a check in the [LinkMap], which is an in-memory cache of resolved
@@ -157,13 +213,39 @@ where
     ///   execution;
     ///   * a [Swarm]/DHT query to find the [Receipt] in the network.
     ///
+    /// Promises on [Instruction]s outside of the [Workflow] additionally wait,
+    /// up to a configured deadline, on the [Receipt] to be captured locally or
+    /// received over gossip.
+    ///
+    /// Tasks that fail are committed as [InstructionResult::Error] receipts,
+    /// without halting tasks that don't depend on them. Tasks awaiting on a
+    /// failed task's `await/ok` result, or a successful task's `await/error`
+    /// result, are skipped, recording the reason as their own
+    /// [InstructionResult::Error] receipt. `await/error` branches are handed
+    /// the failed task's error payload as input.
+    ///
+    /// The workflow's stored [Status] moves to running once the [Worker]
+    /// starts, and to completed or failed once it's done, unless it was
+    /// paused.
+    ///
     /// [Instruction]: homestar_core::workflow::Instruction
     /// [Swarm]: crate::network::swarm
+    /// [Status]: workflow::Status
     pub(crate) async fn run<F>(self, running_tasks: Arc<RunningTaskSet>, fetch_fn: F) -> Result<()>
     where
         F: FnOnce(FnvHashSet<Resource>) -> BoxFuture<'a, Result<IndexMap<Resource, Vec<u8>>>>,
     {
-        match TaskScheduler::init(
+        let workflow_cid = self.workflow_info.cid;
+        let db = self.db.clone();
+        let paused = Arc::clone(&self.paused);
+        Db::update_workflow_status(
+            workflow_cid,
+            workflow::Status::Running,
+            None,
+            &mut db.conn()?,
+        )?;
+
+        let result = match TaskScheduler::init(
             self.graph.clone(), // Arc'ed
             &mut self.db.conn()?,
             fetch_fn,
@@ -176,9 +258,26 @@ where
                        category = "worker.run",
                        err=?err,
                        "error initializing scheduler");
-                Err(anyhow!("error initializing scheduler"))
+                Err(err.context("error initializing scheduler"))
+            }
+        };
+
+        // Paused workflows carry on running once resumed.
+        let outcome = match &result {
+            Ok(()) if paused.load(Ordering::Acquire) => None,
+            Ok(()) => Some((workflow::Status::Completed, None)),
+            Err(err) => Some((workflow::Status::Failed, Some(format!("{err:#}")))),
+        };
+        if let Some((status, last_error)) = outcome {
+            if Db::update_workflow_status(workflow_cid, status, last_error, &mut db.conn()?)? {
+                match status {
+                    workflow::Status::Completed => crate::metrics::record_workflow_completed(),
+                    _ => crate::metrics::record_workflow_failed(),
+                }
             }
         }
+
+        result
     }
 
     #[allow(unused_mut)]
@@ -187,110 +286,6 @@ where
         mut scheduler: TaskScheduler<'a>,
         running_tasks: Arc<RunningTaskSet>,
     ) -> Result<()> {
-        async fn insert_into_map<T>(map: Arc<RwLock<LinkMap<T>>>, key: Cid, value: T)
-        where
-            T: Clone,
-        {
-            map.write()
-                .await
-                .entry(key)
-                .or_insert_with(|| value.clone());
-        }
-
-        async fn resolve_cid(
-            cid: Cid,
-            workflow_cid: Cid,
-            network_settings: Arc<settings::Dht>,
-            linkmap: Arc<RwLock<IndexMap<Cid, InstructionResult<Arg>>>>,
-            resources: Arc<RwLock<IndexMap<Resource, Vec<u8>>>>,
-            db: impl Database,
-            event_sender: Arc<AsyncChannelSender<Event>>,
-        ) -> Result<InstructionResult<Arg>, ResolveError> {
-            info!(
-                subject = "worker.resolve_cid",
-                category = "worker.run",
-                workflow_cid = workflow_cid.to_string(),
-                cid = cid.to_string(),
-                "attempting to resolve cid in workflow"
-            );
-
-            if let Some(result) = linkmap.read().await.get(&cid) {
-                debug!(
-                    subject = "worker.resolve_cid",
-                    category = "worker.run",
-                    cid = cid.to_string(),
-                    "found CID in in-memory linkmap"
-                );
-
-                Ok(result.to_owned())
-            } else if let Some(bytes) = resources.read().await.get(&Resource::Cid(cid)) {
-                debug!(
-                    subject = "worker.resolve_cid",
-                    category = "worker.run",
-                    cid = cid.to_string(),
-                    "found CID in map of resources"
-                );
-
-                Ok(InstructionResult::Ok(Arg::Ipld(Ipld::Bytes(
-                    bytes.to_vec(),
-                ))))
-            } else {
-                let conn = &mut db.conn()?;
-                match Db::find_instruction_by_cid(cid, conn) {
-                    Ok(found) => Ok(found.output_as_arg()),
-                    Err(_) => {
-                        debug!(
-                            subject = "worker.resolve_cid",
-                            category = "worker.run",
-                            "no related instruction receipt found in the DB"
-                        );
-
-                        let (tx, rx) = AsyncChannel::oneshot();
-                        let _ = event_sender
-                            .send_async(Event::FindRecord(QueryRecord::with(
-                                cid,
-                                CapsuleTag::Receipt,
-                                Some(tx),
-                            )))
-                            .await;
-
-                        let found = match rx
-                            .recv_deadline(Instant::now() + network_settings.p2p_receipt_timeout)
-                        {
-                            Ok(ResponseEvent::Found(Ok(FoundEvent::Receipt(found)))) => found,
-                            Ok(ResponseEvent::Found(Err(err))) => {
-                                bail!(ResolveError::UnresolvedCid(format!(
-                                    "failure in attempting to find event: {err}"
-                                )))
-                            }
-                            Ok(_) => bail!(ResolveError::UnresolvedCid(
-                                "wrong or unexpected event message received".to_string(),
-                            )),
-                            Err(err) => bail!(ResolveError::UnresolvedCid(format!(
-                                "timeout deadline reached for invocation receipt @ {cid}: {err}",
-                            ))),
-                        };
-
-                        let receipt = Db::commit_receipt(workflow_cid, found.clone().receipt, conn)
-                            .unwrap_or(found.clone().receipt);
-                        let found_result = receipt.output_as_arg();
-
-                        // Store the result in the linkmap for use in next iterations.
-                        insert_into_map(linkmap.clone(), cid, found_result.clone()).await;
-
-                        // TODO Check this event is sent when we've updated the receipt
-                        // retrieval mechanism.
-                        #[cfg(feature = "websocket-notify")]
-                        let _ = event_sender
-                            .send_async(Event::StoredRecord(FoundEvent::Receipt(found)))
-                            .await;
-
-                        Ok(found_result)
-                    }
-                }
-            }
-        }
-
         // Replay previous receipts if subscriptions are on.
         #[cfg(feature = "websocket-notify")]
         {
@@ -334,172 +329,379 @@ where
             }
         }
 
-        for batch in scheduler.run.into_iter() {
-            let mut task_set = TaskSet::new();
-            let mut handles = Vec::new();
-
-            for node in batch.into_iter() {
-                let vertice = node.into_inner();
-                let invocation_ptr = vertice.invocation;
-                let instruction = vertice.instruction;
-                let rsc = instruction.resource();
-                let parsed = vertice.parsed;
-                let fun = parsed.fun().ok_or_else(|| anyhow!("no function defined"))?;
+        let external_promises: Arc<FnvHashSet<Cid>> =
+            Arc::new(self.graph.external_promises.iter().copied().collect());
 
-                let args = parsed.into_args();
-                let receipt_meta =
-                    Ipld::Map(BTreeMap::from([(OP_KEY.into(), fun.to_string().into())]));
+        match self.workflow_settings.scheduler_mode.unwrap_or_default() {
+            SchedulerMode::Batch => {
+                for batch in mem::take(&mut scheduler.run).into_iter() {
+                    if self.is_paused() {
+                        break;
+                    }
 
-                let additional_meta = Ipld::Map(BTreeMap::from([
-                    (REPLAYED_KEY.into(), Ipld::Bool(false)),
-                    (WORKFLOW_KEY.into(), self.workflow_info.cid().into()),
-                    (
-                        WORKFLOW_NAME_KEY.into(),
-                        self.workflow_name.to_string().into(),
-                    ),
-                ]));
+                    let mut task_set = TaskSet::new();
+                    let mut handles = Vec::new();
 
-                match RegisteredTasks::ability(&instruction.op().to_string()) {
-                    Some(RegisteredTasks::WasmRun) => {
-                        let wasm = scheduler
-                            .resources
-                            .read()
-                            .await
-                            .get(&Resource::Url(rsc.to_owned()))
-                            .ok_or_else(|| anyhow!("resource not available"))?
-                            .to_owned();
-
-                        let instruction_ptr = Pointer::try_from(instruction)?;
-                        let state = State::default();
-                        let mut wasm_ctx = WasmContext::new(state)?;
-
-                        let db = self.db.clone();
-                        let network_settings = self.network_settings.clone();
-                        let linkmap = scheduler.linkmap.clone();
-                        let resources = scheduler.resources.clone();
-                        let event_sender = self.event_sender.clone();
-                        let workflow_cid = self.workflow_info.cid();
-
-                        let resolved = args.resolve(move |cid| {
-                            resolve_cid(
-                                cid,
-                                workflow_cid,
-                                network_settings.clone(),
-                                linkmap.clone(),
-                                resources.clone(),
-                                db.clone(),
-                                event_sender.clone(),
+                    for node in batch.into_iter() {
+                        if let Some(handle) = self
+                            .spawn_task(
+                                node.into_inner(),
+                                &scheduler,
+                                external_promises.clone(),
+                                &mut task_set,
                             )
-                            .boxed()
-                        });
-
-                        let handle = task_set.spawn(async move {
-                            let resolved = match resolved.await {
-                                Ok(inst_result) => inst_result,
-                                Err(err) => {
-                                    error!(subject = "worker.resolve_cid.err",
-                                           category = "worker.run",
-                                           err=?err,
-                                           "error resolving cid");
-                                    return Err(anyhow!("error resolving cid: {err}"))
-                                        .with_context(|| {
-                                            format!("could not spawn task for cid: {workflow_cid}")
-                                        });
-                                }
-                            };
-                            match wasm_ctx.run(wasm, &fun, resolved).await {
-                                Ok(output) => Ok((
-                                    output,
-                                    instruction_ptr,
-                                    invocation_ptr,
-                                    receipt_meta,
-                                    additional_meta)),
-                                Err(err) => Err(
-                                    anyhow!("cannot execute wasm module: {err}"))
-                                    .with_context(|| {
-                                        format!("not able to run fn {fun} for cid: {instruction_ptr}, in workflow {workflow_cid}")
-                                }),
-                            }
-                        });
-                        handles.push(handle);
+                            .await?
+                        {
+                            handles.push(handle);
+                        }
+                    }
+
+                    // Concurrently add handles to Runner's running set.
+                    running_tasks.append_or_insert(self.workflow_info.cid(), handles);
+                    while let Some(res) = task_set.join_next().await {
+                        self.commit_task(res, &scheduler).await?;
                     }
-                    None => error!(
-                        subject = "worker.run.task.err",
-                        category = "worker.run",
-                        "no valid task/instruction-type referenced by operation: {}",
-                        instruction.op()
-                    ),
                 }
             }
+            SchedulerMode::Ready => {
+                // Instruction [Cid]s left to run, which tasks may await on.
+                let mut pending = mem::take(&mut scheduler.run)
+                    .into_iter()
+                    .flatten()
+                    .map(|node| Ok((Cid::from_str(node.name())?, node.into_inner())))
+                    .collect::<Result<Vec<_>>>()?;
+                let scheduled: FnvHashSet<Cid> = pending.iter().map(|(cid, _)| *cid).collect();
+                let mut task_set = TaskSet::new();
+
+                loop {
+                    let (mut ready, mut waiting): (Vec<_>, Vec<_>) = {
+                        let linkmap = scheduler.linkmap.read().await;
+                        pending.into_iter().partition(|(_, vertice)| {
+                            vertice
+                                .parsed
+                                .args()
+                                .deferreds()
+                                .filter(|cid| scheduled.contains(cid))
+                                .all(|cid| linkmap.contains_key(&cid))
+                        })
+                    };
+
+                    // Nothing in flight can complete what's left waiting,
+                    // e.g. an awaited task had no valid operation, so hand
+                    // the rest over to resolve or fail as they would in
+                    // batch mode.
+                    if ready.is_empty() && task_set.is_empty() {
+                        mem::swap(&mut ready, &mut waiting);
+                    }
 
-            // Concurrently add handles to Runner's running set.
-            running_tasks.append_or_insert(self.workflow_info.cid(), handles);
-            while let Some(res) = task_set.join_next().await {
-                let (executed, instruction_ptr, invocation_ptr, receipt_meta, add_meta) = match res
-                {
-                    Ok(Ok(data)) => data,
-                    Ok(Err(err)) => {
-                        error!(subject = "worker.run.task.err",
-                               category = "worker.run",
-                               err=?err,
-                               "error in running task");
-                        break;
+                    // Once paused, only let in-flight tasks complete.
+                    if self.is_paused() {
+                        waiting.append(&mut ready);
                     }
-                    Err(err) => {
-                        error!(subject = "worker.run.task.err",
-                               category = "worker.run",
-                               err=?err,
-                               "error in running task");
-                        break;
+                    pending = waiting;
+
+                    let mut handles = Vec::new();
+                    for (_, vertice) in ready.into_iter() {
+                        if let Some(handle) = self
+                            .spawn_task(
+                                vertice,
+                                &scheduler,
+                                external_promises.clone(),
+                                &mut task_set,
+                            )
+                            .await?
+                        {
+                            handles.push(handle);
+                        }
                     }
-                };
 
-                let output_to_store = Ipld::try_from(executed)?;
-                let invocation_receipt = InvocationReceipt::new(
-                    invocation_ptr,
-                    InstructionResult::Ok(output_to_store),
-                    receipt_meta,
-                    None,
-                    UcanPrf::default(),
-                );
+                    running_tasks.append_or_insert(self.workflow_info.cid(), handles);
+                    match task_set.join_next().await {
+                        Some(res) => self.commit_task(res, &scheduler).await?,
+                        None => break,
+                    }
+                }
+            }
+        }
 
-                let receipt = Receipt::try_with(instruction_ptr, &invocation_receipt)?;
+        Ok(())
+    }
+
+    /// Check if the [Runner] has paused the [Workflow], logging that
+    /// scheduling stops if so.
+    ///
+    /// [Runner]: crate::Runner
+    fn is_paused(&self) -> bool {
+        let paused = self.paused.load(Ordering::Acquire);
+        if paused {
+            info!(
+                subject = "worker.paused",
+                category = "worker.run",
+                workflow_cid = self.workflow_info.cid.to_string(),
+                "workflow paused, no longer scheduling tasks"
+            );
+        }
+        paused
+    }
 
-                scheduler
-                    .linkmap
-                    .write()
+    /// Spawn a scheduled task onto the [TaskSet], returning a handle for
+    /// tracking it as running, or [None] if its operation isn't registered.
+    async fn spawn_task(
+        &self,
+        vertice: Vertex<'a>,
+        scheduler: &TaskScheduler<'a>,
+        external_promises: Arc<FnvHashSet<Cid>>,
+        task_set: &mut TaskSet,
+    ) -> Result<Option<AbortHandle>> {
+        let branches = vertice.branches();
+        let invocation_ptr = vertice.invocation;
+        let instruction = vertice.instruction;
+        let rsc = instruction.resource();
+        let parsed = vertice.parsed;
+        let config = vertice.config;
+        let prf = vertice.prf;
+        let fun = parsed.fun().ok_or_else(|| anyhow!("no function defined"))?;
+
+        let args = parsed.into_args();
+        let mut receipt_meta: BTreeMap<String, Ipld> =
+            BTreeMap::from([(OP_KEY.into(), fun.to_string().into())]);
+
+        let additional_meta = Ipld::Map(BTreeMap::from([
+            (REPLAYED_KEY.into(), Ipld::Bool(false)),
+            (WORKFLOW_KEY.into(), self.workflow_info.cid().into()),
+            (
+                WORKFLOW_NAME_KEY.into(),
+                self.workflow_name.to_string().into(),
+            ),
+        ]));
+
+        match RegisteredTasks::ability(&instruction.op().to_string()) {
+            Some(RegisteredTasks::WasmRun) => {
+                let wasm = scheduler
+                    .resources
+                    .read()
                     .await
-                    .insert(receipt.instruction().cid(), receipt.output_as_arg());
-
-                // modify workflow info before progress update, in case
-                // that we time out getting info from the network, but later
-                // recovered where we last started from.
-                if let Some(step) = scheduler.resume_step {
-                    let current_progress_count = self.workflow_info.progress_count;
-                    Arc::make_mut(&mut self.workflow_info)
-                        .set_progress_count(std::cmp::max(current_progress_count, step as u32))
-                };
+                    .get(&Resource::Url(rsc.to_owned()))
+                    .ok_or_else(|| anyhow!("resource not available"))?
+                    .to_owned();
+
+                let rsc_cid = tasks::resource_cid(rsc, &wasm);
+                let rsc_str = rsc.to_string();
+                let op = instruction.op().to_string();
+                let instruction_ptr = Pointer::try_from(instruction)?;
+                let time_limit = config.time();
+                let fuel_limit = config.fuel().unwrap_or(u64::MAX);
+                let state = State::from(config);
+                let mut wasm_ctx = WasmContext::new(state, self.component_cache.clone())?;
+
+                let db = self.db.clone();
+                let network_settings = self.network_settings.clone();
+                let linkmap = scheduler.linkmap.clone();
+                let resources = scheduler.resources.clone();
+                let event_sender = self.event_sender.clone();
+                let workflow_cid = self.workflow_info.cid();
+                let task_permits = self.task_permits.clone();
+                let authorizer = self.authorizer.clone();
+                let auth_db = self.db.clone();
+
+                let resolved = args.resolve(move |cid| {
+                    resolve_cid(
+                        cid,
+                        workflow_cid,
+                        network_settings.clone(),
+                        linkmap.clone(),
+                        resources.clone(),
+                        external_promises.clone(),
+                        db.clone(),
+                        event_sender.clone(),
+                    )
+                    .boxed()
+                });
+
+                let handle = task_set.spawn(async move {
+                    let mut started_at = None;
+                    let executed = async {
+                        // Refuse the task before resolving its inputs if
+                        // its proofs don't delegate it to the node.
+                        if let Some(authorizer) = authorizer {
+                            authorizer
+                                .authorize(&prf, &rsc_str, &op, &auth_db)
+                                .await
+                                .map_err(|err| TaskFailure::Unauthorized(err.to_string()))?;
+                        }
+
+                        let resolved = resolved.await.map_err(|err| {
+                            error!(subject = "worker.resolve_cid.err",
+                                   category = "worker.run",
+                                   err=?err,
+                                   "error resolving cid");
+                            TaskFailure::Execution(format!("error resolving cid: {err}"))
+                        })?;
+
+                        // Skip the task if any instruction it awaits on
+                        // did not take the awaited branch, i.e. it
+                        // failed for `await/ok` or succeeded for
+                        // `await/error`.
+                        if let Some(promise) = branches.into_iter().find_map(|(idx, promise)| {
+                            let failed = match resolved.inner().get(idx) {
+                                Some(Input::Arg(InstructionResult::Error(_))) => true,
+                                Some(Input::Arg(_)) => false,
+                                _ => return None,
+                            };
+                            let taken = match promise.result() {
+                                AwaitResult::Error => failed,
+                                _ => !failed,
+                            };
+                            (!taken).then_some(promise)
+                        }) {
+                            return Err(TaskFailure::Skipped(promise));
+                        }
+
+                        // Hold a node-wide permit for the length of the
+                        // execution, bounding concurrent Wasm runs.
+                        let _permit = task_permits.acquire().await.map_err(|err| {
+                            TaskFailure::Execution(format!("cannot acquire task permit: {err}"))
+                        })?;
+
+                        started_at = Some(Utc::now().naive_utc());
+                        let run = wasm_ctx.run(rsc_cid, wasm, &fun, resolved);
+                        let output = match time_limit {
+                            Some(time) => tokio::time::timeout(time, run)
+                                .await
+                                .map_err(|_| ResourceLimit::Time(time))?,
+                            None => run.await,
+                        }
+                        .map_err(|err| match err {
+                            WasmRuntimeError::OutOfFuel => ResourceLimit::Fuel(fuel_limit).into(),
+                            WasmRuntimeError::MemoryLimitExceeded(bytes) => {
+                                ResourceLimit::Memory(bytes).into()
+                            }
+                            err => {
+                                TaskFailure::Execution(format!("cannot execute wasm module: {err}"))
+                            }
+                        })?;
 
-                let stored_receipt =
-                    Db::commit_receipt(self.workflow_info.cid, receipt, &mut self.db.conn()?)?;
+                        Ipld::try_from(output).map_err(|err| {
+                            TaskFailure::Execution(format!("cannot convert wasm output: {err}"))
+                        })
+                    }
+                    .await;
 
-                debug!(
-                    subject = "db.commit_receipt",
+                    // Only tasks that got as far as executing carry stats.
+                    if let Some(started_at) = started_at {
+                        let stats = Stats {
+                            started_at,
+                            finished_at: Utc::now().naive_utc(),
+                            fuel: wasm_ctx.fuel_consumed(),
+                            memory: wasm_ctx.memory_consumed(),
+                        };
+                        stats.extend_meta(&mut receipt_meta);
+                        crate::metrics::record_task_execution(&op, &fun, &stats);
+                    }
+
+                    let result = match executed {
+                        Ok(output) => InstructionResult::Ok(output),
+                        Err(failure) => {
+                            warn!(
+                                subject = "worker.run.task.err",
+                                category = "worker.run",
+                                cid = instruction_ptr.cid().to_string(),
+                                workflow_cid = workflow_cid.to_string(),
+                                "task {fun} did not succeed: {failure}"
+                            );
+                            InstructionResult::Error(failure.into())
+                        }
+                    };
+
+                    (
+                        result,
+                        instruction_ptr,
+                        invocation_ptr,
+                        Ipld::Map(receipt_meta),
+                        additional_meta,
+                    )
+                });
+                Ok(Some(handle))
+            }
+            None => {
+                error!(
+                    subject = "worker.run.task.err",
                     category = "worker.run",
-                    cid = self.workflow_info.cid.to_string(),
-                    "commited to database"
+                    "no valid task/instruction-type referenced by operation: {}",
+                    instruction.op()
                 );
-
-                let _ = self
-                    .event_sender
-                    .send_async(Event::CapturedReceipt(Captured::with(
-                        stored_receipt.cid(),
-                        self.workflow_info.clone(),
-                        Some(add_meta),
-                    )))
-                    .await;
+                Ok(None)
             }
         }
+    }
+
+    /// Commit the [Receipt] of a completed task, making its result available
+    /// to tasks awaiting on it.
+    ///
+    /// Tasks that panicked or were aborted leave no result for tasks
+    /// awaiting on them to resolve, so the [Workflow] is failed instead.
+    ///
+    /// [Workflow]: homestar_core::Workflow
+    async fn commit_task(
+        &mut self,
+        res: Result<TaskOutput, JoinError>,
+        scheduler: &TaskScheduler<'a>,
+    ) -> Result<()> {
+        let (executed, instruction_ptr, invocation_ptr, receipt_meta, add_meta) = match res {
+            Ok(data) => data,
+            Err(err) => {
+                error!(subject = "worker.run.task.err",
+                       category = "worker.run",
+                       err=?err,
+                       "error in running task");
+                return Err(anyhow!("task did not complete: {err}"));
+            }
+        };
+
+        let invocation_receipt = InvocationReceipt::new(
+            invocation_ptr,
+            executed,
+            receipt_meta,
+            None,
+            UcanPrf::default(),
+        );
+
+        let receipt = Receipt::try_signed(instruction_ptr, invocation_receipt, &self.signer)?;
+
+        scheduler
+            .linkmap
+            .write()
+            .await
+            .insert(receipt.instruction().cid(), receipt.output_as_arg());
+
+        // modify workflow info before progress update, in case
+        // that we time out getting info from the network, but later
+        // recovered where we last started from.
+        if let Some(step) = scheduler.resume_step {
+            let current_progress_count = self.workflow_info.progress_count;
+            Arc::make_mut(&mut self.workflow_info)
+                .set_progress_count(std::cmp::max(current_progress_count, step as u32))
+        };
+
+        let stored_receipt =
+            Db::commit_receipt(self.workflow_info.cid, receipt, &mut self.db.conn()?)?;
+
+        debug!(
+            subject = "db.commit_receipt",
+            category = "worker.run",
+            cid = self.workflow_info.cid.to_string(),
+            "commited to database"
+        );
+
+        let _ = self
+            .event_sender
+            .send_async(Event::CapturedReceipt(Captured::with(
+                stored_receipt.cid(),
+                self.workflow_info.clone(),
+                Some(add_meta),
+            )))
+            .await;
+
         Ok(())
     }
 }
@@ -509,9 +711,201 @@ where
     DB: Database,
 {
     fn drop(&mut self) {
-        let _ = self
-            .runner_sender
-            .try_send(WorkerMessage::Dropped(self.workflow_info.cid));
+        // Paused workflows are kept by the runner to be resumed, which
+        // could otherwise race with aborting the dropped worker.
+        let msg = if self.paused.load(Ordering::Acquire) {
+            WorkerMessage::Paused(self.workflow_info.cid)
+        } else {
+            WorkerMessage::Dropped(self.workflow_info.cid)
+        };
+        let _ = self.runner_sender.try_send(msg);
+    }
+}
+
+async fn insert_into_map<T>(map: Arc<RwLock<LinkMap<T>>>, key: Cid, value: T)
+where
+    T: Clone,
+{
+    map.write()
+        .await
+        .entry(key)
+        .or_insert_with(|| value.clone());
+}
+
+async fn find_receipt(
+    cid: Cid,
+    p2p_timeout: Duration,
+    event_sender: Arc<AsyncChannelSender<Event>>,
+) -> Result<ReceiptEvent, ResolveError> {
+    let (tx, rx) = AsyncChannel::oneshot();
+    let _ = event_sender
+        .send_async(Event::FindRecord(QueryRecord::with(
+            cid,
+            CapsuleTag::Receipt,
+            Some(tx),
+        )))
+        .await;
+
+    match timeout_at(Instant::now() + p2p_timeout, rx.recv_async()).await {
+        Ok(Ok(ResponseEvent::Found(Ok(FoundEvent::Receipt(found))))) => Ok(found),
+        Ok(Ok(ResponseEvent::Found(Err(err)))) => bail!(ResolveError::UnresolvedCid(format!(
+            "failure in attempting to find event: {err}"
+        ))),
+        Ok(Ok(_)) => bail!(ResolveError::UnresolvedCid(
+            "wrong or unexpected event message received".to_string(),
+        )),
+        Ok(Err(err)) => bail!(ResolveError::UnresolvedCid(format!(
+            "channel closed while finding invocation receipt @ {cid}: {err}",
+        ))),
+        Err(_) => bail!(ResolveError::UnresolvedCid(format!(
+            "timeout deadline reached for invocation receipt @ {cid}",
+        ))),
+    }
+}
+
+async fn await_receipt(
+    cid: Cid,
+    network_settings: Arc<settings::Dht>,
+    db: impl Database,
+    event_sender: Arc<AsyncChannelSender<Event>>,
+) -> Result<Receipt, ResolveError> {
+    // Subscribe first, so a receipt captured or gossiped while
+    // looking elsewhere isn't missed.
+    let (tx, rx) = AsyncChannel::oneshot();
+    let _ = event_sender
+        .send_async(Event::SubscribeReceipt(cid, tx))
+        .await;
+
+    if let Ok(found) = find_receipt(
+        cid,
+        network_settings.p2p_receipt_timeout,
+        event_sender.clone(),
+    )
+    .await
+    {
+        #[cfg(feature = "websocket-notify")]
+        let _ = event_sender
+            .send_async(Event::StoredRecord(FoundEvent::Receipt(found.clone())))
+            .await;
+
+        return Ok(found.receipt);
+    }
+
+    if let Ok(receipt) = Db::find_instruction_by_cid(cid, &mut db.conn()?) {
+        return Ok(receipt);
+    }
+
+    info!(
+        subject = "worker.await_receipt",
+        category = "worker.run",
+        cid = cid.to_string(),
+        "awaiting receipt of instruction outside of the workflow"
+    );
+
+    let deadline = Instant::now() + network_settings.p2p_external_receipt_timeout;
+    match timeout_at(deadline, rx.recv_async()).await {
+        Ok(Ok(ResponseEvent::Receipt(receipt))) => Ok(receipt),
+        Ok(Ok(_)) => bail!(ResolveError::UnresolvedCid(
+            "wrong or unexpected event message received".to_string(),
+        )),
+        Ok(Err(err)) => bail!(ResolveError::UnresolvedCid(format!(
+            "channel closed while awaiting invocation receipt @ {cid}: {err}",
+        ))),
+        Err(_) => bail!(ResolveError::UnresolvedCid(format!(
+            "timeout deadline reached awaiting invocation receipt @ {cid} from outside of the workflow",
+        ))),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn resolve_cid(
+    cid: Cid,
+    workflow_cid: Cid,
+    network_settings: Arc<settings::Dht>,
+    linkmap: Arc<RwLock<IndexMap<Cid, InstructionResult<Arg>>>>,
+    resources: Arc<RwLock<IndexMap<Resource, Vec<u8>>>>,
+    external_promises: Arc<FnvHashSet<Cid>>,
+    db: impl Database,
+    event_sender: Arc<AsyncChannelSender<Event>>,
+) -> Result<InstructionResult<Arg>, ResolveError> {
+    info!(
+        subject = "worker.resolve_cid",
+        category = "worker.run",
+        workflow_cid = workflow_cid.to_string(),
+        cid = cid.to_string(),
+        "attempting to resolve cid in workflow"
+    );
+
+    // Bind the lookup so the read guard is released before the
+    // linkmap is written to below.
+    let cached = linkmap.read().await.get(&cid).cloned();
+    if let Some(result) = cached {
+        debug!(
+            subject = "worker.resolve_cid",
+            category = "worker.run",
+            cid = cid.to_string(),
+            "found CID in in-memory linkmap"
+        );
+
+        Ok(result)
+    } else if let Some(bytes) = resources.read().await.get(&Resource::Cid(cid)) {
+        debug!(
+            subject = "worker.resolve_cid",
+            category = "worker.run",
+            cid = cid.to_string(),
+            "found CID in map of resources"
+        );
+
+        Ok(InstructionResult::Ok(Arg::Ipld(Ipld::Bytes(
+            bytes.to_vec(),
+        ))))
+    } else {
+        let found = Db::find_instruction_by_cid(cid, &mut db.conn()?);
+        match found {
+            Ok(found) => Ok(found.output_as_arg()),
+            Err(_) => {
+                debug!(
+                    subject = "worker.resolve_cid",
+                    category = "worker.run",
+                    "no related instruction receipt found in the DB"
+                );
+
+                let found = if external_promises.contains(&cid) {
+                    await_receipt(
+                        cid,
+                        network_settings.clone(),
+                        db.clone(),
+                        event_sender.clone(),
+                    )
+                    .await?
+                } else {
+                    let found = find_receipt(
+                        cid,
+                        network_settings.p2p_receipt_timeout,
+                        event_sender.clone(),
+                    )
+                    .await?;
+
+                    // TODO Check this event is sent when we've updated the receipt
+                    // retrieval mechanism.
+                    #[cfg(feature = "websocket-notify")]
+                    let _ = event_sender
+                        .send_async(Event::StoredRecord(FoundEvent::Receipt(found.clone())))
+                        .await;
+
+                    found.receipt
+                };
+
+                let receipt = Db::commit_receipt(workflow_cid, found.clone(), &mut db.conn()?)
+                    .unwrap_or(found);
+                let found_result = receipt.output_as_arg();
+
+                // Store the result in the linkmap for use in next iterations.
+                insert_into_map(linkmap.clone(), cid, found_result.clone()).await;
+
+                Ok(found_result)
+            }
+        }
     }
 }
 
@@ -525,11 +919,13 @@ mod test {
     };
     use homestar_core::{
         ipld::DagCbor,
-        test_utils::workflow as workflow_test_utils,
+        test_utils::{cid::generate_cid, workflow as workflow_test_utils},
         workflow::{
-            config::Resources, instruction::RunInstruction, prf::UcanPrf, Invocation, Task,
+            config::Resources, instruction::RunInstruction, pointer::Await, prf::UcanPrf, Ability,
+            Instruction, Invocation, Task,
         },
     };
+    use rand::thread_rng;
 
     #[homestar_runtime_proc_macro::db_async_test]
     fn initialize_worker() {
@@ -586,6 +982,10 @@ mod test {
                     let (_, workflow_info) =
                         MemoryDb::get_workflow_info(workflow_cid, &mut conn).unwrap();
                     assert_eq!(info.progress_count, workflow_info.progress_count);
+                    let stored_receipt = MemoryDb::find_receipt_by_cid(receipt, &mut conn).unwrap();
+                    let stats = stored_receipt.stats().unwrap();
+                    assert!(stats.finished_at >= stats.started_at);
+                    assert!(stats.fuel > 0);
                     captured_receipt = true;
                     receipts_cnt += 1;
                 }
@@ -604,6 +1004,10 @@ mod test {
         assert_eq!(workflow_info.cid, workflow_cid);
         assert_eq!(workflow_info.progress.len(), 2);
         assert_eq!(workflow_info.resources.len(), 2);
+
+        let stored = MemoryDb::select_workflow(workflow_cid, &mut conn).unwrap();
+        assert_eq!(stored.status, workflow::Status::Completed);
+        assert!(stored.finished_at.is_some());
     }
 
     #[homestar_runtime_proc_macro::db_async_test]
@@ -715,6 +1119,7 @@ mod test {
                 info.increment_progress(next_receipt.cid());
 
                 assert_ne!(next_receipt, receipt);
+                assert!(next_receipt.signature().is_some());
 
                 (next_receipt, info)
             }
@@ -733,6 +1138,457 @@ mod test {
         assert_eq!(wf_info.progress_count, workflow_info.progress_count);
     }
 
+    #[homestar_runtime_proc_macro::db_async_test]
+    fn run_task_exceeding_fuel_limit() {
+        let settings = TestSettings::load();
+
+        let mut config = Resources::default();
+        config.set_fuel(1);
+        let (instruction, _, _) = workflow_test_utils::related_wasm_instructions::<Arg>();
+
+        let task = Task::new(
+            RunInstruction::Expanded(instruction),
+            config.into(),
+            UcanPrf::default(),
+        );
+
+        let (tx, rx) = test_utils::event::setup_event_channel(settings.node.clone());
+
+        let builder = WorkerBuilder::new(settings.node)
+            .with_event_sender(tx)
+            .with_tasks(vec![task]);
+        let fetch_fn = builder.fetch_fn();
+        let db = builder.db();
+        let worker = builder.build().await;
+
+        let running_tasks = Arc::new(RunningTaskSet::new());
+        worker.run(running_tasks, fetch_fn).await.unwrap();
+
+        let mut conn = db.conn().unwrap();
+        let mut receipts_cnt = 0;
+
+        while let Ok(event) = rx.recv_async().await {
+            if let Event::CapturedReceipt(Captured { receipt, .. }) = event {
+                let stored = MemoryDb::find_receipt_by_cid(receipt, &mut conn).unwrap();
+                let InstructionResult::Error(Ipld::Map(err)) = stored.output().to_owned() else {
+                    panic!("expected an error receipt");
+                };
+                assert_eq!(err.get("limit").unwrap(), &Ipld::String("fuel".into()));
+                assert_eq!(err.get("max").unwrap(), &Ipld::Integer(1));
+                receipts_cnt += 1;
+            }
+        }
+
+        assert_eq!(receipts_cnt, 1);
+    }
+
+    #[homestar_runtime_proc_macro::db_async_test]
+    fn fail_workflow_on_panicked_task() {
+        fn panicking() -> TaskOutput {
+            panic!("task panicked")
+        }
+
+        let settings = TestSettings::load();
+        let builder = WorkerBuilder::new(settings.node);
+        let db = builder.db();
+        let fetch_fn = builder.fetch_fn();
+        let mut worker = builder.build().await;
+        let ctx = TaskScheduler::init(worker.graph.clone(), &mut db.conn().unwrap(), fetch_fn)
+            .await
+            .unwrap();
+
+        let mut task_set = TaskSet::new();
+        task_set.spawn(async { panicking() });
+        let res = task_set.join_next().await.unwrap();
+        let err = worker.commit_task(res, &ctx.scheduler).await.unwrap_err();
+        assert!(err.to_string().contains("panicked"));
+
+        // No receipt is committed for it.
+        let workflow_info =
+            MemoryDb::get_workflow_info(worker.workflow_info.cid, &mut db.conn().unwrap())
+                .unwrap()
+                .1;
+        assert_eq!(workflow_info.progress_count, 0);
+    }
+
+    #[homestar_runtime_proc_macro::db_async_test]
+    fn stop_scheduling_when_paused() {
+        let settings = TestSettings::load();
+
+        let (tx, rx) = test_utils::event::setup_event_channel(settings.node.clone());
+
+        let builder = WorkerBuilder::new(settings.node).with_event_sender(tx);
+        let fetch_fn = builder.fetch_fn();
+        let db = builder.db();
+        let worker = builder.build().await;
+        let workflow_cid = worker.workflow_info.cid;
+        worker.paused.store(true, Ordering::Release);
+
+        let running_tasks = Arc::new(RunningTaskSet::new());
+        worker.run(running_tasks.clone(), fetch_fn).await.unwrap();
+
+        while let Ok(event) = rx.recv_async().await {
+            assert!(!matches!(event, Event::CapturedReceipt(_)));
+        }
+        assert!(running_tasks.is_empty());
+
+        // Still running, to carry on once resumed.
+        let stored = MemoryDb::select_workflow(workflow_cid, &mut db.conn().unwrap()).unwrap();
+        assert_eq!(stored.status, workflow::Status::Running);
+    }
+
+    #[homestar_runtime_proc_macro::db_async_test]
+    fn run_task_from_instruction_pointer() {
+        let settings = TestSettings::load();
+
+        let instruction = workflow_test_utils::wasm_instruction::<Arg>();
+        let instruction_cid = instruction.clone().to_cid().unwrap();
+        let task = Task::new(
+            RunInstruction::Ptr(Pointer::new(instruction_cid)),
+            Resources::default().into(),
+            UcanPrf::default(),
+        );
+
+        let (tx, rx) = test_utils::event::setup_event_channel(settings.node.clone());
+
+        let builder = WorkerBuilder::new(settings.node)
+            .with_event_sender(tx)
+            .with_tasks(vec![task]);
+        let fetch_fn = builder.fetch_fn();
+        let db = builder.db();
+        let mut conn = db.conn().unwrap();
+
+        // Instruction resolved ahead of time by the runner.
+        let stored = workflow::StoredInstruction::try_from(instruction).unwrap();
+        MemoryDb::store_instruction(stored, &mut conn).unwrap();
+
+        let worker = builder.build().await;
+        let running_tasks = Arc::new(RunningTaskSet::new());
+        worker.run(running_tasks, fetch_fn).await.unwrap();
+
+        let mut receipts_cnt = 0;
+        while let Ok(event) = rx.recv_async().await {
+            if let Event::CapturedReceipt(Captured { receipt, .. }) = event {
+                let stored = MemoryDb::find_receipt_by_cid(receipt, &mut conn).unwrap();
+                assert_eq!(stored.instruction(), &Pointer::new(instruction_cid));
+                assert_eq!(stored.output(), &InstructionResult::Ok(Ipld::Integer(2)));
+                receipts_cnt += 1;
+            }
+        }
+
+        assert_eq!(receipts_cnt, 1);
+    }
+
+    #[homestar_runtime_proc_macro::db_async_test]
+    fn await_receipt_from_outside_workflow() {
+        let settings = TestSettings::load();
+
+        let (instruction1, instruction2, _) =
+            workflow_test_utils::related_wasm_instructions::<Arg>();
+        let instruction1_cid = instruction1.to_cid().unwrap();
+        let instruction2_cid = instruction2.clone().to_cid().unwrap();
+        let task = Task::new(
+            RunInstruction::Expanded(instruction2),
+            Resources::default().into(),
+            UcanPrf::default(),
+        );
+
+        let (tx, rx) = test_utils::event::setup_event_channel(settings.node.clone());
+
+        let builder = WorkerBuilder::new(settings.node)
+            .with_event_sender(tx)
+            .with_tasks(vec![task]);
+        let fetch_fn = builder.fetch_fn();
+        let db = builder.db();
+        let worker = builder.build().await;
+        assert_eq!(worker.graph.external_promises, vec![instruction1_cid]);
+
+        // Receipt of the awaited instruction, run as part of another workflow.
+        let external = Receipt::try_with(
+            Pointer::new(instruction1_cid),
+            &InvocationReceipt::new(
+                Pointer::new(generate_cid(&mut thread_rng())),
+                InstructionResult::Ok(Ipld::Integer(2)),
+                Ipld::Null,
+                None,
+                UcanPrf::default(),
+            ),
+        )
+        .unwrap();
+
+        let running_tasks = Arc::new(RunningTaskSet::new());
+        let run = worker.run(running_tasks, fetch_fn);
+        let handle_events = async {
+            let mut outputs = vec![];
+            while let Ok(event) = rx.recv_async().await {
+                match event {
+                    Event::FindRecord(QueryRecord {
+                        sender: Some(sender),
+                        ..
+                    }) => {
+                        let _ = sender
+                            .send_async(ResponseEvent::Found(Err(anyhow!("not found"))))
+                            .await;
+                    }
+                    Event::SubscribeReceipt(cid, sender) => {
+                        assert_eq!(cid, instruction1_cid);
+                        let _ = sender
+                            .send_async(ResponseEvent::Receipt(external.clone()))
+                            .await;
+                    }
+                    Event::CapturedReceipt(Captured { receipt, .. }) => {
+                        let stored =
+                            MemoryDb::find_receipt_by_cid(receipt, &mut db.conn().unwrap())
+                                .unwrap();
+                        outputs.push((stored.instruction().cid(), stored.output().to_owned()));
+                    }
+                    _ => {}
+                }
+            }
+            outputs
+        };
+
+        let (ran, outputs) = tokio::join!(run, handle_events);
+        ran.unwrap();
+
+        assert_eq!(
+            outputs,
+            vec![(instruction2_cid, InstructionResult::Ok(Ipld::Integer(3)))]
+        );
+    }
+
+    #[homestar_runtime_proc_macro::db_async_test]
+    fn run_tasks_in_ready_mode() {
+        let settings = TestSettings::load();
+
+        let (instruction1, instruction2, _) =
+            workflow_test_utils::related_wasm_instructions::<Arg>();
+        let (independent_instruction, _) =
+            workflow_test_utils::wasm_instruction_with_nonce::<Arg>();
+        let instruction1_cid = instruction1.clone().to_cid().unwrap();
+        let instruction2_cid = instruction2.clone().to_cid().unwrap();
+        let independent_cid = independent_instruction.clone().to_cid().unwrap();
+
+        let tasks = [instruction1, instruction2, independent_instruction]
+            .into_iter()
+            .map(|instruction| {
+                Task::new(
+                    RunInstruction::Expanded(instruction),
+                    Resources::default().into(),
+                    UcanPrf::default(),
+                )
+            })
+            .collect();
+
+        let (tx, rx) = test_utils::event::setup_event_channel(settings.node.clone());
+
+        let workflow_settings = workflow::Settings {
+            scheduler_mode: Some(SchedulerMode::Ready),
+            ..Default::default()
+        };
+        let builder = WorkerBuilder::new(settings.node)
+            .with_event_sender(tx)
+            .with_workflow_settings(workflow_settings)
+            .with_tasks(tasks);
+        let fetch_fn = builder.fetch_fn();
+        let db = builder.db();
+        let worker = builder.build().await;
+
+        let running_tasks = Arc::new(RunningTaskSet::new());
+        worker.run(running_tasks, fetch_fn).await.unwrap();
+
+        let mut conn = db.conn().unwrap();
+        let mut outputs = BTreeMap::new();
+
+        while let Ok(event) = rx.recv_async().await {
+            if let Event::CapturedReceipt(Captured { receipt, .. }) = event {
+                let stored = MemoryDb::find_receipt_by_cid(receipt, &mut conn).unwrap();
+                outputs.insert(stored.instruction().cid(), stored.output().to_owned());
+            }
+        }
+
+        assert_eq!(
+            outputs,
+            BTreeMap::from([
+                (instruction1_cid, InstructionResult::Ok(Ipld::Integer(2))),
+                (instruction2_cid, InstructionResult::Ok(Ipld::Integer(3))),
+                (independent_cid, InstructionResult::Ok(Ipld::Integer(2))),
+            ])
+        );
+    }
+
+    #[homestar_runtime_proc_macro::db_async_test]
+    fn skip_dependents_of_failed_task() {
+        let settings = TestSettings::load();
+
+        let mut failing_config = Resources::default();
+        failing_config.set_fuel(1);
+        let (instruction1, instruction2, _) =
+            workflow_test_utils::related_wasm_instructions::<Arg>();
+        let (independent_instruction, _) =
+            workflow_test_utils::wasm_instruction_with_nonce::<Arg>();
+        let instruction1_cid = instruction1.clone().to_cid().unwrap();
+        let instruction2_cid = instruction2.clone().to_cid().unwrap();
+        let independent_cid = independent_instruction.clone().to_cid().unwrap();
+
+        let task1 = Task::new(
+            RunInstruction::Expanded(instruction1),
+            failing_config.into(),
+            UcanPrf::default(),
+        );
+        let task2 = Task::new(
+            RunInstruction::Expanded(instruction2),
+            Resources::default().into(),
+            UcanPrf::default(),
+        );
+        let task3 = Task::new(
+            RunInstruction::Expanded(independent_instruction),
+            Resources::default().into(),
+            UcanPrf::default(),
+        );
+
+        let (tx, rx) = test_utils::event::setup_event_channel(settings.node.clone());
+
+        let builder = WorkerBuilder::new(settings.node)
+            .with_event_sender(tx)
+            .with_tasks(vec![task1, task2, task3]);
+        let fetch_fn = builder.fetch_fn();
+        let db = builder.db();
+        let worker = builder.build().await;
+
+        let running_tasks = Arc::new(RunningTaskSet::new());
+        worker.run(running_tasks, fetch_fn).await.unwrap();
+
+        let mut conn = db.conn().unwrap();
+        let mut outputs = BTreeMap::new();
+
+        while let Ok(event) = rx.recv_async().await {
+            if let Event::CapturedReceipt(Captured { receipt, .. }) = event {
+                let stored = MemoryDb::find_receipt_by_cid(receipt, &mut conn).unwrap();
+                outputs.insert(stored.instruction().cid(), stored.output().to_owned());
+            }
+        }
+
+        assert_eq!(outputs.len(), 3);
+        assert!(matches!(
+            outputs.get(&instruction1_cid).unwrap(),
+            InstructionResult::Error(_)
+        ));
+        assert_eq!(
+            outputs.get(&instruction2_cid).unwrap(),
+            &InstructionResult::Error(
+                TaskFailure::Skipped(Await::new(Pointer::new(instruction1_cid), AwaitResult::Ok))
+                    .into()
+            )
+        );
+        assert_eq!(
+            outputs.get(&independent_cid).unwrap(),
+            &InstructionResult::Ok(Ipld::Integer(2))
+        );
+    }
+
+    #[homestar_runtime_proc_macro::db_async_test]
+    fn run_error_branch_of_failed_task() {
+        let settings = TestSettings::load();
+
+        let config = Resources::default();
+        let (instruction1, _, _) = workflow_test_utils::related_wasm_instructions::<Arg>();
+        let instruction1_cid = instruction1.clone().to_cid().unwrap();
+
+        let branch_instruction = |result| {
+            Instruction::<Arg>::new(
+                instruction1.resource().to_owned(),
+                Ability::from("wasm/run"),
+                Input::Ipld(Ipld::Map(BTreeMap::from([
+                    ("func".into(), Ipld::String("add_one".to_string())),
+                    (
+                        "args".into(),
+                        Ipld::List(vec![Ipld::from(Await::new(
+                            Pointer::new(instruction1_cid),
+                            result,
+                        ))]),
+                    ),
+                ]))),
+            )
+        };
+        let ok_instruction = branch_instruction(AwaitResult::Ok);
+        let err_instruction = branch_instruction(AwaitResult::Error);
+        let ok_cid = ok_instruction.clone().to_cid().unwrap();
+        let err_cid = err_instruction.clone().to_cid().unwrap();
+
+        let task1 = Task::new(
+            RunInstruction::Expanded(instruction1.clone()),
+            config.clone().into(),
+            UcanPrf::default(),
+        );
+        let ok_task = Task::new(
+            RunInstruction::Expanded(ok_instruction),
+            config.clone().into(),
+            UcanPrf::default(),
+        );
+        let err_task = Task::new(
+            RunInstruction::Expanded(err_instruction),
+            config.into(),
+            UcanPrf::default(),
+        );
+
+        // Failed receipt for the first task, whose error payload is handed to
+        // the `await/error` branch.
+        let invocation_receipt = InvocationReceipt::new(
+            Invocation::new(task1.clone()).try_into().unwrap(),
+            InstructionResult::Error(Ipld::Integer(41)),
+            Ipld::Null,
+            None,
+            UcanPrf::default(),
+        );
+        let receipt = Receipt::try_with(
+            instruction1.clone().try_into().unwrap(),
+            &invocation_receipt,
+        )
+        .unwrap();
+
+        let (tx, rx) = test_utils::event::setup_event_channel(settings.node.clone());
+
+        let builder = WorkerBuilder::new(settings.node)
+            .with_event_sender(tx)
+            .with_tasks(vec![task1, ok_task, err_task]);
+        let fetch_fn = builder.fetch_fn();
+        let db = builder.db();
+        let workflow_cid = builder.workflow_cid();
+
+        let mut conn = db.conn().unwrap();
+        let _ = MemoryDb::store_workflow(
+            workflow::Stored::default(Pointer::new(workflow_cid), builder.workflow_len() as i32),
+            &mut conn,
+        );
+        let _ = MemoryDb::commit_receipt(workflow_cid, receipt, &mut conn).unwrap();
+
+        let worker = builder.build().await;
+        let running_tasks = Arc::new(RunningTaskSet::new());
+        worker.run(running_tasks, fetch_fn).await.unwrap();
+
+        let mut outputs = BTreeMap::new();
+        while let Ok(event) = rx.recv_async().await {
+            if let Event::CapturedReceipt(Captured { receipt, .. }) = event {
+                let stored = MemoryDb::find_receipt_by_cid(receipt, &mut conn).unwrap();
+                outputs.insert(stored.instruction().cid(), stored.output().to_owned());
+            }
+        }
+
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(
+            outputs.get(&err_cid).unwrap(),
+            &InstructionResult::Ok(Ipld::Integer(42))
+        );
+        assert_eq!(
+            outputs.get(&ok_cid).unwrap(),
+            &InstructionResult::Error(
+                TaskFailure::Skipped(Await::new(Pointer::new(instruction1_cid), AwaitResult::Ok))
+                    .into()
+            )
+        );
+    }
+
     #[homestar_runtime_proc_macro::db_async_test]
     fn initialize_worker_with_all_receipted_instruction() {
         let settings = TestSettings::load();
@@ -17,8 +17,11 @@ use diesel::{
 };
 use homestar_core::{
     workflow::{
-        input::{Parse, Parsed},
+        config::Resources,
+        input::{Input, Parse, Parsed},
         instruction::RunInstruction,
+        pointer::{Await, AwaitResult},
+        prf::UcanPrf,
         Instruction, Invocation, Pointer,
     },
     Workflow,
@@ -33,9 +36,14 @@ use tracing::debug;
 use url::Url;
 
 mod info;
+pub(crate) mod instruction;
+pub(crate) mod proof;
 pub mod settings;
 pub use info::WORKFLOW_TAG;
-pub(crate) use info::{Info, Stored, StoredReceipt};
+pub(crate) use info::{Info, Status, Stored, StoredReceipt};
+pub(crate) use instruction::StoredInstruction;
+pub use instruction::INSTRUCTION_TAG;
+pub(crate) use proof::{Authorizer, StoredProof};
 #[allow(unused_imports)]
 pub use settings::Settings;
 
@@ -43,7 +51,41 @@ type Dag<'a> = dagga::Dag<Vertex<'a>, usize>;
 
 /// A [Workflow] [Builder] wrapper for the runtime.
 #[derive(Debug, Clone, PartialEq)]
-pub struct Builder<'a>(Workflow<'a, Arg>);
+pub struct Builder<'a> {
+    workflow: Workflow<'a, Arg>,
+    /// Resolved [Instruction]s for tasks given as [RunInstruction::Ptr],
+    /// keyed by [Instruction] [Cid].
+    instructions: IndexMap<Cid, Instruction<'a, Arg>>,
+    /// Default [Resources] limits for tasks, overridden key-by-key by each
+    /// task's own metadata.
+    resources: Resources,
+}
+
+/// [Resources] limits a task can set in its metadata.
+const TASK_RESOURCES_KEYS: [&str; 3] = ["fuel", "memory", "time"];
+
+/// Overlay a task's metadata over default [Resources] limits, key-by-key.
+///
+/// [Resources::try_from] leaves values it can't read unset, which would lift
+/// the limit instead, so every limit the task sets is checked up front.
+/// `u64::MAX`, as rounded by JSON encoders to `18446744073709552000`, is
+/// still read as unset.
+fn task_resources(task: &Pointer, defaults: &Resources, meta: &Ipld) -> anyhow::Result<Resources> {
+    let mut limits = from_ipld::<BTreeMap<String, Ipld>>(Ipld::from(defaults.to_owned()))?;
+    if let Ipld::Map(overrides) = meta {
+        for key in TASK_RESOURCES_KEYS {
+            match overrides.get(key) {
+                None | Some(Ipld::Null) => (),
+                Some(Ipld::Float(limit)) if *limit == u64::MAX as f64 => (),
+                Some(limit) if from_ipld::<u64>(limit.to_owned()).is_ok() => (),
+                Some(_) => bail!("task {task} metadata {key} must be a non-negative integer"),
+            }
+        }
+        limits.extend(overrides.to_owned());
+    }
+
+    Ok(Resources::try_from(Ipld::Map(limits))?)
+}
 
 /// A resource can refer to a [URI] or [Cid]
 /// being accessed.
@@ -78,6 +120,7 @@ impl fmt::Display for Resource {
 pub(crate) struct AOTContext<'a> {
     dag: Dag<'a>,
     indexed_resources: IndexedResources,
+    external_promises: Vec<Cid>,
 }
 
 impl AOTContext<'static> {
@@ -105,6 +148,11 @@ pub(crate) struct Vertex<'a> {
     pub(crate) instruction: Instruction<'a, Arg>,
     pub(crate) parsed: Parsed<Arg>,
     pub(crate) invocation: Pointer,
+    /// [Resources] (fuel, memory, time) limits declared in the task's
+    /// metadata.
+    pub(crate) config: Resources,
+    /// [UcanPrf] proofs authorizing the task's [Instruction].
+    pub(crate) prf: UcanPrf,
 }
 
 impl<'a> Vertex<'a> {
@@ -112,29 +160,72 @@ impl<'a> Vertex<'a> {
         instruction: Instruction<'a, Arg>,
         parsed: Parsed<Arg>,
         invocation: Pointer,
+        config: Resources,
+        prf: UcanPrf,
     ) -> Vertex<'a> {
         Vertex {
             instruction,
             parsed,
             invocation,
+            config,
+            prf,
         }
     }
+
+    /// Positional `await/ok` and `await/error` inputs of the [Vertex], as
+    /// branches that should only run if the awaited [Instruction]
+    /// respectively succeeded or failed.
+    pub(crate) fn branches(&self) -> Vec<(usize, Await)> {
+        self.parsed
+            .args()
+            .inner()
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, input)| match input {
+                Input::Deferred(promise) if promise.result() != &AwaitResult::Ptr => {
+                    Some((idx, promise.to_owned()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
 }
 
 impl<'a> Builder<'a> {
     /// Create a new [Workflow] [Builder] given a [Workflow].
     pub fn new(workflow: Workflow<'a, Arg>) -> Builder<'a> {
-        Builder(workflow)
+        Builder {
+            workflow,
+            instructions: IndexMap::new(),
+            resources: Resources::default(),
+        }
+    }
+
+    /// Set default [Resources] limits for tasks, which each task's metadata
+    /// may override key-by-key.
+    pub(crate) fn with_resources(mut self, resources: Resources) -> Builder<'a> {
+        self.resources = resources;
+        self
+    }
+
+    /// Set resolved [Instruction]s for tasks given as [RunInstruction::Ptr],
+    /// keyed by [Instruction] [Cid].
+    pub(crate) fn with_instructions(
+        mut self,
+        instructions: IndexMap<Cid, Instruction<'a, Arg>>,
+    ) -> Builder<'a> {
+        self.instructions = instructions;
+        self
     }
 
     /// Return an owned [Workflow] from the [Builder].
     pub fn into_inner(self) -> Workflow<'a, Arg> {
-        self.0
+        self.workflow
     }
 
     /// Return a referenced [Workflow] from the [Builder].
     pub fn inner(&self) -> &Workflow<'a, Arg> {
-        &self.0
+        &self.workflow
     }
 
     /// Convert the [Workflow] into an batch-separated [ExecutionGraph].
@@ -144,6 +235,7 @@ impl<'a> Builder<'a> {
             Ok(schedule) => Ok(ExecutionGraph {
                 schedule: schedule.batches,
                 indexed_resources: aot.indexed_resources,
+                external_promises: aot.external_promises,
             }),
             Err(e) => bail!("schedule could not be built from given workflow: {e}"),
         }
@@ -151,10 +243,15 @@ impl<'a> Builder<'a> {
 
     fn aot(self) -> anyhow::Result<AOTContext<'a>> {
         let lookup_table = self.lookup_table()?;
-        let (mut dag, unawaits, awaited, resources) =
-            self.into_inner().tasks().into_iter().enumerate().try_fold(
-                (Dag::default(), vec![], vec![], IndexMap::new()),
-                |(mut dag, mut unawaits, mut awaited, mut resources), (i, task)| {
+        let Builder {
+            workflow,
+            instructions,
+            resources: default_resources,
+        } = self;
+        let (mut dag, unawaits, awaited, resources, external_promises) =
+            workflow.tasks().into_iter().enumerate().try_fold(
+                (Dag::default(), vec![], vec![], IndexMap::new(), vec![]),
+                |(mut dag, mut unawaits, mut awaited, mut resources, mut external), (i, task)| {
                     let instr_cid = task.instruction_cid()?;
                     debug!(
                         subject = "task.instruction",
@@ -165,9 +262,18 @@ impl<'a> Builder<'a> {
 
                     // Clone as we're owning the struct going backward.
                     let ptr: Pointer = Invocation::<Arg>::from(task.clone()).try_into()?;
-
-                    let RunInstruction::Expanded(instr) = task.into_instruction() else {
-                        bail!("workflow tasks/instructions must be expanded / inlined")
+                    // Fallback to the workflow's default limits for any not
+                    // given as metadata.
+                    let config = task_resources(&ptr, &default_resources, task.meta())?;
+                    let prf = task.prf().to_owned();
+
+                    let instr = match task.into_instruction() {
+                        RunInstruction::Expanded(instr) => instr,
+                        RunInstruction::Ptr(ptr) => {
+                            instructions.get(&ptr.cid()).cloned().ok_or_else(|| {
+                                anyhow!("instruction pointer {ptr} could not be resolved")
+                            })?
+                        }
                     };
 
                     resources
@@ -180,9 +286,11 @@ impl<'a> Builder<'a> {
                         .fold(vec![], |mut in_flow_reads, cid| {
                             if let Some(v) = lookup_table.get(&cid) {
                                 in_flow_reads.push(*v)
+                            } else if !external.contains(&cid) {
+                                // Promise from a task outside of the workflow,
+                                // awaited on at runtime.
+                                external.push(cid)
                             }
-                            // TODO: else, it's a Promise from another task outside
-                            // of the workflow.
                             in_flow_reads
                         });
 
@@ -195,7 +303,7 @@ impl<'a> Builder<'a> {
                             .or_insert_with(|| vec![Resource::Cid(cid.to_owned())]);
                     });
 
-                    let node = Node::new(Vertex::new(instr.to_owned(), parsed, ptr))
+                    let node = Node::new(Vertex::new(instr.to_owned(), parsed, ptr, config, prf))
                         .with_name(instr_cid.to_string())
                         .with_result(i);
 
@@ -206,7 +314,7 @@ impl<'a> Builder<'a> {
                         unawaits.push(node);
                     }
 
-                    Ok::<_, anyhow::Error>((dag, unawaits, awaited, resources))
+                    Ok::<_, anyhow::Error>((dag, unawaits, awaited, resources, external))
                 },
             )?;
 
@@ -223,6 +331,7 @@ impl<'a> Builder<'a> {
         Ok(AOTContext {
             dag,
             indexed_resources: IndexedResources(resources),
+            external_promises,
         })
     }
 
@@ -398,6 +507,7 @@ mod test {
         },
         Unit,
     };
+    use libipld::multihash::{Code, MultihashDigest};
     use std::path::Path;
 
     #[test]
@@ -502,6 +612,57 @@ mod test {
         dagga::assert_batches(&[&instr1, &instr2], dag);
     }
 
+    #[test]
+    fn build_graph_with_resolved_pointer() {
+        let config = Resources::default();
+        let instruction = test_utils::workflow::wasm_instruction::<Arg>();
+        let instruction_cid = instruction.clone().to_cid().unwrap();
+        let task = Task::new(
+            RunInstruction::Ptr(Pointer::new(instruction_cid)),
+            config.into(),
+            UcanPrf::default(),
+        );
+
+        let workflow = Workflow::new(vec![task]);
+        assert!(Builder::new(workflow.clone()).graph().is_err());
+
+        let builder = Builder::new(workflow)
+            .with_instructions(IndexMap::from([(instruction_cid, instruction.clone())]));
+        let schedule = builder.graph().unwrap().schedule;
+        let vertex = schedule.first().unwrap().first().unwrap();
+
+        assert_eq!(vertex.name(), instruction_cid.to_string());
+        assert_eq!(vertex.inner().instruction, instruction);
+    }
+
+    #[test]
+    fn build_graph_with_external_promise() {
+        let config = Resources::default();
+        let (instruction1, instruction2, instruction3) =
+            test_utils::workflow::related_wasm_instructions::<Arg>();
+        let task2 = Task::new(
+            RunInstruction::Expanded(instruction2),
+            config.clone().into(),
+            UcanPrf::default(),
+        );
+        let task3 = Task::new(
+            RunInstruction::Expanded(instruction3),
+            config.into(),
+            UcanPrf::default(),
+        );
+
+        let workflow = Workflow::new(vec![task2, task3]);
+        let graph = Builder::new(workflow).graph().unwrap();
+
+        // Both tasks await on the first instruction, which isn't part of the
+        // workflow, and it's only collected once.
+        assert_eq!(
+            graph.external_promises,
+            vec![instruction1.to_cid().unwrap()]
+        );
+        assert_eq!(graph.schedule.len(), 2);
+    }
+
     #[test]
     fn build_mixed_graph() {
         let config = Resources::default();
@@ -626,4 +787,51 @@ mod test {
                     ]
         );
     }
+
+    #[test]
+    fn overlay_task_resources() {
+        let task = Pointer::new(Cid::new_v1(0x71, Code::Sha2_256.digest(b"task")));
+        let defaults = Resources::new(1_000, 4_096, std::time::Duration::from_millis(500));
+        let meta = Ipld::Map(BTreeMap::from([
+            ("fuel".into(), Ipld::Integer(10)),
+            ("time".into(), Ipld::Null),
+        ]));
+
+        let config = task_resources(&task, &defaults, &meta).unwrap();
+        assert_eq!(config.fuel(), Some(10));
+        assert_eq!(config.memory(), Some(4_096));
+        assert_eq!(config.time(), None);
+
+        assert_eq!(
+            task_resources(&task, &defaults, &Ipld::Null).unwrap(),
+            defaults
+        );
+
+        // `u64::MAX` as rounded in JSON workflows.
+        let meta = Ipld::Map(BTreeMap::from([(
+            "fuel".into(),
+            Ipld::Float(18446744073709552000.0),
+        )]));
+        let config = task_resources(&task, &defaults, &meta).unwrap();
+        assert_eq!(config.fuel(), None);
+    }
+
+    #[test]
+    fn reject_invalid_task_resources() {
+        let task = Pointer::new(Cid::new_v1(0x71, Code::Sha2_256.digest(b"task")));
+        let defaults = Resources::default();
+
+        for (key, limit) in [
+            ("memory", Ipld::String("4GiB".into())),
+            ("fuel", Ipld::Integer(-1)),
+            ("time", Ipld::Float(1.5)),
+        ] {
+            let meta = Ipld::Map(BTreeMap::from([(key.into(), limit)]));
+            let err = task_resources(&task, &defaults, &meta).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!("task {task} metadata {key} must be a non-negative integer")
+            );
+        }
+    }
 }
@@ -12,12 +12,19 @@ use crate::{
 };
 use anyhow::{anyhow, bail, Result};
 use chrono::{NaiveDateTime, Utc};
-use diesel::{Associations, Identifiable, Insertable, Queryable, Selectable};
+use diesel::{
+    backend::Backend,
+    deserialize::{self, FromSql},
+    serialize::{self, IsNull, Output, ToSql},
+    sql_types::Text,
+    sqlite::Sqlite,
+    AsExpression, Associations, FromSqlRow, Identifiable, Insertable, Queryable, Selectable,
+};
 use faststr::FastStr;
 use homestar_core::{ipld::DagJson, workflow::Pointer};
 use libipld::{cbor::DagCborCodec, prelude::Codec, serde::from_ipld, Cid, Ipld};
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, fmt, sync::Arc, time::Duration};
+use std::{collections::BTreeMap, fmt, str::FromStr, sync::Arc, time::Duration};
 use tokio::{
     runtime::Handle,
     time::{timeout_at, Instant},
@@ -66,6 +73,23 @@ pub struct Stored {
     ///
     /// [Workflow]: homestar_core::Workflow
     pub(crate) completed_at: Option<NaiveDateTime>,
+    /// Lifecycle [Status] of [Workflow].
+    ///
+    /// [Workflow]: homestar_core::Workflow
+    pub(crate) status: Status,
+    /// Error the [Workflow] last failed with.
+    ///
+    /// [Workflow]: homestar_core::Workflow
+    pub(crate) last_error: Option<String>,
+    /// Local timestamp of [Workflow] reaching a final [Status].
+    ///
+    /// [Workflow]: homestar_core::Workflow
+    pub(crate) finished_at: Option<NaiveDateTime>,
+    /// DAG-CBOR encoded [Workflow], with its settings as its `meta`
+    /// section, kept for resuming it.
+    ///
+    /// [Workflow]: homestar_core::Workflow
+    pub(crate) body: Option<Vec<u8>>,
 }
 
 impl Stored {
@@ -86,6 +110,10 @@ impl Stored {
             resources,
             created_at,
             completed_at: None,
+            status: Status::Queued,
+            last_error: None,
+            finished_at: None,
+            body: None,
         }
     }
 
@@ -105,6 +133,10 @@ impl Stored {
             resources,
             created_at: Utc::now().naive_utc(),
             completed_at: None,
+            status: Status::Queued,
+            last_error: None,
+            finished_at: None,
+            body: None,
         }
     }
 
@@ -120,10 +152,103 @@ impl Stored {
             resources: IndexedResources::default(),
             created_at: Utc::now().naive_utc(),
             completed_at: None,
+            status: Status::Queued,
+            last_error: None,
+            finished_at: None,
+            body: None,
         }
     }
 }
 
+/// Lifecycle status of a [Workflow] run, as stored in the database.
+///
+/// [Workflow]: homestar_core::Workflow
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, AsExpression, FromSqlRow,
+)]
+#[diesel(sql_type = Text)]
+#[serde(rename_all = "kebab-case")]
+pub enum Status {
+    /// Waiting for a running worker slot.
+    Queued,
+    /// Running on a worker.
+    Running,
+    /// Paused by the user, until resumed.
+    Paused,
+    /// Ran to completion.
+    Completed,
+    /// Stopped by an error.
+    Failed,
+    /// Cancelled by the user.
+    Cancelled,
+    /// Aborted after exceeding its timeout.
+    TimedOut,
+}
+
+impl Status {
+    /// Statuses a [Workflow] can still move on from.
+    ///
+    /// [Workflow]: homestar_core::Workflow
+    pub(crate) const UNFINISHED: [Status; 3] = [Status::Queued, Status::Running, Status::Paused];
+
+    /// Whether the [Status] is final, i.e. the [Workflow] is no longer
+    /// queued, running or paused.
+    ///
+    /// [Workflow]: homestar_core::Workflow
+    pub(crate) fn is_finished(&self) -> bool {
+        !Self::UNFINISHED.contains(self)
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Queued => write!(f, "queued"),
+            Status::Running => write!(f, "running"),
+            Status::Paused => write!(f, "paused"),
+            Status::Completed => write!(f, "completed"),
+            Status::Failed => write!(f, "failed"),
+            Status::Cancelled => write!(f, "cancelled"),
+            Status::TimedOut => write!(f, "timed-out"),
+        }
+    }
+}
+
+impl FromStr for Status {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "queued" => Ok(Status::Queued),
+            "running" => Ok(Status::Running),
+            "paused" => Ok(Status::Paused),
+            "completed" => Ok(Status::Completed),
+            "failed" => Ok(Status::Failed),
+            "cancelled" => Ok(Status::Cancelled),
+            "timed-out" => Ok(Status::TimedOut),
+            _ => Err(anyhow!("unknown workflow status: {s}")),
+        }
+    }
+}
+
+impl ToSql<Text, Sqlite> for Status {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+        out.set_value(self.to_string());
+        Ok(IsNull::No)
+    }
+}
+
+impl<DB> FromSql<Text, DB> for Status
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        let s = String::from_sql(bytes)?;
+        Ok(Status::from_str(&s)?)
+    }
+}
+
 /// [Workflow] information stored in the database, tied to [receipts].
 ///
 /// [Workflow]: homestar_core::Workflow
@@ -283,8 +408,9 @@ impl Info {
     ) -> Result<(Self, NaiveDateTime)> {
         let timestamp = Utc::now().naive_utc();
         match Db::get_workflow_info(workflow_cid, &mut conn) {
-            Ok((Some(stored_name), info)) if stored_name != name => {
-                Db::update_local_name(&name, &mut conn)?;
+            Ok((stored_name, mut info)) if stored_name.as_deref() != Some(name.as_str()) => {
+                Db::update_local_name(workflow_cid, &name, &mut conn)?;
+                info.name = Some(name);
                 Ok((info, timestamp))
             }
             Ok((_, info)) => Ok((info, timestamp)),
@@ -559,6 +685,30 @@ mod test {
     use homestar_wasm::io::Arg;
     use indexmap::IndexMap;
 
+    #[test]
+    fn status_string_roundtrip() {
+        for status in [
+            Status::Queued,
+            Status::Running,
+            Status::Paused,
+            Status::Completed,
+            Status::Failed,
+            Status::Cancelled,
+            Status::TimedOut,
+        ] {
+            assert_eq!(Status::from_str(&status.to_string()).unwrap(), status);
+            assert_eq!(
+                serde_json::to_value(status).unwrap(),
+                serde_json::json!(status.to_string())
+            );
+        }
+
+        assert!(!Status::Running.is_finished());
+        assert!(!Status::Paused.is_finished());
+        assert!(Status::TimedOut.is_finished());
+        assert!(Status::from_str("stopped").is_err());
+    }
+
     #[test]
     fn ipld_roundtrip_workflow_info() {
         let config = Resources::default();
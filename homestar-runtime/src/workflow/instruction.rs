@@ -0,0 +1,255 @@
+//! Resolution of [Instruction]s referenced by [Pointer] within a [Workflow]'s
+//! tasks, i.e. [RunInstruction::Ptr].
+//!
+//! [Workflow]: homestar_core::Workflow
+
+#[cfg(feature = "ipfs")]
+use crate::network::IpfsCli;
+use crate::{
+    channel::{AsyncChannel, AsyncChannelReceiver, AsyncChannelSender},
+    db::Database,
+    event_handler::{
+        event::QueryRecord,
+        swarm_event::{FoundEvent, ResponseEvent},
+        Event,
+    },
+    network::swarm::CapsuleTag,
+    settings, Db,
+};
+use anyhow::{anyhow, bail, ensure, Result};
+use diesel::{Insertable, Queryable, Selectable};
+use homestar_core::{
+    ipld::DagCbor,
+    workflow::{instruction::RunInstruction, Instruction, Pointer},
+    Workflow,
+};
+use homestar_wasm::io::Arg;
+use libipld::{cbor::DagCborCodec, prelude::Codec, Cid, Ipld};
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+use tokio::time::{timeout_at, Instant};
+use tracing::info;
+
+/// [Instruction] header tag, for sharing instructions over libp2p.
+pub const INSTRUCTION_TAG: &str = "ipvm/instruction";
+
+/// [Instruction] stored in the database as [DagCbor] encoded bytes, keyed by
+/// its [Cid].
+///
+/// [DagCbor]: DagCborCodec
+#[derive(Debug, Clone, PartialEq, Queryable, Insertable, Selectable)]
+#[diesel(table_name = crate::db::schema::instructions, primary_key(cid))]
+pub struct StoredInstruction {
+    /// Wrapped-[Cid] of the [Instruction].
+    pub(crate) cid: Pointer,
+    /// [DagCbor] encoded [Instruction].
+    ///
+    /// [DagCbor]: DagCborCodec
+    pub(crate) instruction: Vec<u8>,
+}
+
+impl StoredInstruction {
+    /// Create a [StoredInstruction] from [DagCbor] encoded bytes, verifying
+    /// that the bytes decode to an [Instruction] matching the given [Cid].
+    ///
+    /// [DagCbor]: DagCborCodec
+    pub(crate) fn try_with(cid: Cid, bytes: Vec<u8>) -> Result<Self> {
+        let stored = Self {
+            cid: Pointer::new(cid),
+            instruction: bytes,
+        };
+
+        let found_cid = stored.instruction()?.to_cid()?;
+        ensure!(
+            found_cid == cid,
+            "instruction cid mismatch: expected {cid}, found {found_cid}"
+        );
+
+        Ok(stored)
+    }
+
+    /// Return the [Cid] of the [Instruction].
+    pub(crate) fn cid(&self) -> Cid {
+        self.cid.cid()
+    }
+
+    /// Decode the stored bytes into an [Instruction].
+    pub(crate) fn instruction(&self) -> Result<Instruction<'static, Arg>> {
+        let ipld: Ipld = DagCborCodec.decode(&self.instruction)?;
+        Ok(Instruction::try_from(ipld)?)
+    }
+
+    /// Capsule-wrapper for a [StoredInstruction] to be shared over libp2p as
+    /// [DagCbor] encoded bytes.
+    ///
+    /// [DagCbor]: DagCborCodec
+    pub(crate) fn capsule(&self) -> Result<Vec<u8>> {
+        let ipld: Ipld = DagCborCodec.decode(&self.instruction)?;
+        let capsule = Ipld::Map(BTreeMap::from([(INSTRUCTION_TAG.into(), ipld)]));
+        DagCborCodec.encode(&capsule)
+    }
+
+    /// Resolve [Instruction]s referenced by [Pointer]s, storing them in the
+    /// database, via 3 lookups:
+    ///   * a check in the database;
+    ///   * a single DHT query for providers of the instruction, fetching it
+    ///     from the first provider that returns it;
+    ///   * a lookup of the block on IPFS.
+    pub(crate) async fn resolve(
+        pointers: Vec<Pointer>,
+        db: impl Database,
+        network_settings: &settings::Dht,
+        event_sender: Arc<AsyncChannelSender<Event>>,
+        #[cfg(feature = "ipfs")] ipfs: IpfsCli,
+    ) -> Result<()> {
+        let lookups = pointers.into_iter().map(|ptr| {
+            let db = db.clone();
+            let event_sender = event_sender.clone();
+            #[cfg(feature = "ipfs")]
+            let ipfs = ipfs.clone();
+            async move {
+                let cid = ptr.cid();
+                if Db::find_stored_instruction(cid, &mut db.conn()?).is_ok() {
+                    return Ok(());
+                }
+
+                info!(
+                    subject = "instruction.resolve.db.check",
+                    category = "workflow",
+                    cid = cid.to_string(),
+                    "instruction not available in the database"
+                );
+
+                let stored = Self::retrieve_from_provider(
+                    cid,
+                    event_sender.clone(),
+                    network_settings.p2p_provider_timeout,
+                )
+                .await;
+
+                #[cfg(feature = "ipfs")]
+                let stored = match stored {
+                    Ok(stored) => Ok(stored),
+                    Err(_) => Self::retrieve_from_ipfs(cid, ipfs).await,
+                };
+
+                let stored = stored.map_err(|err| {
+                    anyhow!("instruction pointer {cid} could not be resolved: {err}")
+                })?;
+
+                Db::store_instruction(stored, &mut db.conn()?)?;
+                event_sender
+                    .send_async(Event::ProvideRecord(cid, None, CapsuleTag::Instruction))
+                    .await?;
+
+                Ok::<_, anyhow::Error>(())
+            }
+        });
+
+        futures::future::try_join_all(lookups).await?;
+        Ok(())
+    }
+
+    // Retrieve a [StoredInstruction] from a provider found through the DHT.
+    async fn retrieve_from_provider(
+        cid: Cid,
+        event_sender: Arc<AsyncChannelSender<Event>>,
+        p2p_provider_timeout: Duration,
+    ) -> Result<Self> {
+        let (tx, rx) = AsyncChannel::oneshot();
+        event_sender
+            .send_async(Event::GetProviders(QueryRecord::with(
+                cid,
+                CapsuleTag::Instruction,
+                Some(tx),
+            )))
+            .await?;
+
+        Self::await_found(cid, rx, p2p_provider_timeout).await
+    }
+
+    async fn await_found(
+        cid: Cid,
+        rx: AsyncChannelReceiver<ResponseEvent>,
+        p2p_timeout: Duration,
+    ) -> Result<Self> {
+        match timeout_at(Instant::now() + p2p_timeout, rx.recv_async()).await {
+            Ok(Ok(ResponseEvent::Found(Ok(FoundEvent::Instruction(event))))) => {
+                Ok(*event.instruction)
+            }
+            Ok(Ok(ResponseEvent::Found(Err(err)))) => {
+                bail!("failure in attempting to find instruction: {err}")
+            }
+            Ok(Ok(event)) => {
+                bail!("received unexpected event {event:?} for instruction {cid}")
+            }
+            Ok(Err(err)) => {
+                bail!("unexpected error while retrieving instruction: {err}")
+            }
+            Err(_) => {
+                bail!("timeout deadline reached while finding instruction with cid {cid}")
+            }
+        }
+    }
+
+    // Retrieve a [StoredInstruction] from IPFS as a raw block.
+    #[cfg(feature = "ipfs")]
+    async fn retrieve_from_ipfs(cid: Cid, ipfs: IpfsCli) -> Result<Self> {
+        let bytes = ipfs.get_block(cid).await?;
+        Self::try_with(cid, bytes)
+    }
+}
+
+impl TryFrom<Instruction<'_, Arg>> for StoredInstruction {
+    type Error = anyhow::Error;
+
+    fn try_from(instruction: Instruction<'_, Arg>) -> Result<Self, Self::Error> {
+        let cid = instruction.clone().to_cid()?;
+        let bytes = DagCborCodec.encode(&Ipld::from(instruction))?;
+
+        Ok(Self {
+            cid: Pointer::new(cid),
+            instruction: bytes,
+        })
+    }
+}
+
+/// Gather [Pointer]s of [Workflow] tasks given as [RunInstruction::Ptr].
+pub(crate) fn pointers(workflow: &Workflow<'_, Arg>) -> Vec<Pointer> {
+    workflow
+        .tasks_ref()
+        .iter()
+        .filter_map(|task| match task.run() {
+            RunInstruction::Ptr(ptr) => Some(ptr.to_owned()),
+            RunInstruction::Expanded(_) => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use homestar_core::test_utils::workflow as workflow_test_utils;
+
+    #[test]
+    fn stored_instruction_roundtrip() {
+        let instruction = workflow_test_utils::wasm_instruction::<Arg>();
+        let cid = instruction.clone().to_cid().unwrap();
+        let stored = StoredInstruction::try_from(instruction.clone()).unwrap();
+
+        assert_eq!(stored.cid(), cid);
+        assert_eq!(stored.instruction().unwrap(), instruction);
+        assert_eq!(
+            StoredInstruction::try_with(cid, stored.instruction.clone()).unwrap(),
+            stored
+        );
+    }
+
+    #[test]
+    fn stored_instruction_cid_mismatch() {
+        let instruction = workflow_test_utils::wasm_instruction::<Arg>();
+        let (other, _) = workflow_test_utils::wasm_instruction_with_nonce::<Arg>();
+        let stored = StoredInstruction::try_from(instruction).unwrap();
+
+        assert!(StoredInstruction::try_with(other.to_cid().unwrap(), stored.instruction).is_err());
+    }
+}
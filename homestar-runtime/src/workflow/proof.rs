@@ -0,0 +1,380 @@
+//! UCAN proofs linked from a task's `prf` field, and authorization of tasks
+//! against the delegation chains they form.
+
+use crate::{blockstore::Blockstore, db::Database, tasks::unixfs, Db};
+use anyhow::{anyhow, bail, Result};
+use diesel::{Insertable, Queryable, Selectable};
+use homestar_core::workflow::{prf::UcanPrf, Pointer};
+use libipld::Cid;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    str::FromStr,
+};
+use ucan::{
+    crypto::did::{DidParser, KeyConstructorSlice},
+    Ucan,
+};
+use ucan_key_support::{
+    ed25519::{bytes_to_ed25519_key, ED25519_MAGIC_BYTES},
+    rsa::{bytes_to_rsa_key, RSA_MAGIC_BYTES},
+};
+
+/// Key types UCAN issuers are resolved from, as `did:key`s.
+const SUPPORTED_KEYS: &KeyConstructorSlice = &[
+    (ED25519_MAGIC_BYTES, bytes_to_ed25519_key),
+    (RSA_MAGIC_BYTES, bytes_to_rsa_key),
+];
+
+/// Upper bound on the number of UCANs resolved for a single task's proofs,
+/// guarding against runaway delegation chains.
+const MAX_PROOFS: usize = 64;
+
+/// Wildcard matching any resource or ability in a capability.
+const WILDCARD: &str = "*";
+
+/// UCAN proof stored in the database as its encoded JWT, keyed by its [Cid].
+#[derive(Debug, Clone, PartialEq, Queryable, Insertable, Selectable)]
+#[diesel(table_name = crate::db::schema::proofs, primary_key(cid))]
+pub struct StoredProof {
+    /// Wrapped-[Cid] of the UCAN.
+    pub(crate) cid: Pointer,
+    /// Encoded UCAN JWT.
+    pub(crate) token: String,
+}
+
+impl StoredProof {
+    /// Create a [StoredProof] from an encoded UCAN, verifying that the
+    /// token's bytes hash to the given [Cid].
+    pub(crate) fn try_with(cid: Cid, token: String) -> Result<Self> {
+        unixfs::verify(cid, token.as_bytes())?;
+        Ok(Self {
+            cid: Pointer::new(cid),
+            token,
+        })
+    }
+
+    /// Return the [Cid] of the UCAN.
+    pub(crate) fn cid(&self) -> Cid {
+        self.cid.cid()
+    }
+
+    /// Return the encoded UCAN JWT.
+    pub(crate) fn token(&self) -> &str {
+        &self.token
+    }
+}
+
+/// Authorizes tasks against the UCANs linked from their [UcanPrf], which
+/// must delegate the task's resource and operation to the node, from a
+/// trusted root issuer.
+#[derive(Clone, Debug)]
+pub(crate) struct Authorizer {
+    /// `did:key` of the node, which proofs must be delegated to.
+    audience: String,
+    /// `did:key`s trusted to issue root UCANs, including the node's own.
+    roots: HashSet<String>,
+    /// [Blockstore] proofs are looked up in, after the database.
+    blockstore: Blockstore,
+}
+
+impl Authorizer {
+    /// Create an [Authorizer] for the node's `did:key`, trusting root UCANs
+    /// issued by the node itself or by one of `trusted_issuers`.
+    pub(crate) fn new(
+        audience: String,
+        trusted_issuers: &[String],
+        blockstore: Blockstore,
+    ) -> Self {
+        let roots = trusted_issuers
+            .iter()
+            .cloned()
+            .chain([audience.clone()])
+            .collect();
+        Self {
+            audience,
+            roots,
+            blockstore,
+        }
+    }
+
+    /// Authorize running operation `op` on resource `rsc`, given a task's
+    /// [UcanPrf].
+    ///
+    /// Every UCAN in the delegation chain is resolved via 2 lookups:
+    ///   * a check in the database, which includes proofs bundled with a
+    ///     submitted workflow;
+    ///   * a check in the [Blockstore].
+    ///
+    /// The task is authorized if any UCAN it links to is delegated to the
+    /// node, is valid (signed and within its time bounds), grants the
+    /// capability, and is backed by a chain of proofs that each grant the
+    /// capability to their delegate's issuer and encompass its lifetime,
+    /// rooted in a UCAN issued by the node or a trusted issuer.
+    pub(crate) async fn authorize(
+        &self,
+        prf: &UcanPrf,
+        rsc: &str,
+        op: &str,
+        db: &impl Database,
+    ) -> Result<()> {
+        let links: Vec<String> = prf
+            .inner()
+            .iter()
+            .map(|link| link.cid().to_string())
+            .collect();
+        if links.is_empty() {
+            bail!("task carries no proofs authorizing {op} on {rsc}");
+        }
+
+        let ucans = self.resolve(&links, db).await?;
+        if links
+            .iter()
+            .filter_map(|cid| ucans.get(cid))
+            .filter(|ucan| ucan.audience() == self.audience)
+            .any(|ucan| delegates(ucan, rsc, op, &ucans, &self.roots, 0))
+        {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "no proof chain delegates {op} on {rsc} to {}",
+                self.audience
+            ))
+        }
+    }
+
+    /// Resolve and validate UCANs reachable from the given links,
+    /// breadth-first, keyed by their (string-encoded) [Cid].
+    async fn resolve(&self, links: &[String], db: &impl Database) -> Result<HashMap<String, Ucan>> {
+        let mut did_parser = DidParser::new(SUPPORTED_KEYS);
+        let mut ucans = HashMap::new();
+        let mut queue: VecDeque<String> = links.iter().cloned().collect();
+
+        while let Some(link) = queue.pop_front() {
+            if ucans.contains_key(&link) {
+                continue;
+            }
+            if ucans.len() >= MAX_PROOFS {
+                bail!("proof chain exceeds {MAX_PROOFS} UCANs");
+            }
+
+            let cid = Cid::from_str(&link)?;
+            let token = self.lookup(cid, db).await?;
+            let ucan = Ucan::from_str(&token)
+                .map_err(|err| anyhow!("proof {cid} is not a valid UCAN: {err}"))?;
+            ucan.validate(None, &mut did_parser)
+                .await
+                .map_err(|err| anyhow!("proof {cid} failed validation: {err}"))?;
+
+            if let Some(proofs) = ucan.proofs() {
+                queue.extend(proofs.iter().cloned());
+            }
+            ucans.insert(link, ucan);
+        }
+
+        Ok(ucans)
+    }
+
+    /// Look up an encoded UCAN by [Cid] in the database, then in the
+    /// [Blockstore].
+    async fn lookup(&self, cid: Cid, db: &impl Database) -> Result<String> {
+        if let Ok(stored) = Db::find_proof(cid, &mut db.conn()?) {
+            return Ok(stored.token().to_string());
+        }
+
+        let block = self
+            .blockstore
+            .get(cid)
+            .await?
+            .ok_or_else(|| anyhow!("proof {cid} could not be resolved"))?;
+        Ok(String::from_utf8(block)?)
+    }
+}
+
+/// Check that a [Ucan] grants `op` on `rsc`, and that it is either a root
+/// UCAN issued by one of the trusted `roots` or backed by a proof delegated
+/// to its issuer that does so too.
+fn delegates(
+    ucan: &Ucan,
+    rsc: &str,
+    op: &str,
+    ucans: &HashMap<String, Ucan>,
+    roots: &HashSet<String>,
+    depth: usize,
+) -> bool {
+    if depth > MAX_PROOFS || !grants(ucan, rsc, op) {
+        return false;
+    }
+
+    match ucan.proofs() {
+        Some(proofs) if !proofs.is_empty() => proofs
+            .iter()
+            .filter_map(|cid| ucans.get(cid))
+            .filter(|proof| proof.audience() == ucan.issuer() && proof.lifetime_encompasses(ucan))
+            .any(|proof| delegates(proof, rsc, op, ucans, roots, depth + 1)),
+        _ => roots.contains(ucan.issuer()),
+    }
+}
+
+/// Check that one of a [Ucan]'s capabilities covers `op` on `rsc`.
+fn grants(ucan: &Ucan, rsc: &str, op: &str) -> bool {
+    ucan.capabilities().iter().any(|capability| {
+        (capability.resource == rsc || capability.resource == WILDCARD)
+            && (capability.ability == op || capability.ability == WILDCARD)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{settings, test_utils::db::MemoryDb};
+    use homestar_core::ipld::Link;
+    use libipld::multihash::{Code, MultihashDigest};
+    use ucan::{builder::UcanBuilder, capability::Capability, crypto::KeyMaterial};
+    use ucan_key_support::ed25519::Ed25519KeyMaterial;
+
+    const RSC: &str = "ipfs://bafybeig6u35v6t3f4j3zgz2jvj4erd45fbkeolioaddu3lmu6uxm3ilb7a";
+    const OP: &str = "wasm/run";
+    const TOKEN: &str = "eyJhbGciOiJFZERTQSIsInR5cCI6IkpXVCJ9.e30.c2ln";
+
+    fn cid_of(token: &str) -> Cid {
+        Cid::new_v1(0x55, Code::Sha2_256.digest(token.as_bytes()))
+    }
+
+    fn keypair(seed: u8) -> Ed25519KeyMaterial {
+        let private = ed25519_zebra::SigningKey::from([seed; 32]);
+        Ed25519KeyMaterial(
+            ed25519_zebra::VerificationKey::from(&private),
+            Some(private),
+        )
+    }
+
+    /// Issue and encode a UCAN granting `op` on `rsc` to `audience`,
+    /// backed by `proof`, if any.
+    async fn issue(
+        issuer: &Ed25519KeyMaterial,
+        audience: &str,
+        rsc: &str,
+        proof: Option<&str>,
+    ) -> String {
+        let capability = Capability::new(rsc.to_string(), OP.to_string(), serde_json::json!({}));
+        let builder = UcanBuilder::default()
+            .issued_by(issuer)
+            .for_audience(audience)
+            .with_lifetime(3600)
+            .claiming_capability(&capability);
+        let builder = match proof {
+            Some(token) => {
+                builder.witnessed_by(&Ucan::from_str(token).unwrap(), Some(Code::Sha2_256))
+            }
+            None => builder,
+        };
+
+        builder
+            .build()
+            .unwrap()
+            .sign()
+            .await
+            .unwrap()
+            .encode()
+            .unwrap()
+    }
+
+    fn store(token: &str, db: &MemoryDb) -> UcanPrf {
+        let stored = StoredProof::try_with(cid_of(token), token.to_string()).unwrap();
+        MemoryDb::store_proof(stored, &mut db.conn().unwrap()).unwrap();
+        UcanPrf::new(vec![Link::new(cid_of(token))])
+    }
+
+    #[test]
+    fn stored_proof_checks_cid() {
+        let stored = StoredProof::try_with(cid_of(TOKEN), TOKEN.to_string()).unwrap();
+        assert_eq!(stored.cid(), cid_of(TOKEN));
+        assert_eq!(stored.token(), TOKEN);
+
+        assert!(StoredProof::try_with(cid_of("other"), TOKEN.to_string()).is_err());
+    }
+
+    #[homestar_runtime_proc_macro::db_async_test]
+    fn refuse_unproven_tasks() {
+        let settings = TestSettings::load();
+        let db = MemoryDb::setup_connection_pool(settings.node(), None).unwrap();
+        let authorizer = Authorizer::new(
+            "did:key:z6MkiTBz1ymuepAQ4HEHYSF1H8quG5GLVVQR3djdX3mDooWp".to_string(),
+            &[],
+            Blockstore::new(&settings::Blockstore::default()),
+        );
+
+        assert!(authorizer
+            .authorize(&UcanPrf::default(), RSC, OP, &db)
+            .await
+            .is_err());
+
+        let prf = UcanPrf::new(vec![Link::new(cid_of(TOKEN))]);
+        assert!(authorizer.authorize(&prf, RSC, OP, &db).await.is_err());
+
+        let stored = StoredProof::try_with(cid_of(TOKEN), TOKEN.to_string()).unwrap();
+        MemoryDb::store_proof(stored.clone(), &mut db.conn().unwrap()).unwrap();
+        assert_eq!(
+            MemoryDb::find_proof(cid_of(TOKEN), &mut db.conn().unwrap()).unwrap(),
+            stored
+        );
+        // Resolved, but not a UCAN delegating the capability.
+        assert!(authorizer.authorize(&prf, RSC, OP, &db).await.is_err());
+    }
+
+    #[homestar_runtime_proc_macro::db_async_test]
+    fn authorize_delegated_tasks() {
+        let settings = TestSettings::load();
+        let db = MemoryDb::setup_connection_pool(settings.node(), None).unwrap();
+        let (root, delegate, node) = (keypair(1), keypair(2), keypair(3));
+        let root_did = root.get_did().await.unwrap();
+        let node_did = node.get_did().await.unwrap();
+
+        let root_token = issue(&root, &delegate.get_did().await.unwrap(), RSC, None).await;
+        let token = issue(&delegate, &node_did, RSC, Some(&root_token)).await;
+        store(&root_token, &db);
+        let prf = store(&token, &db);
+
+        let authorizer = Authorizer::new(
+            node_did.clone(),
+            &[root_did],
+            Blockstore::new(&settings::Blockstore::default()),
+        );
+        assert!(authorizer.authorize(&prf, RSC, OP, &db).await.is_ok());
+        assert!(authorizer
+            .authorize(&prf, RSC, "wasm/other", &db)
+            .await
+            .is_err());
+
+        // Same chain, but its root issuer isn't trusted.
+        let untrusted = Authorizer::new(
+            node_did,
+            &[],
+            Blockstore::new(&settings::Blockstore::default()),
+        );
+        assert!(untrusted.authorize(&prf, RSC, OP, &db).await.is_err());
+    }
+
+    #[homestar_runtime_proc_macro::db_async_test]
+    fn refuse_self_issued_roots() {
+        let settings = TestSettings::load();
+        let db = MemoryDb::setup_connection_pool(settings.node(), None).unwrap();
+        let (root, anyone, node) = (keypair(1), keypair(4), keypair(3));
+        let node_did = node.get_did().await.unwrap();
+        let authorizer = Authorizer::new(
+            node_did.clone(),
+            &[root.get_did().await.unwrap()],
+            Blockstore::new(&settings::Blockstore::default()),
+        );
+
+        // A root UCAN granting everything, issued by anyone.
+        let token = issue(&anyone, &node_did, WILDCARD, None).await;
+        let prf = store(&token, &db);
+        assert!(authorizer.authorize(&prf, RSC, OP, &db).await.is_err());
+
+        // The node's own root UCANs are trusted.
+        let token = issue(&node, &node_did, WILDCARD, None).await;
+        let prf = store(&token, &db);
+        assert!(authorizer.authorize(&prf, RSC, OP, &db).await.is_ok());
+    }
+}
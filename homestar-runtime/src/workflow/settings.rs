@@ -2,7 +2,19 @@
 //!
 //! [Workflow]: homestar_core::Workflow
 
-use std::time::Duration;
+use crate::scheduler::SchedulerMode;
+use anyhow::{anyhow, bail, ensure, Result};
+use homestar_core::workflow::config::Resources;
+use libipld::{serde::from_ipld, Ipld};
+use std::{collections::BTreeMap, time::Duration};
+
+const NAME_KEY: &str = "name";
+const RETRIES_KEY: &str = "retries";
+const RETRY_INITIAL_DELAY_KEY: &str = "retry_initial_delay";
+const RETRY_MAX_DELAY_KEY: &str = "retry_max_delay";
+const TIMEOUT_KEY: &str = "timeout";
+const RESOURCES_KEY: &str = "resources";
+const SCHEDULER_MODE_KEY: &str = "scheduler_mode";
 
 /// Workflow settings.
 #[derive(Debug, Clone, PartialEq)]
@@ -15,6 +27,187 @@ pub struct Settings {
     pub(crate) retry_initial_delay: Duration,
     /// Timeout for a given workflow.
     pub(crate) timeout: Duration,
+    /// Mode for dispatching the workflow's tasks, falling back to the
+    /// node's configured mode if not set.
+    pub(crate) scheduler_mode: Option<SchedulerMode>,
+    /// Default [Resources] limits for tasks, overridden key-by-key by each
+    /// task's own metadata.
+    pub(crate) resources: Resources,
+}
+
+impl Settings {
+    /// Merge [Metadata] read from a workflow over these [Settings],
+    /// validating the result.
+    pub(crate) fn merge(mut self, metadata: &Metadata) -> Result<Self> {
+        if let Some(retries) = metadata.retries {
+            self.retries = retries;
+        }
+        if let Some(delay) = metadata.retry_initial_delay {
+            self.retry_initial_delay = delay;
+        }
+        if let Some(delay) = metadata.retry_max_delay {
+            self.retry_max_delay = delay;
+        }
+        if let Some(timeout) = metadata.timeout {
+            self.timeout = timeout;
+        }
+        if let Some(resources) = &metadata.resources {
+            self.resources = resources.to_owned();
+        }
+        if metadata.scheduler_mode.is_some() {
+            self.scheduler_mode = metadata.scheduler_mode;
+        }
+
+        ensure!(!self.timeout.is_zero(), "workflow timeout must be non-zero");
+        ensure!(
+            self.retry_initial_delay <= self.retry_max_delay,
+            "initial retry delay of {:?} exceeds maximum retry delay of {:?}",
+            self.retry_initial_delay,
+            self.retry_max_delay
+        );
+
+        Ok(self)
+    }
+}
+
+/// Workflow-level metadata, given as the top-level `meta` section of a
+/// workflow file, with durations in milliseconds.
+///
+/// Any values set override the default [Settings] for the workflow's run,
+/// so it runs the same way on any node.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Metadata {
+    /// Human-readable name of the workflow.
+    pub(crate) name: Option<String>,
+    /// Number of retries for the workflow.
+    pub(crate) retries: Option<u32>,
+    /// Initial delay between retries.
+    pub(crate) retry_initial_delay: Option<Duration>,
+    /// Maximum delay between retries.
+    pub(crate) retry_max_delay: Option<Duration>,
+    /// Timeout for the workflow.
+    pub(crate) timeout: Option<Duration>,
+    /// Default [Resources] limits for the workflow's tasks.
+    pub(crate) resources: Option<Resources>,
+    /// Mode for dispatching the workflow's tasks.
+    pub(crate) scheduler_mode: Option<SchedulerMode>,
+}
+
+impl TryFrom<Ipld> for Metadata {
+    type Error = anyhow::Error;
+
+    fn try_from(ipld: Ipld) -> Result<Self, Self::Error> {
+        let map = from_ipld::<BTreeMap<String, Ipld>>(ipld)
+            .map_err(|_| anyhow!("workflow metadata must be a map"))?;
+
+        if let Some(key) = map.keys().find(|key| {
+            ![
+                NAME_KEY,
+                RETRIES_KEY,
+                RETRY_INITIAL_DELAY_KEY,
+                RETRY_MAX_DELAY_KEY,
+                TIMEOUT_KEY,
+                RESOURCES_KEY,
+                SCHEDULER_MODE_KEY,
+            ]
+            .contains(&key.as_str())
+        }) {
+            return Err(anyhow!("unknown workflow metadata field: {key}"));
+        }
+
+        let field = |key: &str| map.get(key).filter(|ipld| **ipld != Ipld::Null).cloned();
+        let millis = |key: &str| {
+            field(key)
+                .map(|ipld| {
+                    from_ipld::<u64>(ipld)
+                        .map(Duration::from_millis)
+                        .map_err(|_| anyhow!("workflow metadata {key} must be milliseconds"))
+                })
+                .transpose()
+        };
+
+        let name = field(NAME_KEY)
+            .map(|ipld| match ipld {
+                Ipld::String(name) if !name.is_empty() => Ok(name),
+                _ => Err(anyhow!(
+                    "workflow metadata {NAME_KEY} must be a non-empty string"
+                )),
+            })
+            .transpose()?;
+        let retries = field(RETRIES_KEY)
+            .map(|ipld| {
+                from_ipld::<u32>(ipld)
+                    .map_err(|_| anyhow!("workflow metadata {RETRIES_KEY} must be a count"))
+            })
+            .transpose()?;
+        let resources = field(RESOURCES_KEY).map(parse_resources).transpose()?;
+        let scheduler_mode = field(SCHEDULER_MODE_KEY)
+            .map(|ipld| {
+                from_ipld::<SchedulerMode>(ipld).map_err(|_| {
+                    anyhow!("workflow metadata {SCHEDULER_MODE_KEY} must be batch or ready")
+                })
+            })
+            .transpose()?;
+
+        Ok(Self {
+            name,
+            retries,
+            retry_initial_delay: millis(RETRY_INITIAL_DELAY_KEY)?,
+            retry_max_delay: millis(RETRY_MAX_DELAY_KEY)?,
+            timeout: millis(TIMEOUT_KEY)?,
+            resources,
+            scheduler_mode,
+        })
+    }
+}
+
+impl From<&Settings> for Metadata {
+    fn from(settings: &Settings) -> Self {
+        Self {
+            name: None,
+            retries: Some(settings.retries),
+            retry_initial_delay: Some(settings.retry_initial_delay),
+            retry_max_delay: Some(settings.retry_max_delay),
+            timeout: Some(settings.timeout),
+            resources: Some(settings.resources.clone()),
+            scheduler_mode: settings.scheduler_mode,
+        }
+    }
+}
+
+impl From<Metadata> for Ipld {
+    fn from(metadata: Metadata) -> Self {
+        let millis = |duration: Duration| Ipld::Integer(duration.as_millis() as i128);
+
+        let mut map = BTreeMap::new();
+        if let Some(name) = metadata.name {
+            map.insert(NAME_KEY.into(), Ipld::String(name));
+        }
+        if let Some(retries) = metadata.retries {
+            map.insert(RETRIES_KEY.into(), Ipld::Integer(retries.into()));
+        }
+        if let Some(delay) = metadata.retry_initial_delay {
+            map.insert(RETRY_INITIAL_DELAY_KEY.into(), millis(delay));
+        }
+        if let Some(delay) = metadata.retry_max_delay {
+            map.insert(RETRY_MAX_DELAY_KEY.into(), millis(delay));
+        }
+        if let Some(timeout) = metadata.timeout {
+            map.insert(TIMEOUT_KEY.into(), millis(timeout));
+        }
+        if let Some(resources) = metadata.resources {
+            map.insert(RESOURCES_KEY.into(), resources.into());
+        }
+        if let Some(mode) = metadata.scheduler_mode {
+            let mode = match mode {
+                SchedulerMode::Batch => "batch",
+                SchedulerMode::Ready => "ready",
+            };
+            map.insert(SCHEDULER_MODE_KEY.into(), Ipld::String(mode.into()));
+        }
+
+        Ipld::Map(map)
+    }
 }
 
 #[cfg(all(not(test), not(feature = "test-utils")))]
@@ -25,6 +218,8 @@ impl Default for Settings {
             retry_max_delay: Duration::new(60, 0),
             retry_initial_delay: Duration::from_millis(500),
             timeout: Duration::new(3600, 0),
+            scheduler_mode: None,
+            resources: Resources::default(),
         }
     }
 }
@@ -37,6 +232,107 @@ impl Default for Settings {
             retry_max_delay: Duration::new(1, 0),
             retry_initial_delay: Duration::from_millis(50),
             timeout: Duration::from_secs(3600),
+            scheduler_mode: None,
+            resources: Resources::default(),
         }
     }
 }
+
+/// Parse [Resources] limits from workflow metadata.
+///
+/// [Resources::try_from] leaves values it can't read unset, which would lift
+/// the limit instead, so every value set is checked up front.
+fn parse_resources(ipld: Ipld) -> Result<Resources> {
+    let limits = from_ipld::<BTreeMap<String, Ipld>>(ipld.clone())
+        .map_err(|_| anyhow!("workflow metadata {RESOURCES_KEY} must be a map"))?;
+    if let Some(key) = limits.iter().find_map(|(key, value)| {
+        (*value != Ipld::Null && from_ipld::<u64>(value.to_owned()).is_err()).then_some(key)
+    }) {
+        bail!("workflow metadata {RESOURCES_KEY} {key} must be a non-negative integer");
+    }
+
+    Resources::try_from(ipld)
+        .map_err(|err| anyhow!("invalid workflow metadata {RESOURCES_KEY}: {err}"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn merge_metadata() {
+        let ipld = Ipld::Map(BTreeMap::from([
+            (NAME_KEY.into(), Ipld::String("crop-images".into())),
+            (RETRIES_KEY.into(), Ipld::Integer(5)),
+            (TIMEOUT_KEY.into(), Ipld::Integer(30_000)),
+            (
+                RESOURCES_KEY.into(),
+                Resources::new(1_000, 4_096, Duration::from_millis(500)).into(),
+            ),
+            (SCHEDULER_MODE_KEY.into(), Ipld::String("ready".into())),
+        ]));
+
+        let metadata = Metadata::try_from(ipld).unwrap();
+        assert_eq!(metadata.name, Some("crop-images".to_string()));
+
+        let settings = Settings::default().merge(&metadata).unwrap();
+        assert_eq!(settings.retries, 5);
+        assert_eq!(settings.timeout, Duration::from_secs(30));
+        assert_eq!(settings.resources.fuel(), Some(1_000));
+        assert_eq!(settings.scheduler_mode, Some(SchedulerMode::Ready));
+        assert_eq!(
+            settings.retry_initial_delay,
+            Settings::default().retry_initial_delay
+        );
+    }
+
+    #[test]
+    fn settings_to_metadata_roundtrip() {
+        let settings = Settings {
+            retries: 2,
+            timeout: Duration::from_secs(90),
+            scheduler_mode: Some(SchedulerMode::Ready),
+            resources: Resources::new(1_000, 4_096, Duration::from_millis(500)),
+            ..Default::default()
+        };
+
+        let ipld = Ipld::from(Metadata::from(&settings));
+        let metadata = Metadata::try_from(ipld).unwrap();
+        assert_eq!(metadata.name, None);
+        assert_eq!(Settings::default().merge(&metadata).unwrap(), settings);
+    }
+
+    #[test]
+    fn reject_invalid_metadata() {
+        let unknown = Ipld::Map(BTreeMap::from([("retry".into(), Ipld::Integer(1))]));
+        assert!(Metadata::try_from(unknown).is_err());
+
+        let negative = Ipld::Map(BTreeMap::from([(TIMEOUT_KEY.into(), Ipld::Integer(-1))]));
+        assert!(Metadata::try_from(negative).is_err());
+
+        let resources = Ipld::Map(BTreeMap::from([(
+            RESOURCES_KEY.into(),
+            Ipld::Map(BTreeMap::from([
+                ("fuel".into(), Ipld::Null),
+                ("memory".into(), Ipld::String("4GiB".into())),
+            ])),
+        )]));
+        assert_eq!(
+            Metadata::try_from(resources).unwrap_err().to_string(),
+            "workflow metadata resources memory must be a non-negative integer"
+        );
+
+        let zero = Metadata {
+            timeout: Some(Duration::ZERO),
+            ..Default::default()
+        };
+        assert!(Settings::default().merge(&zero).is_err());
+
+        let delays = Metadata {
+            retry_initial_delay: Some(Duration::from_secs(10)),
+            retry_max_delay: Some(Duration::from_secs(1)),
+            ..Default::default()
+        };
+        assert!(Settings::default().merge(&delays).is_err());
+    }
+}
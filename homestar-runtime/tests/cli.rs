@@ -198,6 +198,91 @@ fn test_workflow_run_integration() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_workflow_names_integration() -> Result<()> {
+    const DB: &str = "test_workflow_names_integration.db";
+    let _db_guard = FileGuard::new(DB);
+
+    let homestar_proc = Command::new(BIN.as_os_str())
+        .arg("start")
+        .arg("-c")
+        .arg("tests/fixtures/test_workflow3.toml")
+        .arg("--db")
+        .arg(DB)
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let _proc_guard = ChildGuard::new(homestar_proc);
+
+    if wait_for_socket_connection_v6(9861, 100).is_err() {
+        panic!("Homestar server/runtime failed to start in time");
+    }
+
+    // run two different workflows, each under its own local name.
+    let mut runs = vec![];
+    for (name, workflow) in [
+        (
+            "part-one",
+            "tests/fixtures/test-workflow-add-one-part-one.json",
+        ),
+        (
+            "part-two",
+            "tests/fixtures/test-workflow-add-one-part-two.json",
+        ),
+    ] {
+        let output = Command::new(BIN.as_os_str())
+            .arg("run")
+            .arg("-p")
+            .arg("9861")
+            .arg("-n")
+            .arg(name)
+            .arg("-w")
+            .arg(workflow)
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        runs.push((name, String::from_utf8(output)?));
+    }
+
+    Command::new(BIN.as_os_str())
+        .arg("workflow")
+        .arg("list")
+        .arg("-p")
+        .arg("9861")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("created_at"))
+        .stdout(predicate::str::contains("running"));
+
+    // each name resolves to the workflow it was run under.
+    let mut cids = vec![];
+    for (name, run) in runs {
+        let output = Command::new(BIN.as_os_str())
+            .arg("workflow")
+            .arg("status")
+            .arg("-p")
+            .arg("9861")
+            .arg("--json")
+            .arg(name)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\"num_tasks\": 1"))
+            .stdout(predicate::str::contains("\"running\""))
+            .get_output()
+            .stdout
+            .clone();
+        let status: serde_json::Value = serde_json::from_slice(&output)?;
+        let cid = status["cid"].as_str().unwrap().to_string();
+        assert!(run.contains(&cid));
+        cids.push(cid);
+    }
+    assert_ne!(cids[0], cids[1]);
+
+    Ok(())
+}
+
 #[test]
 #[cfg(not(windows))]
 fn test_daemon_integration() -> Result<()> {
@@ -0,0 +1,177 @@
+//! Cache of compiled Wasm [Component]s, keyed by resource [Cid] and sharing a
+//! single [Engine], with an optional on-disk cache of precompiled components.
+
+use crate::wasmtime::{world, Error, World};
+use libipld::Cid;
+use lru::LruCache;
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+use std::{
+    fmt, fs,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+use tracing::{debug, warn};
+use wasmtime::{component::Component, Engine};
+
+/// File extension for precompiled components stored on disk.
+const PRECOMPILED_EXT: &str = "cwasm";
+
+/// Cache of compiled [Component]s shared across tasks and workflows.
+///
+/// Components are compiled once per resource [Cid] with a shared [Engine],
+/// and, if a directory is given, serialized to disk so they can be loaded
+/// without recompiling after a restart.
+///
+/// At most `capacity` components are kept in memory, evicting the least
+/// recently used ones first.
+#[derive(Clone)]
+pub struct ComponentCache {
+    engine: Engine,
+    components: Arc<Mutex<LruCache<Cid, Component>>>,
+    dir: Option<PathBuf>,
+}
+
+impl fmt::Debug for ComponentCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ComponentCache")
+            .field("components", &self.len())
+            .field("dir", &self.dir)
+            .finish()
+    }
+}
+
+impl ComponentCache {
+    /// Create a new [ComponentCache] holding up to `capacity` components in
+    /// memory, optionally backed by a directory of precompiled components.
+    ///
+    /// The directory is created if missing, and restricted to the node's
+    /// own user, as components loaded from it are trusted to be valid.
+    pub fn new(dir: Option<PathBuf>, capacity: NonZeroUsize) -> Result<Self, Error> {
+        if let Some(dir) = &dir {
+            fs::create_dir_all(dir).map_err(|err| Error::WasmRuntime(err.into()))?;
+            #[cfg(unix)]
+            fs::set_permissions(dir, fs::Permissions::from_mode(0o700))
+                .map_err(|err| Error::WasmRuntime(err.into()))?;
+        }
+
+        Ok(Self {
+            engine: Engine::new(&World::configure())?,
+            components: Arc::new(Mutex::new(LruCache::new(capacity))),
+            dir,
+        })
+    }
+
+    /// Shared [Engine] that cached [Component]s are compiled with.
+    pub fn engine(&self) -> &Engine {
+        &self.engine
+    }
+
+    /// Get the compiled [Component] for a resource [Cid], loading it from
+    /// disk or compiling the given bytes if it's not cached yet.
+    pub fn get_or_compile(&self, cid: Cid, bytes: &[u8]) -> Result<Component, Error> {
+        if let Some(component) = self
+            .components
+            .lock()
+            .map_err(|_| Error::Unknown)?
+            .get(&cid)
+        {
+            return Ok(component.clone());
+        }
+
+        let component = match self.load(cid) {
+            Some(component) => component,
+            None => {
+                debug!(
+                    subject = "wasm.cache.compile",
+                    category = "wasm.cache",
+                    cid = cid.to_string(),
+                    "compiling Wasm component"
+                );
+                let component = world::component_from_bytes(bytes, self.engine.clone())?;
+                self.store(cid, &component);
+                component
+            }
+        };
+
+        self.components
+            .lock()
+            .map_err(|_| Error::Unknown)?
+            .put(cid, component.clone());
+
+        Ok(component)
+    }
+
+    /// Number of [Component]s cached in memory.
+    pub fn len(&self) -> usize {
+        self.components.lock().map(|c| c.len()).unwrap_or_default()
+    }
+
+    /// Whether no [Component]s are cached in memory.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn path(dir: &Path, cid: Cid) -> PathBuf {
+        dir.join(format!("{cid}.{PRECOMPILED_EXT}"))
+    }
+
+    // Load a precompiled component from disk, if there's one usable by the
+    // current engine.
+    fn load(&self, cid: Cid) -> Option<Component> {
+        let path = Self::path(self.dir.as_ref()?, cid);
+        if !path.exists() {
+            return None;
+        }
+
+        // SAFETY: deserializing arbitrary bytes as a precompiled component
+        // is unsound, so files in the cache directory must only ever be
+        // written by [ComponentCache::store], from components compiled by
+        // wasmtime. The directory is made private to the node's user in
+        // [ComponentCache::new] so no one else can place files there, and
+        // files compiled by an incompatible engine or wasmtime version are
+        // rejected by wasmtime's header checks.
+        match unsafe { Component::deserialize_file(&self.engine, &path) } {
+            Ok(component) => {
+                debug!(
+                    subject = "wasm.cache.load",
+                    category = "wasm.cache",
+                    cid = cid.to_string(),
+                    "loaded precompiled Wasm component from disk"
+                );
+                Some(component)
+            }
+            Err(err) => {
+                warn!(
+                    subject = "wasm.cache.load.err",
+                    category = "wasm.cache",
+                    cid = cid.to_string(),
+                    err=?err,
+                    "unable to load precompiled Wasm component, recompiling"
+                );
+                None
+            }
+        }
+    }
+
+    // Store a precompiled component on disk, if a directory is configured.
+    fn store(&self, cid: Cid, component: &Component) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+
+        if let Err(err) = component
+            .serialize()
+            .and_then(|bytes| Ok(fs::write(Self::path(dir, cid), bytes)?))
+        {
+            warn!(
+                subject = "wasm.cache.store.err",
+                category = "wasm.cache",
+                cid = cid.to_string(),
+                err=?err,
+                "unable to write precompiled Wasm component to disk"
+            );
+        }
+    }
+}
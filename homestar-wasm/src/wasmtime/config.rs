@@ -7,7 +7,10 @@ impl From<Resources> for wasmtime::State {
     fn from(resources: Resources) -> wasmtime::State {
         wasmtime::State::new(
             resources.fuel().unwrap_or(u64::MAX),
-            StoreLimitsAsync::new(Some(consts::WASM_MAX_MEMORY as usize), None),
+            StoreLimitsAsync::new(
+                Some(resources.memory().unwrap_or(consts::WASM_MAX_MEMORY) as usize),
+                None,
+            ),
         )
     }
 }
@@ -15,6 +15,13 @@ pub enum Error {
     /// Failure to convert from Wasm binary into Wasm component.
     #[error("cannot convert from binary structure to Wasm component")]
     IntoWasmComponent(#[source] anyhow::Error),
+    /// Failure due to a Wasm component attempting to grow its memory past
+    /// the configured limit (in bytes).
+    #[error("Wasm execution exceeded memory limit of {0} bytes")]
+    MemoryLimitExceeded(u64),
+    /// Failure due to a Wasm component exhausting its fuel quota.
+    #[error("Wasm execution ran out of fuel")]
+    OutOfFuel,
     /// Bubble-up [ResolveError]s for [Cid]s still awaiting resolution.
     ///
     /// [ResolveError]: homestar_core::workflow::error::ResolveError
@@ -16,6 +16,7 @@ pub struct StoreLimitsAsync {
     max_memory_size: Option<usize>,
     max_table_elements: Option<u32>,
     memory_consumed: u64,
+    memory_limit_reached: bool,
 }
 
 impl Default for StoreLimitsAsync {
@@ -24,6 +25,7 @@ impl Default for StoreLimitsAsync {
             max_memory_size: Some(consts::WASM_MAX_MEMORY as usize),
             max_table_elements: None,
             memory_consumed: 0,
+            memory_limit_reached: false,
         }
     }
 }
@@ -44,6 +46,8 @@ impl ResourceLimiterAsync for StoreLimitsAsync {
         if can_grow {
             self.memory_consumed =
                 (self.memory_consumed as i64 + (desired as i64 - current as i64)) as u64;
+        } else {
+            self.memory_limit_reached = true;
         }
         Ok(can_grow)
     }
@@ -70,6 +74,7 @@ impl StoreLimitsAsync {
             max_memory_size,
             max_table_elements,
             memory_consumed: 0,
+            memory_limit_reached: false,
         }
     }
 
@@ -77,4 +82,15 @@ impl StoreLimitsAsync {
     pub fn memory_consumed(&self) -> u64 {
         self.memory_consumed
     }
+
+    /// Maximum memory size in bytes, if any.
+    pub fn max_memory_size(&self) -> Option<usize> {
+        self.max_memory_size
+    }
+
+    /// Whether a request to grow memory was denied for exceeding the
+    /// maximum memory size.
+    pub fn memory_limit_reached(&self) -> bool {
+        self.memory_limit_reached
+    }
 }
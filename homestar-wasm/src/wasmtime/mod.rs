@@ -4,6 +4,7 @@
 //! [Wasmtime]: <https://wasmtime.dev/>
 //! [Ipld]: libipld::Ipld
 
+pub mod cache;
 pub mod config;
 mod error;
 mod host;
@@ -11,5 +12,6 @@ pub mod ipld;
 pub mod limits;
 pub mod world;
 
+pub use cache::ComponentCache;
 pub use error::*;
 pub use world::{State, World};
@@ -21,7 +21,7 @@ use homestar_core::{
 use std::{iter, time::Instant};
 use wasmtime::{
     component::{self, Component, Func, Instance, Linker},
-    Config, Engine, Store,
+    Config, Engine, Store, Trap,
 };
 use wit_component::ComponentEncoder;
 
@@ -111,10 +111,20 @@ impl State {
         self.fuel = fuel
     }
 
+    /// Fuel the store is given for execution.
+    pub fn fuel(&self) -> u64 {
+        self.fuel
+    }
+
     /// Initial time from instantiation.
     pub fn start_time(&self) -> Instant {
         self.start_time
     }
+
+    /// Get the [StoreLimitsAsync] applied to the store.
+    pub fn limits(&self) -> &StoreLimitsAsync {
+        &self.limits
+    }
 }
 
 /// Runtime struct wrapping wasm/host bindings, the
@@ -204,7 +214,11 @@ impl<T> Env<T> {
             .ok_or(Error::WasmInstantiation)?
             .func()
             .call_async(&mut self.store, &params, &mut results_alloc)
-            .await?;
+            .await
+            .map_err(|err| match err.downcast_ref::<Trap>() {
+                Some(Trap::OutOfFuel) => Error::OutOfFuel,
+                _ => Error::WasmRuntime(err),
+            })?;
 
         self.bindings
             .as_mut()
@@ -257,6 +271,18 @@ impl World {
     pub fn default(data: State) -> Result<Env<State>, Error> {
         let config = Self::configure();
         let engine = Engine::new(&config)?;
+        Self::with_engine(engine, data)
+    }
+
+    /// Instantiate an [environment] for a [World] on a given, possibly
+    /// shared, [Engine], given [State].
+    ///
+    /// The [Engine] must be configured as for [World::default], e.g. the
+    /// one of a [ComponentCache].
+    ///
+    /// [environment]: Env
+    /// [ComponentCache]: crate::wasmtime::ComponentCache
+    pub fn with_engine(engine: Engine, data: State) -> Result<Env<State>, Error> {
         let mut linker = Self::define_linker(&engine);
 
         // Add WASI to the linker in order to support WASI modules.
@@ -266,6 +292,7 @@ impl World {
         Imports::add_to_linker(&mut linker, |state: &mut State| state)?;
 
         let mut store = Store::new(&engine, data);
+        store.limiter_async(|s| &mut s.limits);
         store.set_fuel(store.data().fuel)?;
 
         // Configures a `Store` to yield execution of async WebAssembly code
@@ -336,9 +363,28 @@ impl World {
     {
         // engine clones are shallow (not deep).
         let component = component_from_bytes(&bytes, env.engine.clone())?;
+        Self::instantiate_component_with_current_env(&component, fun_name, env).await
+    }
 
+    /// Instantiates an already compiled [Component] using the current
+    /// [environment]'s engine, linker, and store, producing a new set of
+    /// bindings for execution, and overriding the instance for the Wasm
+    /// component.
+    ///
+    /// The [Component] must have been compiled with the [environment]'s
+    /// engine.
+    ///
+    /// [environment]: Env
+    pub async fn instantiate_component_with_current_env<'a, T>(
+        component: &Component,
+        fun_name: &'a str,
+        env: &'a mut Env<T>,
+    ) -> Result<&'a mut Env<T>, Error>
+    where
+        T: Send,
+    {
         let (_bindings, instance) =
-            Imports::instantiate_async(&mut env.store, &component, &env.linker).await?;
+            Imports::instantiate_async(&mut env.store, component, &env.linker).await?;
 
         let bindings = Self::new(&mut env.store, &instance, fun_name)?;
         env.set_instance(instance);
@@ -350,7 +396,7 @@ impl World {
         self.0
     }
 
-    fn configure() -> Config {
+    pub(crate) fn configure() -> Config {
         let mut config = Config::new();
         config.strategy(wasmtime::Strategy::Cranelift);
         config.wasm_component_model(true);
@@ -399,7 +445,7 @@ impl World {
 }
 
 /// Turn bytes into a Wasm [Component] module.
-fn component_from_bytes(bytes: &[u8], engine: Engine) -> Result<Component, Error> {
+pub(crate) fn component_from_bytes(bytes: &[u8], engine: Engine) -> Result<Component, Error> {
     fn is_component(chunk: wasmparser::Chunk<'_>) -> bool {
         matches!(
             chunk,
@@ -5,7 +5,7 @@ use homestar_core::workflow::{
 };
 use homestar_wasm::{
     io::{Arg, Output},
-    wasmtime::{limits::StoreLimitsAsync, Error, State, World},
+    wasmtime::{limits::StoreLimitsAsync, ComponentCache, Error, State, World},
 };
 use libipld::{
     cid::{
@@ -14,7 +14,7 @@ use libipld::{
     },
     Ipld, Link,
 };
-use std::{collections::BTreeMap, fs, path::PathBuf};
+use std::{collections::BTreeMap, fs, num::NonZeroUsize, path::PathBuf};
 
 fn fixtures(file: &str) -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("fixtures/{file}"))
@@ -37,6 +37,21 @@ async fn test_wasm_exceeds_max_memory() {
     }
 }
 
+#[tokio::test]
+async fn test_wasm_out_of_fuel() {
+    let ipld = Input::Ipld(Ipld::Map(BTreeMap::from([
+        ("func".into(), Ipld::String("add_two".to_string())),
+        ("args".into(), Ipld::List(vec![Ipld::Integer(1)])),
+    ])));
+    let wat = fs::read(fixtures("example_add_component.wat")).unwrap();
+    let mut env = World::instantiate(wat, "add_two", State::new(1, StoreLimitsAsync::default()))
+        .await
+        .unwrap();
+    let res = env.execute(ipld.parse().unwrap().try_into().unwrap()).await;
+
+    assert!(matches!(res, Err(Error::OutOfFuel)));
+}
+
 #[tokio::test]
 async fn test_execute_wat() {
     let ipld = Input::Ipld(Ipld::Map(BTreeMap::from([
@@ -424,3 +439,82 @@ async fn test_execute_wasms_with_multiple_inits() {
         Output::Value(wasmtime::component::Val::String("RoundRoundabout".into()))
     );
 }
+
+#[tokio::test]
+async fn test_execute_wat_from_component_cache() {
+    let ipld = Input::Ipld(Ipld::Map(BTreeMap::from([
+        ("func".into(), Ipld::String("add_two".to_string())),
+        ("args".into(), Ipld::List(vec![Ipld::Integer(1)])),
+    ])));
+    let wat = fs::read(fixtures("example_add_component.wat")).unwrap();
+    let cid = Cid::new_v1(0x55, Code::Sha3_256.digest(&wat));
+    let cache = ComponentCache::new(None, NonZeroUsize::new(8).unwrap()).unwrap();
+
+    for _ in 0..2 {
+        let component = cache.get_or_compile(cid, &wat).unwrap();
+        let mut env = World::with_engine(cache.engine().clone(), State::default()).unwrap();
+        let env = World::instantiate_component_with_current_env(&component, "add_two", &mut env)
+            .await
+            .unwrap();
+        let res = env
+            .execute(ipld.clone().parse().unwrap().into())
+            .await
+            .unwrap();
+        assert_eq!(res, Output::Value(wasmtime::component::Val::S32(3)));
+    }
+
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn test_component_cache_evicts_least_recently_used() {
+    let wat = fs::read(fixtures("example_add_component.wat")).unwrap();
+    let cid1 = Cid::new_v1(0x55, Code::Sha3_256.digest(&wat));
+    let cid2 = Cid::new_v1(0x70, Code::Sha3_256.digest(&wat));
+    let cid3 = Cid::new_v1(0x71, Code::Sha3_256.digest(&wat));
+    let cache = ComponentCache::new(None, NonZeroUsize::new(2).unwrap()).unwrap();
+
+    cache.get_or_compile(cid1, &wat).unwrap();
+    cache.get_or_compile(cid2, &wat).unwrap();
+    // Touch the first component, so the second is the least recently used.
+    cache.get_or_compile(cid1, &[]).unwrap();
+    cache.get_or_compile(cid3, &wat).unwrap();
+    assert_eq!(cache.len(), 2);
+
+    // Still cached, so no bytes are needed.
+    assert!(cache.get_or_compile(cid1, &[]).is_ok());
+    assert!(cache.get_or_compile(cid3, &[]).is_ok());
+    // Evicted, so it must be compiled again.
+    assert!(cache.get_or_compile(cid2, &[]).is_err());
+}
+
+#[tokio::test]
+async fn test_component_cache_on_disk() {
+    let wat = fs::read(fixtures("example_add_component.wat")).unwrap();
+    let cid = Cid::new_v1(0x55, Code::Sha3_256.digest(&wat));
+    let dir = std::env::temp_dir().join(format!("homestar-wasm-cache-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+
+    let cache = ComponentCache::new(Some(dir.clone()), NonZeroUsize::new(8).unwrap()).unwrap();
+    cache.get_or_compile(cid, &wat).unwrap();
+    assert!(dir.join(format!("{cid}.cwasm")).exists());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+    }
+
+    // A fresh cache loads the precompiled component from disk, without
+    // needing the original bytes.
+    let cache = ComponentCache::new(Some(dir.clone()), NonZeroUsize::new(8).unwrap()).unwrap();
+    let component = cache.get_or_compile(cid, &[]).unwrap();
+    let mut env = World::with_engine(cache.engine().clone(), State::default()).unwrap();
+    assert!(
+        World::instantiate_component_with_current_env(&component, "add_two", &mut env)
+            .await
+            .is_ok()
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}